@@ -9,6 +9,28 @@ impl Display for FnUpdateTemp {
             Var(name) => write!(f, "{}", name)?,
             Not(inner) => write!(f, "!{}", inner)?,
             Binary(op, l, r) => write!(f, "({} {} {})", l, op, r)?,
+            Nary(op, args) => {
+                let args = args.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+                write!(f, "({})", args.join(format!(" {} ", op).as_str()))?
+            }
+            Threshold(args, threshold) => {
+                write!(f, "[")?;
+                for (i, (weight, arg)) in args.iter().enumerate() {
+                    if i == 0 && *weight < 0 {
+                        write!(f, "-")?;
+                    } else if i > 0 {
+                        write!(f, "{}", if *weight < 0 { " - " } else { " + " })?;
+                    }
+                    if weight.unsigned_abs() != 1 {
+                        write!(f, "{} * ", weight.unsigned_abs())?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                if args.is_empty() {
+                    write!(f, "0")?;
+                }
+                write!(f, " >= {}]", threshold)?
+            }
             Param(name, args) => {
                 write!(f, "{}", name)?;
                 if !args.is_empty() {
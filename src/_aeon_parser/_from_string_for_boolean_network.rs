@@ -20,11 +20,22 @@ impl TryFrom<&str> for BooleanNetwork {
 
         let mut update_functions = Vec::new();
         let mut regulations = Vec::new();
+        let mut function_classes = Vec::new();
+        let mut function_values = Vec::new();
 
         // Regex that matches lines which define an update function.
         let function_re =
             Regex::new(r"^\$\s*(?P<name>[a-zA-Z0-9_]+)\s*:\s*(?P<function>.+)$").unwrap();
 
+        // Regex that matches lines which define function classes.
+        let class_re = Regex::new(r"^@\s*(?P<name>[a-zA-Z0-9_]+)\s*:\s*(?P<classes>.+)$").unwrap();
+
+        // Regex that matches lines which define known function values, e.g. `@f(1, 0) = 1`.
+        let value_re = Regex::new(
+            r"^@\s*(?P<name>[a-zA-Z0-9_]+)\s*\((?P<args>[^)]*)\)\s*=\s*(?P<value>[a-zA-Z0-9_]+)$",
+        )
+        .unwrap();
+
         // Split lines between update functions and regulations
         for line in lines {
             if let Some(captures) = function_re.captures(line.trim()) {
@@ -32,6 +43,17 @@ impl TryFrom<&str> for BooleanNetwork {
                     captures["name"].to_string(),
                     FnUpdateTemp::try_from(&captures["function"])?,
                 ));
+            } else if let Some(captures) = class_re.captures(line.trim()) {
+                function_classes.push((
+                    captures["name"].to_string(),
+                    captures["classes"].to_string(),
+                ));
+            } else if let Some(captures) = value_re.captures(line.trim()) {
+                function_values.push((
+                    captures["name"].to_string(),
+                    captures["args"].to_string(),
+                    captures["value"].to_string(),
+                ));
             } else {
                 regulations.push(RegulationTemp::try_from(line)?);
             }
@@ -84,6 +106,14 @@ impl TryFrom<&str> for BooleanNetwork {
             bn.add_template_update_function(&name, function)?;
         }
 
+        // Function classes can only be assigned once all functions are known.
+        for (name, classes) in function_classes {
+            bn.add_string_function_classes(&name, &classes)?;
+        }
+        for (name, args, value) in function_values {
+            bn.add_string_function_value(&name, &args, &value)?;
+        }
+
         Ok(bn)
     }
 }
@@ -93,6 +123,7 @@ mod tests {
     use crate::biodivine_std::structs::build_index_map;
     use crate::BinaryOp::{And, Iff, Imp, Or, Xor};
     use crate::{BooleanNetwork, FnUpdate, Parameter, ParameterId, RegulatoryGraph, VariableId};
+    use std::collections::HashMap;
     use std::convert::TryFrom;
 
     #[test]
@@ -192,6 +223,10 @@ mod tests {
             ),
             parameters,
             update_functions: vec![Some(f1), Some(f2), Some(f3), None],
+            parameter_classes: HashMap::new(),
+            implicit_classes: HashMap::new(),
+            parameter_values: HashMap::new(),
+            implicit_values: HashMap::new(),
         };
 
         assert_eq!(bn, BooleanNetwork::try_from(bn_string).unwrap());
@@ -214,4 +249,33 @@ $c: q(b, b) => !(b ^ k)
             BooleanNetwork::try_from(bn_string).unwrap().to_string()
         );
     }
+
+    #[test]
+    fn test_bn_string_round_trip_with_chains() {
+        let bn_string = "a -> a
+b -> a
+c -> a
+a -> b
+b -> b
+c -> b
+a -> c
+b -> c
+c -> c
+$a: a & b & c
+$b: (a & b) & c
+$c: a | (b | c) | (!a & b & c)
+";
+        let bn = BooleanNetwork::try_from(bn_string).unwrap();
+        assert_eq!(bn_string, bn.to_string());
+        assert_eq!(
+            bn,
+            BooleanNetwork::try_from(bn.to_string().as_str()).unwrap()
+        );
+
+        let a = bn.get_update_function(VariableId(0)).as_ref().unwrap();
+        let b = bn.get_update_function(VariableId(1)).as_ref().unwrap();
+        assert!(a.as_nary().is_some());
+        assert!(b.as_binary().is_some());
+        assert_eq!(a, &b.flatten());
+    }
 }
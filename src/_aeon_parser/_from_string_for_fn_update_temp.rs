@@ -1,6 +1,7 @@
-use crate::BinaryOp::*;
 use crate::_aeon_parser::FnUpdateTemp;
 use crate::_aeon_parser::FnUpdateTemp::*;
+use crate::BinaryOp::*;
+use crate::NaryOp;
 use std::convert::TryFrom;
 use std::iter::Peekable;
 use std::str::Chars;
@@ -9,7 +10,7 @@ impl TryFrom<&str> for FnUpdateTemp {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let tokens = tokenize_function_group(&mut value.chars().peekable(), true)?;
+        let tokens = tokenize_function_group(&mut value.chars().peekable(), None)?;
         Ok(*(parse_update_function(&tokens)?))
     }
 }
@@ -18,24 +19,29 @@ impl TryFrom<&str> for FnUpdateTemp {
 /// a `FnUpdate`.
 #[derive(Debug, Eq, PartialEq)]
 enum Token {
-    Not,                // '!'
-    And,                // '&'
-    Or,                 // '|'
-    Xor,                // '^'
-    Imp,                // '=>'
-    Iff,                // '<=>'
-    Comma,              // ','
-    Name(String),       // 'name'
-    Tokens(Vec<Token>), // A block of tokens inside parentheses
+    Not,                   // '!'
+    And,                   // '&'
+    Or,                    // '|'
+    Xor,                   // '^'
+    Imp,                   // '=>'
+    Iff,                   // '<=>'
+    Comma,                 // ','
+    Plus,                  // '+'
+    Minus,                 // '-'
+    Times,                 // '*'
+    Geq,                   // '>='
+    Name(String),          // 'name'
+    Tokens(Vec<Token>),    // A block of tokens inside parentheses
+    Threshold(Vec<Token>), // A block of tokens inside square brackets
 }
 
 /// **(internal)** Process a peekable iterator of characters into a vector of `Token`s.
 ///
-/// The outer method always consumes the opening parenthesis and the recursive call consumes the
-/// closing parenthesis. Use `top_level` to indicate that there will be no closing parenthesis.
+/// The outer method always consumes the opening parenthesis (or bracket) and the recursive call
+/// consumes the `closing` one. Use `None` to indicate that there will be no closing parenthesis.
 fn tokenize_function_group(
     data: &mut Peekable<Chars>,
-    top_level: bool,
+    closing: Option<char>,
 ) -> Result<Vec<Token>, String> {
     let mut output = Vec::new();
     while let Some(c) = data.next() {
@@ -47,6 +53,9 @@ fn tokenize_function_group(
             '&' => output.push(Token::And),
             '|' => output.push(Token::Or),
             '^' => output.push(Token::Xor),
+            '+' => output.push(Token::Plus),
+            '-' => output.push(Token::Minus),
+            '*' => output.push(Token::Times),
             '=' => {
                 if Some('>') == data.next() {
                     output.push(Token::Imp);
@@ -65,20 +74,30 @@ fn tokenize_function_group(
                     return Err("Expected '=' after '<'.".to_string());
                 }
             }
-            // '>' is invalid as a start of a token
-            '>' => return Err("Unexpected '>'.".to_string()),
-            ')' => {
-                return if !top_level {
+            '>' => {
+                if Some('=') == data.next() {
+                    output.push(Token::Geq);
+                } else {
+                    return Err("Expected '=' after '>'.".to_string());
+                }
+            }
+            ')' | ']' => {
+                return if closing == Some(c) {
                     Ok(output)
                 } else {
-                    Err("Unexpected ')'.".to_string())
+                    Err(format!("Unexpected '{}'.", c))
                 }
             }
             '(' => {
                 // start a nested token group
-                let tokens = tokenize_function_group(data, false)?;
+                let tokens = tokenize_function_group(data, Some(')'))?;
                 output.push(Token::Tokens(tokens));
             }
+            '[' => {
+                // start a nested threshold group
+                let tokens = tokenize_function_group(data, Some(']'))?;
+                output.push(Token::Threshold(tokens));
+            }
             c if is_valid_in_name(c) => {
                 // start of a variable name
                 let mut name = vec![c];
@@ -95,10 +114,10 @@ fn tokenize_function_group(
             _ => return Err(format!("Unexpected '{}'.", c)),
         }
     }
-    if top_level {
-        Ok(output)
+    if let Some(closing) = closing {
+        Err(format!("Expected '{}'.", closing))
     } else {
-        Err("Expected ')'.".to_string())
+        Ok(output)
     }
 }
 
@@ -137,24 +156,37 @@ fn imp(data: &[Token]) -> Result<Box<FnUpdateTemp>, String> {
     })
 }
 
+/// **(internal)** Split `data` into operands of an associative `op` operator and combine
+/// them using `operand`. Chains of three or more operands produce a flat n-ary operator.
+fn associative(
+    data: &[Token],
+    token: Token,
+    op: NaryOp,
+    operand: fn(&[Token]) -> Result<Box<FnUpdateTemp>, String>,
+) -> Result<Box<FnUpdateTemp>, String> {
+    let mut args = Vec::new();
+    for arg in data.split(|it| *it == token) {
+        args.push(*operand(arg)?);
+    }
+    Ok(Box::new(match args.len() {
+        1 => args.remove(0),
+        2 => {
+            let right = args.pop().unwrap();
+            let left = args.pop().unwrap();
+            Binary(op.to_binary(), Box::new(left), Box::new(right))
+        }
+        _ => Nary(op, args),
+    }))
+}
+
 /// **(internal)** Recursive parsing step 3: extract `|` operators.
 fn or(data: &[Token]) -> Result<Box<FnUpdateTemp>, String> {
-    let or_token = index_of_first(data, Token::Or);
-    Ok(if let Some(i) = or_token {
-        Box::new(Binary(Or, and(&data[..i])?, or(&data[(i + 1)..])?))
-    } else {
-        and(data)?
-    })
+    associative(data, Token::Or, NaryOp::Or, and)
 }
 
 /// **(internal)** Recursive parsing step 4: extract `&` operators.
 fn and(data: &[Token]) -> Result<Box<FnUpdateTemp>, String> {
-    let and_token = index_of_first(data, Token::And);
-    Ok(if let Some(i) = and_token {
-        Box::new(Binary(And, xor(&data[..i])?, and(&data[(i + 1)..])?))
-    } else {
-        xor(data)?
-    })
+    associative(data, Token::And, NaryOp::And, xor)
 }
 
 /// **(internal)** Recursive parsing step 5: extract `^` operators.
@@ -187,6 +219,7 @@ fn terminal(data: &[Token]) -> Result<Box<FnUpdateTemp>, String> {
                     }
                 }
                 Token::Tokens(inner) => return parse_update_function(inner),
+                Token::Threshold(inner) => return threshold(inner),
                 _ => {} // otherwise, fall through to the error at the end.
             }
         } else if data.len() == 2 {
@@ -203,6 +236,63 @@ fn terminal(data: &[Token]) -> Result<Box<FnUpdateTemp>, String> {
     }
 }
 
+/// **(internal)** Parse the contents of a threshold operator `[w_1 * x_1 + ... >= k]`.
+///
+/// Each summand is an optionally negated (`-`) and optionally weighted (`w *`) formula.
+/// An empty sum is written as `0`.
+fn threshold(data: &[Token]) -> Result<Box<FnUpdateTemp>, String> {
+    let Some(geq) = index_of_first(data, Token::Geq) else {
+        return Err("Expected '>=' in threshold operator.".to_string());
+    };
+    let threshold = match &data[(geq + 1)..] {
+        [Token::Name(value)] => read_integer(value, 1)?,
+        [Token::Minus, Token::Name(value)] => read_integer(value, -1)?,
+        _ => return Err("Expected integer threshold after '>='.".to_string()),
+    };
+    let mut args = Vec::new();
+    let mut sum = &data[..geq];
+    if sum.len() == 1 && sum[0] == Token::Name("0".to_string()) {
+        // An empty sum is written as zero. This does not clash with variable names, because
+        // `0` is always parsed as the constant `false` (see `terminal`), which would not
+        // contribute to the sum anyway.
+        return Ok(Box::new(Threshold(args, threshold)));
+    }
+    while !sum.is_empty() {
+        let mut sign = 1i64;
+        if sum[0] == Token::Minus {
+            sign = -1;
+            sum = &sum[1..];
+        } else if sum[0] == Token::Plus && !args.is_empty() {
+            sum = &sum[1..];
+        } else if !args.is_empty() {
+            return Err(format!("Expected '+' or '-', found {:?}.", sum[0]));
+        }
+        let end = sum
+            .iter()
+            .position(|it| *it == Token::Plus || *it == Token::Minus)
+            .unwrap_or(sum.len());
+        let mut summand = &sum[..end];
+        sum = &sum[end..];
+        let mut weight = if sign < 0 { -1 } else { 1 };
+        if let [Token::Name(value), Token::Times, ..] = summand {
+            weight = read_integer(value, sign)?;
+            summand = &summand[2..];
+        }
+        args.push((weight, *parse_update_function(summand)?));
+    }
+    Ok(Box::new(Threshold(args, threshold)))
+}
+
+/// **(internal)** Parse a non-negative integer constant used in a threshold operator and
+/// multiply it by `sign` (the result must fit into `i32`, which includes `i32::MIN`).
+fn read_integer(value: &str, sign: i64) -> Result<i32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .and_then(|it| i32::try_from(sign * i64::from(it)).ok())
+        .ok_or_else(|| format!("Expected integer, found `{}`.", value))
+}
+
 /// **(internal)** Parse a list of function arguments. All arguments must be expressions separated
 /// by commas.
 ///
@@ -225,8 +315,8 @@ fn read_args(data: &[Token]) -> Result<Vec<FnUpdateTemp>, String> {
 
 #[cfg(test)]
 mod tests {
-    use crate::BinaryOp;
     use crate::_aeon_parser::FnUpdateTemp;
+    use crate::BinaryOp;
     use std::convert::TryFrom;
 
     #[test]
@@ -243,6 +333,12 @@ mod tests {
             "(a <=> !(f(a, b) => (c ^ d)))",
             "f(a, f(b), c)",
             "f((a & c))",
+            "[a + 2 * !b - (c & d) >= 2]",
+            "[-a - 3 * b >= -1]",
+            "[0 >= 0]",
+            "[-2147483648 * a + 2147483647 * b >= -2147483648]",
+            "(a & b & !c)",
+            "((a | b | c) & (a ^ b))",
         ];
         for str in inputs {
             assert_eq!(str, format!("{}", FnUpdateTemp::try_from(str).unwrap()))
@@ -6,12 +6,13 @@ impl TryFrom<&str> for RegulationTemp {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if let Some((r, m, o, t)) = Regulation::try_from_string(value) {
+        if let Some((r, u, m, o, t)) = Regulation::try_from_uncertain_string(value) {
             Ok(RegulationTemp {
                 regulator: r,
                 target: t,
                 observable: o,
                 monotonicity: m,
+                uncertain: u,
             })
         } else {
             Err(format!("String \"{}\" is not a valid regulation.", value))
@@ -32,7 +33,8 @@ mod tests {
                 regulator: "abc".to_string(),
                 target: "123".to_string(),
                 observable: true,
-                monotonicity: Some(Activation)
+                monotonicity: Some(Activation),
+                uncertain: false,
             },
             RegulationTemp::try_from("  abc -> 123 ").unwrap()
         );
@@ -42,7 +44,8 @@ mod tests {
                 regulator: "abc".to_string(),
                 target: "123".to_string(),
                 observable: false,
-                monotonicity: Some(Activation)
+                monotonicity: Some(Activation),
+                uncertain: false,
             },
             RegulationTemp::try_from("  abc ->? 123 ").unwrap()
         );
@@ -52,7 +55,8 @@ mod tests {
                 regulator: "hello_world".to_string(),
                 target: "world_hello_123".to_string(),
                 observable: true,
-                monotonicity: Some(Inhibition)
+                monotonicity: Some(Inhibition),
+                uncertain: false,
             },
             RegulationTemp::try_from("hello_world -| world_hello_123").unwrap()
         );
@@ -62,7 +66,8 @@ mod tests {
                 regulator: "hello_world".to_string(),
                 target: "world_hello_123".to_string(),
                 observable: false,
-                monotonicity: Some(Inhibition)
+                monotonicity: Some(Inhibition),
+                uncertain: false,
             },
             RegulationTemp::try_from("hello_world -|? world_hello_123").unwrap()
         );
@@ -72,7 +77,8 @@ mod tests {
                 regulator: "abc".to_string(),
                 target: "abc".to_string(),
                 observable: true,
-                monotonicity: None
+                monotonicity: None,
+                uncertain: false,
             },
             RegulationTemp::try_from("abc -? abc").unwrap()
         );
@@ -82,10 +88,22 @@ mod tests {
                 regulator: "abc".to_string(),
                 target: "abc".to_string(),
                 observable: false,
-                monotonicity: None
+                monotonicity: None,
+                uncertain: false,
             },
             RegulationTemp::try_from("abc -?? abc").unwrap()
         );
+
+        assert_eq!(
+            RegulationTemp {
+                regulator: "abc".to_string(),
+                target: "123".to_string(),
+                observable: false,
+                monotonicity: Some(Inhibition),
+                uncertain: true,
+            },
+            RegulationTemp::try_from("abc ?-|? 123").unwrap()
+        );
     }
 
     #[test]
@@ -96,6 +114,7 @@ mod tests {
         assert!(RegulationTemp::try_from(" -? foo").is_err());
         assert!(RegulationTemp::try_from("hello -?> there").is_err());
         assert!(RegulationTemp::try_from("world -??? is").is_err());
+        assert!(RegulationTemp::try_from("world ??-> is").is_err());
         assert!(RegulationTemp::try_from("   te - ? st").is_err());
     }
 }
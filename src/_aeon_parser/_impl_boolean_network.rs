@@ -1,5 +1,5 @@
 use crate::_aeon_parser::FnUpdateTemp;
-use crate::{BooleanNetwork, VariableId};
+use crate::{BooleanNetwork, FunctionClass, VariableId};
 use std::convert::TryFrom;
 
 /// Methods for parsing `BooleanNetwork`s from string representation.
@@ -29,6 +29,62 @@ impl BooleanNetwork {
         Ok(())
     }
 
+    /// Add function classes given as a comma-separated list (e.g. `unate, threshold`) to
+    /// the uninterpreted function `name`. Here, `name` is either an explicit parameter,
+    /// or a variable with an implicit update function.
+    pub fn add_string_function_classes(&mut self, name: &str, classes: &str) -> Result<(), String> {
+        let classes = classes
+            .split(',')
+            .map(FunctionClass::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(parameter) = self.find_parameter(name) {
+            for class in classes {
+                self.add_parameter_class(parameter, class);
+            }
+        } else if let Some(variable) = self.graph.find_variable(name) {
+            for class in classes {
+                self.add_implicit_class(variable, class)?;
+            }
+        } else {
+            return Err(format!(
+                "Can't add function class for `{}`. `{}` is not a parameter or a variable.",
+                name, name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Add a known value of the uninterpreted function `name` for the given arguments.
+    /// Here, `name` is either an explicit parameter, or a variable with an implicit update
+    /// function, `args` is a comma-separated list of Boolean constants (e.g. `1, 0`),
+    /// and `value` is a single Boolean constant. Constants are written as `0`/`1`,
+    /// or `false`/`true`.
+    pub fn add_string_function_value(
+        &mut self,
+        name: &str,
+        args: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        let args = if args.trim().is_empty() {
+            Vec::new()
+        } else {
+            args.split(',')
+                .map(parse_constant)
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let value = parse_constant(value)?;
+        if let Some(parameter) = self.find_parameter(name) {
+            self.add_parameter_value(parameter, &args, value)
+        } else if let Some(variable) = self.graph.find_variable(name) {
+            self.add_implicit_value(variable, &args, value)
+        } else {
+            Err(format!(
+                "Can't add function value for `{}`. `{}` is not a parameter or a variable.",
+                name, name
+            ))
+        }
+    }
+
     /// **(internal)** Utility method to safely obtain variable for the update function
     /// with appropriate error message.
     fn get_variable_for_name(&self, name: &str) -> Result<VariableId, String> {
@@ -39,6 +95,15 @@ impl BooleanNetwork {
     }
 }
 
+/// **(internal)** Parse a Boolean constant used in a known function value.
+fn parse_constant(value: &str) -> Result<bool, String> {
+    match value.trim() {
+        "1" | "true" => Ok(true),
+        "0" | "false" => Ok(false),
+        other => Err(format!("Invalid Boolean constant `{}`.", other)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{BooleanNetwork, RegulatoryGraph};
@@ -15,6 +15,18 @@ impl FnUpdateTemp {
                 l.unknown_variables_to_parameters(rg),
                 r.unknown_variables_to_parameters(rg),
             ),
+            Nary(op, args) => Nary(
+                op,
+                args.into_iter()
+                    .map(|it| *it.unknown_variables_to_parameters(rg))
+                    .collect(),
+            ),
+            Threshold(args, threshold) => Threshold(
+                args.into_iter()
+                    .map(|(w, it)| (w, *it.unknown_variables_to_parameters(rg)))
+                    .collect(),
+                threshold,
+            ),
             Not(inner) => Not(inner.unknown_variables_to_parameters(rg)),
             Param(name, args) => {
                 let args: Vec<FnUpdateTemp> = args
@@ -44,6 +56,16 @@ impl FnUpdateTemp {
                 l.dump_parameters(result);
                 r.dump_parameters(result)
             }
+            Nary(_, args) => {
+                for arg in args {
+                    arg.dump_parameters(result);
+                }
+            }
+            Threshold(args, _) => {
+                for (_, arg) in args {
+                    arg.dump_parameters(result);
+                }
+            }
             Not(inner) => inner.dump_parameters(result),
             Var(_) => {}
             Const(_) => {}
@@ -65,6 +87,16 @@ impl FnUpdateTemp {
                 l.dump_variables(result);
                 r.dump_variables(result)
             }
+            Nary(_, args) => {
+                for arg in args {
+                    arg.dump_variables(result);
+                }
+            }
+            Threshold(args, _) => {
+                for (_, arg) in args {
+                    arg.dump_variables(result);
+                }
+            }
             Not(inner) => inner.dump_variables(result),
             Var(name) => {
                 result.insert(name.clone());
@@ -109,6 +141,20 @@ impl FnUpdateTemp {
             }
             Not(inner) => FnUpdate::Not(inner.into_fn_update(bn)?),
             Binary(op, l, r) => FnUpdate::Binary(op, l.into_fn_update(bn)?, r.into_fn_update(bn)?),
+            Nary(op, args) => {
+                let mut arguments = Vec::with_capacity(args.len());
+                for arg in args {
+                    arguments.push(*arg.into_fn_update(bn)?);
+                }
+                FnUpdate::Nary(op, arguments)
+            }
+            Threshold(args, threshold) => {
+                let mut arguments = Vec::with_capacity(args.len());
+                for (weight, arg) in args {
+                    arguments.push((weight, *arg.into_fn_update(bn)?));
+                }
+                FnUpdate::Threshold(arguments, threshold)
+            }
             Param(name, args) => {
                 let parameter_id = Self::get_parameter(bn, &name)?;
                 Self::check_parameter_arity(&bn[parameter_id], &args)?;
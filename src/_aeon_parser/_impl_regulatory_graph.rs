@@ -51,7 +51,13 @@ impl RegulatoryGraph {
             &regulation.target,
             regulation.observable,
             regulation.monotonicity,
-        )
+        )?;
+        if regulation.uncertain {
+            let regulator = self.find_variable(&regulation.regulator).unwrap();
+            let target = self.find_variable(&regulation.target).unwrap();
+            self.set_regulation_uncertain(regulator, target, true)?;
+        }
+        Ok(())
     }
 }
 
@@ -100,6 +106,7 @@ mod tests {
                     target: VariableId(1),
                     observable: true,
                     monotonicity: Some(Activation),
+                    uncertain: false,
                 },
                 Regulation {
                     // hello -|? abc
@@ -107,6 +114,7 @@ mod tests {
                     target: VariableId(0),
                     observable: false,
                     monotonicity: Some(Inhibition),
+                    uncertain: false,
                 },
                 Regulation {
                     // numbers_123 -?? abc
@@ -114,6 +122,7 @@ mod tests {
                     target: VariableId(0),
                     observable: false,
                     monotonicity: None,
+                    uncertain: false,
                 },
                 Regulation {
                     // numbers_123 -? hello
@@ -121,6 +130,7 @@ mod tests {
                     target: VariableId(1),
                     observable: true,
                     monotonicity: None,
+                    uncertain: false,
                 },
             ],
             variable_to_index: map,
@@ -1,4 +1,4 @@
-use crate::{BinaryOp, Monotonicity};
+use crate::{BinaryOp, Monotonicity, NaryOp};
 
 /// **(internal)** Convert `FnUpdateTemp` back to Boolean expression string.
 mod _display_fn_update_temp;
@@ -23,6 +23,7 @@ pub(crate) struct RegulationTemp {
     target: String,
     observable: bool,
     monotonicity: Option<Monotonicity>,
+    uncertain: bool,
 }
 
 /// **(internal)** A helper enum for representing a parsed `FnUpdate` that has not been
@@ -34,4 +35,6 @@ pub(crate) enum FnUpdateTemp {
     Param(String, Vec<FnUpdateTemp>),
     Not(Box<FnUpdateTemp>),
     Binary(BinaryOp, Box<FnUpdateTemp>, Box<FnUpdateTemp>),
+    Nary(NaryOp, Vec<FnUpdateTemp>),
+    Threshold(Vec<(i32, FnUpdateTemp)>, i32),
 }
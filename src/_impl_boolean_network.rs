@@ -12,9 +12,12 @@ use Monotonicity::Activation;
 /// Basic methods for safely building `BooleanNetwork`s.
 impl BooleanNetwork {
     /// Build a copy of this Boolean network where all essentiality and monotonicity constraints
-    /// are removed. This is basically the "inverse" of [BooleanNetwork::infer_valid_graph].
+    /// (as well as function class constraints and known function values) are removed. This is
+    /// basically the "inverse" of [BooleanNetwork::infer_valid_graph].
     pub fn remove_static_constraints(&self) -> BooleanNetwork {
         let mut new_bn = self.clone();
+        new_bn.clear_function_classes();
+        new_bn.clear_function_values();
         for reg in self.as_graph().regulations() {
             new_bn
                 .graph
@@ -35,6 +38,10 @@ impl BooleanNetwork {
             graph,
             parameters: Vec::new(),
             parameter_to_index: HashMap::new(),
+            parameter_classes: HashMap::new(),
+            implicit_classes: HashMap::new(),
+            parameter_values: HashMap::new(),
+            implicit_values: HashMap::new(),
         }
     }
 
@@ -44,7 +51,9 @@ impl BooleanNetwork {
         let is_aeon = extension == Some("aeon");
         let is_bnet = extension == Some("bnet");
         let is_sbml = extension == Some("sbml");
-        if is_aeon || is_bnet || is_sbml {
+        let is_boolnet = extension == Some("bn");
+        let is_ginml = extension == Some("ginml");
+        if is_aeon || is_bnet || is_sbml || is_boolnet || is_ginml {
             let content = std::fs::read_to_string(path);
             match content {
                 Ok(content) => {
@@ -52,6 +61,10 @@ impl BooleanNetwork {
                         Self::try_from(content.as_str())
                     } else if is_bnet {
                         Self::try_from_bnet(content.as_str())
+                    } else if is_boolnet {
+                        Self::try_from_boolnet(content.as_str())
+                    } else if is_ginml {
+                        Self::try_from_ginml(content.as_str()).map(|(x, _, _)| x)
                     } else {
                         Self::try_from_sbml(content.as_str()).map(|(x, _)| x)
                     }
@@ -339,6 +352,8 @@ impl BooleanNetwork {
                     .unwrap_or_else(|_| unreachable!("Function was valid in the old BN."));
             }
         }
+        new_bn.copy_function_classes(self);
+        new_bn.copy_function_values(self);
 
         new_bn
     }
@@ -469,6 +484,9 @@ impl BooleanNetwork {
             }
         }
 
+        new_bn.copy_function_classes(&old_bn);
+        new_bn.copy_function_values(&old_bn);
+
         // In the end, we still have to prune the network in case we just made some uninterpreted
         // function irrelevant by eliminating unused inputs.
         Ok(new_bn.prune_unused_parameters())
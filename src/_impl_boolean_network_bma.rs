@@ -0,0 +1,859 @@
+use crate::sbml::Layout;
+use crate::{
+    BinaryOp, BooleanNetwork, FnUpdate, ModelAnnotation, Monotonicity, NaryOp, RegulatoryGraph,
+    VariableId,
+};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+
+/// **(internal)** An arithmetic BMA target function over Boolean variables.
+#[derive(Clone, Debug, PartialEq)]
+enum BmaExpression {
+    Const(f64),
+    Var(VariableId),
+    Neg(Box<BmaExpression>),
+    Arithmetic(char, Box<BmaExpression>, Box<BmaExpression>),
+    Call(String, Vec<BmaExpression>),
+}
+
+impl BooleanNetwork {
+    /// Try to read a `BooleanNetwork` from a Bio Model Analyzer (BMA) JSON model.
+    ///
+    /// Only Boolean variables (i.e. with range `0..1`) are supported; other ranges result
+    /// in an error. BMA target functions are arithmetic expressions: `min` and `max` of
+    /// Boolean sub-expressions are translated to conjunctions and disjunctions, and `1 - x`
+    /// to a negation. Other expressions (e.g. `avg` or `+`) are evaluated for every
+    /// valuation of their inputs, with the result rounded to the nearest integer and
+    /// clamped to the `0..1` range. An empty formula represents the BMA default function
+    /// `avg(activators) - avg(inhibitors)`, unless the variable has no regulators, in which
+    /// case it is an input without an update function.
+    ///
+    /// Relationships are translated to non-observable regulations (`Activator` is positive,
+    /// `Inhibitor` is negative). Invalid variable names are sanitized (invalid characters
+    /// are replaced with `_`, and duplicate names are suffixed with the BMA identifier).
+    ///
+    /// Variable positions are returned as a `Layout`. The model name and description are
+    /// returned in a `ModelAnnotation` under the `name` and `description` keys, with
+    /// variable descriptions stored as `description:variable:<name>`.
+    ///
+    /// Requires the `json` feature (enabled by default).
+    pub fn try_from_bma_json(
+        model_file: &str,
+    ) -> Result<(BooleanNetwork, Layout, ModelAnnotation), String> {
+        let document: Value = serde_json::from_str(model_file)
+            .map_err(|e| format!("Invalid JSON in BMA file: {}", e))?;
+        let model = get_ignore_case(&document, "Model")
+            .ok_or_else(|| "Missing `Model` in BMA file.".to_string())?;
+        let json_variables = get_ignore_case(model, "Variables")
+            .and_then(|it| it.as_array())
+            .ok_or_else(|| "Missing `Model.Variables` in BMA file.".to_string())?;
+
+        // Read variable declarations and assign valid, unique names.
+        let name_re = Regex::new(r"[^a-zA-Z0-9_]").unwrap();
+        let mut ids = Vec::new();
+        let mut names = Vec::new();
+        let mut bma_names = Vec::new();
+        let mut formulas = Vec::new();
+        for variable in json_variables {
+            let id = read_bma_id(variable)?;
+            let bma_name = get_ignore_case(variable, "Name")
+                .and_then(|it| it.as_str())
+                .unwrap_or("")
+                .to_string();
+            let range_from = get_ignore_case(variable, "RangeFrom").and_then(|it| it.as_f64());
+            let range_to = get_ignore_case(variable, "RangeTo").and_then(|it| it.as_f64());
+            if range_from != Some(0.0) || range_to != Some(1.0) {
+                return Err(format!(
+                    "Variable `{}` (id {}) has range {}..{}, but only Boolean variables (range 0..1) are supported.",
+                    bma_name,
+                    id,
+                    range_from.map(|it| it.to_string()).unwrap_or_else(|| "?".to_string()),
+                    range_to.map(|it| it.to_string()).unwrap_or_else(|| "?".to_string()),
+                ));
+            }
+            if ids.contains(&id) {
+                return Err(format!("Duplicate variable id {} in BMA file.", id));
+            }
+            let mut name = name_re.replace_all(bma_name.trim(), "_").to_string();
+            if name.is_empty() || names.contains(&name) {
+                name = format!("{}_{}", name, id);
+            }
+            if names.contains(&name) {
+                return Err(format!("Cannot assign a unique name to variable {}.", id));
+            }
+            let formula = get_ignore_case(variable, "Formula")
+                .and_then(|it| it.as_str())
+                .unwrap_or("")
+                .to_string();
+            ids.push(id);
+            names.push(name);
+            bma_names.push(bma_name);
+            formulas.push(formula);
+        }
+        let id_to_var: HashMap<u64, VariableId> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, VariableId::from_index(i)))
+            .collect();
+
+        // Read relationships.
+        let mut activators: HashMap<VariableId, Vec<VariableId>> = HashMap::new();
+        let mut inhibitors: HashMap<VariableId, Vec<VariableId>> = HashMap::new();
+        let mut regulations: Vec<(VariableId, VariableId)> = Vec::new();
+        let json_relationships = get_ignore_case(model, "Relationships")
+            .and_then(|it| it.as_array())
+            .map(|it| it.as_slice())
+            .unwrap_or(&[]);
+        for relationship in json_relationships {
+            let endpoint = |key: &str| -> Result<VariableId, String> {
+                let id = get_ignore_case(relationship, key)
+                    .and_then(|it| it.as_f64())
+                    .ok_or_else(|| format!("Relationship without `{}` in BMA file.", key))?;
+                id_to_var
+                    .get(&(id as u64))
+                    .cloned()
+                    .ok_or_else(|| format!("Relationship references unknown variable {}.", id))
+            };
+            let regulator = endpoint("FromVariable")?;
+            let target = endpoint("ToVariable")?;
+            let kind = get_ignore_case(relationship, "Type")
+                .and_then(|it| it.as_str())
+                .unwrap_or("Activator");
+            match kind.to_lowercase().as_str() {
+                "activator" => activators.entry(target).or_default().push(regulator),
+                "inhibitor" => inhibitors.entry(target).or_default().push(regulator),
+                _ => return Err(format!("Unknown BMA relationship type `{}`.", kind)),
+            }
+            if !regulations.contains(&(regulator, target)) {
+                regulations.push((regulator, target));
+            }
+        }
+
+        // Parse formulas.
+        let mut functions = Vec::new();
+        for (i, formula) in formulas.iter().enumerate() {
+            let target = VariableId::from_index(i);
+            let expression = if formula.trim().is_empty() {
+                mk_default_bma_expression(
+                    activators
+                        .get(&target)
+                        .map(|it| it.as_slice())
+                        .unwrap_or(&[]),
+                    inhibitors
+                        .get(&target)
+                        .map(|it| it.as_slice())
+                        .unwrap_or(&[]),
+                )
+            } else {
+                let resolve = |token: &str| -> Option<VariableId> {
+                    token
+                        .parse::<u64>()
+                        .ok()
+                        .and_then(|id| id_to_var.get(&id).cloned())
+                        .or_else(|| {
+                            bma_names
+                                .iter()
+                                .position(|it| it == token)
+                                .map(VariableId::from_index)
+                        })
+                };
+                let expression = BmaExpression::parse(formula, &resolve).map_err(|e| {
+                    format!("Invalid formula of `{}`: {} (`{}`)", names[i], e, formula)
+                })?;
+                Some(expression)
+            };
+            if let Some(expression) = expression {
+                let mut inputs = HashSet::new();
+                expression.collect_variables(&mut inputs);
+                for regulator in inputs {
+                    if !regulations.contains(&(regulator, target)) {
+                        regulations.push((regulator, target));
+                    }
+                }
+                let function = expression
+                    .to_fn_update()
+                    .map_err(|e| format!("Invalid formula of `{}`: {}", names[i], e))?;
+                functions.push((target, function));
+            }
+        }
+
+        let mut graph = RegulatoryGraph::new(names.clone());
+        for (regulator, target) in regulations {
+            let is_activator = activators
+                .get(&target)
+                .map(|it| it.contains(&regulator))
+                .unwrap_or(false);
+            let is_inhibitor = inhibitors
+                .get(&target)
+                .map(|it| it.contains(&regulator))
+                .unwrap_or(false);
+            let monotonicity = match (is_activator, is_inhibitor) {
+                (true, false) => Some(Monotonicity::Activation),
+                (false, true) => Some(Monotonicity::Inhibition),
+                _ => None,
+            };
+            graph.add_regulation(
+                names[regulator.to_index()].as_str(),
+                names[target.to_index()].as_str(),
+                false,
+                monotonicity,
+            )?;
+        }
+        let mut network = BooleanNetwork::new(graph);
+        for (target, function) in functions {
+            network.set_update_function(target, Some(function))?;
+        }
+
+        // Read model metadata and layout.
+        let mut annotation = ModelAnnotation::new();
+        if let Some(name) = get_ignore_case(model, "Name").and_then(|it| it.as_str()) {
+            if !name.is_empty() {
+                annotation.ensure_value(&["name"], name);
+            }
+        }
+        if let Some(description) = get_ignore_case(model, "Description").and_then(|it| it.as_str())
+        {
+            if !description.is_empty() {
+                annotation.ensure_value(&["description"], description);
+            }
+        }
+        let mut layout = Layout::new();
+        let json_layout = get_ignore_case(&document, "Layout")
+            .and_then(|it| get_ignore_case(it, "Variables"))
+            .and_then(|it| it.as_array())
+            .map(|it| it.as_slice())
+            .unwrap_or(&[]);
+        for variable in json_layout {
+            let id = read_bma_id(variable)?;
+            if let Some(var) = id_to_var.get(&id) {
+                let name = names[var.to_index()].clone();
+                let x = get_ignore_case(variable, "PositionX").and_then(|it| it.as_f64());
+                let y = get_ignore_case(variable, "PositionY").and_then(|it| it.as_f64());
+                if let (Some(x), Some(y)) = (x, y) {
+                    layout.insert(name.clone(), (x, y));
+                }
+                let description =
+                    get_ignore_case(variable, "Description").and_then(|it| it.as_str());
+                if let Some(description) = description {
+                    if !description.is_empty() {
+                        annotation.ensure_value(&["description", "variable", &name], description);
+                    }
+                }
+            }
+        }
+
+        Ok((network, layout, annotation))
+    }
+
+    /// Convert this network to a Bio Model Analyzer (BMA) JSON model.
+    ///
+    /// All variables are Boolean (range `0..1`) and use their index (starting from `1`) as
+    /// their BMA identifier. Update functions are written using `min` (conjunction), `max`
+    /// (disjunction) and `1 - x` (negation). Variables without regulators and without
+    /// an update function are exported with an empty formula.
+    ///
+    /// Regulations become `Inhibitor` relationships if they are negative, and `Activator`
+    /// relationships otherwise. Positions are taken from the optional `layout`, and the model
+    /// name and descriptions from the optional `annotation` (see
+    /// [BooleanNetwork::try_from_bma_json]).
+    ///
+    /// Returns an error if the network is parametrised. Requires the `json` feature (enabled
+    /// by default).
+    pub fn to_bma_json(
+        &self,
+        layout: Option<&Layout>,
+        annotation: Option<&ModelAnnotation>,
+    ) -> Result<String, String> {
+        let bma_id = |var: VariableId| var.to_index() + 1;
+
+        let mut variables = Vec::new();
+        let mut layout_variables = Vec::new();
+        for var in self.variables() {
+            let name = self.get_variable_name(var);
+            let formula = match self.get_update_function(var) {
+                Some(function) => fn_update_to_bma_formula(function)?,
+                None if self.regulators(var).is_empty() => String::new(),
+                None => {
+                    return Err("Parametrised network cannot be converted to BMA.".to_string());
+                }
+            };
+            variables.push(json!({
+                "Id": bma_id(var),
+                "Name": name,
+                "RangeFrom": 0,
+                "RangeTo": 1,
+                "Formula": formula,
+            }));
+
+            let (x, y) = layout
+                .and_then(|it| it.get(name))
+                .cloned()
+                .unwrap_or((0.0, 0.0));
+            let description = annotation
+                .and_then(|it| it.get_value(&["description", "variable", name]))
+                .cloned()
+                .unwrap_or_default();
+            layout_variables.push(json!({
+                "Id": bma_id(var),
+                "Name": name,
+                "Type": "Default",
+                "ContainerId": 0,
+                "PositionX": x,
+                "PositionY": y,
+                "CellX": 0,
+                "CellY": 0,
+                "Angle": 0,
+                "Description": description,
+            }));
+        }
+
+        let relationships = self
+            .as_graph()
+            .regulations()
+            .enumerate()
+            .map(|(i, regulation)| {
+                let kind = if regulation.get_monotonicity() == Some(Monotonicity::Inhibition) {
+                    "Inhibitor"
+                } else {
+                    "Activator"
+                };
+                json!({
+                    "Id": self.num_vars() + i + 1,
+                    "FromVariable": bma_id(regulation.get_regulator()),
+                    "ToVariable": bma_id(regulation.get_target()),
+                    "Type": kind,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let name = annotation
+            .and_then(|it| it.get_value(&["name"]))
+            .cloned()
+            .unwrap_or_else(|| "model".to_string());
+        let description = annotation
+            .and_then(|it| it.get_value(&["description"]))
+            .cloned()
+            .unwrap_or_default();
+        let document = json!({
+            "Model": {
+                "Name": name,
+                "Description": description,
+                "Variables": variables,
+                "Relationships": relationships,
+            },
+            "Layout": {
+                "Variables": layout_variables,
+                "Containers": [],
+            },
+        });
+        Ok(document.to_string())
+    }
+}
+
+/// **(internal)** Find an object entry with the given `key`, ignoring the case of the key.
+/// Returns `None` if the key does not exist or `value` is not an object.
+fn get_ignore_case<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    value
+        .as_object()?
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+/// **(internal)** Read the numeric `Id` of a BMA variable.
+fn read_bma_id(variable: &Value) -> Result<u64, String> {
+    get_ignore_case(variable, "Id")
+        .and_then(|it| it.as_f64())
+        .filter(|it| *it >= 0.0 && it.fract() == 0.0)
+        .map(|it| it as u64)
+        .ok_or_else(|| "BMA variable without a valid `Id`.".to_string())
+}
+
+/// **(internal)** The BMA default target function `avg(activators) - avg(inhibitors)`.
+/// If there are no activators, the maximal value is used instead of their average.
+/// Returns `None` if there are no regulators.
+fn mk_default_bma_expression(
+    activators: &[VariableId],
+    inhibitors: &[VariableId],
+) -> Option<BmaExpression> {
+    let avg = |vars: &[VariableId]| {
+        BmaExpression::Call(
+            "avg".to_string(),
+            vars.iter().map(|it| BmaExpression::Var(*it)).collect(),
+        )
+    };
+    match (activators.is_empty(), inhibitors.is_empty()) {
+        (true, true) => None,
+        (false, true) => Some(avg(activators)),
+        (true, false) => Some(BmaExpression::Arithmetic(
+            '-',
+            Box::new(BmaExpression::Const(1.0)),
+            Box::new(avg(inhibitors)),
+        )),
+        (false, false) => Some(BmaExpression::Arithmetic(
+            '-',
+            Box::new(avg(activators)),
+            Box::new(avg(inhibitors)),
+        )),
+    }
+}
+
+/// **(internal)** Convert a `FnUpdate` to a BMA formula string.
+fn fn_update_to_bma_formula(function: &FnUpdate) -> Result<String, String> {
+    Ok(match function {
+        FnUpdate::Const(value) => if *value { "1" } else { "0" }.to_string(),
+        FnUpdate::Var(id) => format!("var({})", id.to_index() + 1),
+        FnUpdate::Param(_, _) => {
+            return Err("Parametrised network cannot be converted to BMA.".to_string());
+        }
+        FnUpdate::Not(inner) => format!("(1-{})", fn_update_to_bma_formula(inner)?),
+        FnUpdate::Binary(op, left, right) => {
+            let l = fn_update_to_bma_formula(left)?;
+            let r = fn_update_to_bma_formula(right)?;
+            match op {
+                BinaryOp::And => format!("min({},{})", l, r),
+                BinaryOp::Or => format!("max({},{})", l, r),
+                BinaryOp::Imp => format!("max((1-{}),{})", l, r),
+                BinaryOp::Iff => format!("min(max((1-{}),{}),max({},(1-{})))", l, r, l, r),
+                BinaryOp::Xor => format!("max(min({},(1-{})),min((1-{}),{}))", l, r, l, r),
+            }
+        }
+        FnUpdate::Threshold(_, _) => fn_update_to_bma_formula(&function.expand_thresholds())?,
+        FnUpdate::Nary(op, args) => {
+            let function = match op {
+                NaryOp::And => "min",
+                NaryOp::Or => "max",
+            };
+            // BMA min/max functions are binary, hence the arguments are nested.
+            let mut args = args.iter().rev();
+            let Some(last) = args.next() else {
+                return fn_update_to_bma_formula(&FnUpdate::Const(op.neutral_value()));
+            };
+            let mut result = fn_update_to_bma_formula(last)?;
+            for arg in args {
+                let arg = fn_update_to_bma_formula(arg)?;
+                result = format!("{}({},{})", function, arg, result);
+            }
+            result
+        }
+    })
+}
+
+impl BmaExpression {
+    /// Parse a BMA formula. Variables are referenced using `var(x)`, where `x` is resolved
+    /// to a `VariableId` using the `resolve` function.
+    fn parse<F>(formula: &str, resolve: &F) -> Result<BmaExpression, String>
+    where
+        F: Fn(&str) -> Option<VariableId>,
+    {
+        let tokens = tokenize_bma_formula(formula)?;
+        let mut position = 0;
+        let result = Self::parse_sum(&tokens, &mut position, resolve)?;
+        if position < tokens.len() {
+            return Err(format!("Unexpected token `{}`", tokens[position]));
+        }
+        Ok(result)
+    }
+
+    fn parse_sum<F>(tokens: &[String], position: &mut usize, resolve: &F) -> Result<Self, String>
+    where
+        F: Fn(&str) -> Option<VariableId>,
+    {
+        let mut result = Self::parse_product(tokens, position, resolve)?;
+        while *position < tokens.len() && (tokens[*position] == "+" || tokens[*position] == "-") {
+            let op = tokens[*position].chars().next().unwrap();
+            *position += 1;
+            let right = Self::parse_product(tokens, position, resolve)?;
+            result = BmaExpression::Arithmetic(op, Box::new(result), Box::new(right));
+        }
+        Ok(result)
+    }
+
+    fn parse_product<F>(
+        tokens: &[String],
+        position: &mut usize,
+        resolve: &F,
+    ) -> Result<Self, String>
+    where
+        F: Fn(&str) -> Option<VariableId>,
+    {
+        let mut result = Self::parse_unary(tokens, position, resolve)?;
+        while *position < tokens.len() && (tokens[*position] == "*" || tokens[*position] == "/") {
+            let op = tokens[*position].chars().next().unwrap();
+            *position += 1;
+            let right = Self::parse_unary(tokens, position, resolve)?;
+            result = BmaExpression::Arithmetic(op, Box::new(result), Box::new(right));
+        }
+        Ok(result)
+    }
+
+    fn parse_unary<F>(tokens: &[String], position: &mut usize, resolve: &F) -> Result<Self, String>
+    where
+        F: Fn(&str) -> Option<VariableId>,
+    {
+        let token = tokens
+            .get(*position)
+            .ok_or_else(|| "Unexpected end of formula".to_string())?;
+        *position += 1;
+        if token == "-" {
+            return Ok(BmaExpression::Neg(Box::new(Self::parse_unary(
+                tokens, position, resolve,
+            )?)));
+        }
+        if token == "(" {
+            let result = Self::parse_sum(tokens, position, resolve)?;
+            expect_token(tokens, position, ")")?;
+            return Ok(result);
+        }
+        if let Ok(value) = token.parse::<f64>() {
+            return Ok(BmaExpression::Const(value));
+        }
+        let function = token.to_lowercase();
+        expect_token(tokens, position, "(")?;
+        if function == "var" {
+            let mut reference = String::new();
+            while *position < tokens.len() && tokens[*position] != ")" {
+                reference.push_str(tokens[*position].as_str());
+                *position += 1;
+            }
+            expect_token(tokens, position, ")")?;
+            let var = resolve(reference.as_str())
+                .ok_or_else(|| format!("Unknown variable `{}`", reference))?;
+            return Ok(BmaExpression::Var(var));
+        }
+        let mut arguments = vec![Self::parse_sum(tokens, position, resolve)?];
+        while *position < tokens.len() && tokens[*position] == "," {
+            *position += 1;
+            arguments.push(Self::parse_sum(tokens, position, resolve)?);
+        }
+        expect_token(tokens, position, ")")?;
+        match function.as_str() {
+            "min" | "max" | "avg" => Ok(BmaExpression::Call(function, arguments)),
+            "ceil" | "floor" | "abs" if arguments.len() == 1 => {
+                Ok(BmaExpression::Call(function, arguments))
+            }
+            _ => Err(format!("Unsupported function `{}`", token)),
+        }
+    }
+
+    /// Collect all variables used in this expression.
+    fn collect_variables(&self, variables: &mut HashSet<VariableId>) {
+        match self {
+            BmaExpression::Const(_) => (),
+            BmaExpression::Var(var) => {
+                variables.insert(*var);
+            }
+            BmaExpression::Neg(inner) => inner.collect_variables(variables),
+            BmaExpression::Arithmetic(_, left, right) => {
+                left.collect_variables(variables);
+                right.collect_variables(variables);
+            }
+            BmaExpression::Call(_, arguments) => {
+                for argument in arguments {
+                    argument.collect_variables(variables);
+                }
+            }
+        }
+    }
+
+    /// Evaluate the expression in the given valuation.
+    fn evaluate(&self, valuation: &HashMap<VariableId, bool>) -> f64 {
+        match self {
+            BmaExpression::Const(value) => *value,
+            BmaExpression::Var(var) => {
+                if valuation[var] {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            BmaExpression::Neg(inner) => -inner.evaluate(valuation),
+            BmaExpression::Arithmetic(op, left, right) => {
+                let left = left.evaluate(valuation);
+                let right = right.evaluate(valuation);
+                match op {
+                    '+' => left + right,
+                    '-' => left - right,
+                    '*' => left * right,
+                    _ => left / right,
+                }
+            }
+            BmaExpression::Call(function, arguments) => {
+                let values = arguments
+                    .iter()
+                    .map(|it| it.evaluate(valuation))
+                    .collect::<Vec<_>>();
+                match function.as_str() {
+                    "min" => values.into_iter().fold(f64::INFINITY, f64::min),
+                    "max" => values.into_iter().fold(f64::NEG_INFINITY, f64::max),
+                    "avg" if values.is_empty() => 0.0,
+                    "avg" => values.iter().sum::<f64>() / (values.len() as f64),
+                    "ceil" => values[0].ceil(),
+                    "floor" => values[0].floor(),
+                    _ => values[0].abs(),
+                }
+            }
+        }
+    }
+
+    /// Translate `min`, `max` and `1 - x` over Boolean sub-expressions directly. Returns
+    /// `None` if the expression uses other arithmetic.
+    fn as_boolean_function(&self) -> Option<FnUpdate> {
+        match self {
+            BmaExpression::Const(value) if *value == 0.0 => Some(FnUpdate::mk_false()),
+            BmaExpression::Const(value) if *value == 1.0 => Some(FnUpdate::mk_true()),
+            BmaExpression::Var(var) => Some(FnUpdate::mk_var(*var)),
+            BmaExpression::Arithmetic('-', left, right) if **left == BmaExpression::Const(1.0) => {
+                right.as_boolean_function().map(|it| it.negation())
+            }
+            BmaExpression::Call(function, arguments) if function == "min" || function == "max" => {
+                let arguments = arguments
+                    .iter()
+                    .map(|it| it.as_boolean_function())
+                    .collect::<Option<Vec<_>>>()?;
+                // BMA functions are binary, so nested chains are flattened.
+                let function = if function == "min" {
+                    FnUpdate::mk_nary_conjunction(&arguments)
+                } else {
+                    FnUpdate::mk_nary_disjunction(&arguments)
+                };
+                Some(function.flatten())
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert this expression to a `FnUpdate`. If the expression is not directly
+    /// translatable, it is evaluated for every valuation of its inputs (the result is
+    /// rounded and clamped to `0..1`) and the function is built as a DNF of its true rows.
+    ///
+    /// Returns an error if the expression has too many inputs to be evaluated this way.
+    fn to_fn_update(&self) -> Result<FnUpdate, String> {
+        if let Some(function) = self.as_boolean_function() {
+            return Ok(function);
+        }
+        let mut inputs = HashSet::new();
+        self.collect_variables(&mut inputs);
+        let mut inputs = inputs.into_iter().collect::<Vec<_>>();
+        inputs.sort();
+        let row_count = u32::try_from(inputs.len())
+            .ok()
+            .and_then(|it| 1usize.checked_shl(it))
+            .ok_or_else(|| {
+                format!(
+                    "Expression with {} inputs is too large to be evaluated.",
+                    inputs.len()
+                )
+            })?;
+        let mut clauses = Vec::new();
+        for row in 0..row_count {
+            let valuation = inputs
+                .iter()
+                .enumerate()
+                .map(|(i, var)| (*var, (row >> i) & 1 == 1))
+                .collect::<HashMap<_, _>>();
+            if self.evaluate(&valuation).round() >= 1.0 {
+                let literals = inputs
+                    .iter()
+                    .map(|var| {
+                        if valuation[var] {
+                            FnUpdate::mk_var(*var)
+                        } else {
+                            FnUpdate::mk_not(FnUpdate::mk_var(*var))
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                clauses.push(FnUpdate::mk_nary_conjunction(&literals));
+            }
+        }
+        if clauses.is_empty() {
+            Ok(FnUpdate::mk_false())
+        } else {
+            Ok(FnUpdate::mk_nary_disjunction(&clauses))
+        }
+    }
+}
+
+/// **(internal)** Split a BMA formula into numbers, names and single-character symbols.
+fn tokenize_bma_formula(formula: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let chars = formula.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if "()+-*/,".contains(c) {
+            tokens.push(c.to_string());
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' || c == '.' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            return Err(format!("Unexpected character `{}`", c));
+        }
+    }
+    Ok(tokens)
+}
+
+/// **(internal)** Consume the `expected` token or fail.
+fn expect_token(tokens: &[String], position: &mut usize, expected: &str) -> Result<(), String> {
+    if tokens.get(*position).map(|it| it.as_str()) == Some(expected) {
+        *position += 1;
+        Ok(())
+    } else {
+        Err(format!("Expected `{}`", expected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_impl_boolean_network_bma::BmaExpression;
+    use crate::{BooleanNetwork, Monotonicity, VariableId};
+    use std::collections::HashMap;
+
+    const BMA_MODEL: &str = r#"{
+        "Model": {
+            "Name": "Example",
+            "Description": "A small example model.",
+            "Variables": [
+                { "Id": 1, "Name": "A", "RangeFrom": 0, "RangeTo": 1, "Formula": "" },
+                { "Id": 2, "Name": "B", "RangeFrom": 0, "RangeTo": 1, "Formula": "" },
+                { "Id": 3, "Name": "C 1", "RangeFrom": 0, "RangeTo": 1, "Formula": "min(var(1), 1 - var(B))" },
+                { "Id": 4, "Name": "D", "RangeFrom": 0, "RangeTo": 1, "Formula": "(var(1) + var(2) + var(3)) / 3" }
+            ],
+            "Relationships": [
+                { "Id": 5, "FromVariable": 1, "ToVariable": 2, "Type": "Activator" },
+                { "Id": 6, "FromVariable": 3, "ToVariable": 2, "Type": "Inhibitor" },
+                { "Id": 7, "FromVariable": 1, "ToVariable": 3, "Type": "Activator" },
+                { "Id": 8, "FromVariable": 2, "ToVariable": 3, "Type": "Inhibitor" },
+                { "Id": 9, "FromVariable": 1, "ToVariable": 4, "Type": "Activator" },
+                { "Id": 10, "FromVariable": 2, "ToVariable": 4, "Type": "Activator" },
+                { "Id": 11, "FromVariable": 3, "ToVariable": 4, "Type": "Activator" }
+            ]
+        },
+        "Layout": {
+            "Variables": [
+                { "Id": 1, "PositionX": 10.5, "PositionY": 20, "Description": "Input." },
+                { "Id": 3, "PositionX": 30, "PositionY": 40, "Description": "" }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn test_read_bma() {
+        let (bn, layout, annotation) = BooleanNetwork::try_from_bma_json(BMA_MODEL).unwrap();
+        assert_eq!(bn.num_vars(), 4);
+        let a = bn.as_graph().find_variable("A").unwrap();
+        let b = bn.as_graph().find_variable("B").unwrap();
+        let c = bn.as_graph().find_variable("C_1").unwrap();
+        let d = bn.as_graph().find_variable("D").unwrap();
+        assert!(bn.get_update_function(a).is_none());
+        assert_eq!(
+            bn.as_graph()
+                .find_regulation(c, b)
+                .unwrap()
+                .get_monotonicity(),
+            Some(Monotonicity::Inhibition)
+        );
+
+        let table = |var, inputs: &[crate::VariableId]| {
+            let function = bn.get_update_function(var).as_ref().unwrap();
+            (0..(1 << inputs.len()))
+                .map(|row: usize| {
+                    let valuation = inputs
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| (*v, (row >> i) & 1 == 1))
+                        .collect::<HashMap<_, _>>();
+                    function.evaluate(&valuation).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        // Default function `avg(A) - avg(C)` is `A & !C`.
+        assert_eq!(table(b, &[a, c]), vec![false, true, false, false]);
+        // `min(A, 1 - B)` is `A & !B`.
+        assert_eq!(table(c, &[a, b]), vec![false, true, false, false]);
+        // Average of three inputs is a majority.
+        assert_eq!(
+            table(d, &[a, b, c]),
+            vec![false, false, false, true, false, true, true, true]
+        );
+
+        assert_eq!(layout.get("A"), Some(&(10.5, 20.0)));
+        assert_eq!(layout.get("C_1"), Some(&(30.0, 40.0)));
+        assert_eq!(layout.len(), 2);
+        assert_eq!(annotation.get_value(&["name"]).unwrap(), "Example");
+        assert_eq!(
+            annotation.get_value(&["description"]).unwrap(),
+            "A small example model."
+        );
+        assert_eq!(
+            annotation
+                .get_value(&["description", "variable", "A"])
+                .unwrap(),
+            "Input."
+        );
+        assert!(annotation
+            .get_child(&["description", "variable", "C_1"])
+            .is_none());
+    }
+
+    #[test]
+    fn test_read_bma_invalid() {
+        let model = r#"{ "Model": { "Variables": [
+            { "Id": 1, "Name": "A", "RangeFrom": 0, "RangeTo": 2, "Formula": "" }
+        ] } }"#;
+        let error = BooleanNetwork::try_from_bma_json(model).unwrap_err();
+        assert!(error.contains("only Boolean variables"));
+        let model = r#"{ "Model": { "Variables": [
+            { "Id": 1, "Name": "A", "RangeFrom": 0, "RangeTo": 1, "Formula": "var(7)" }
+        ] } }"#;
+        assert!(BooleanNetwork::try_from_bma_json(model).is_err());
+        let model = r#"{ "Model": { "Variables": [
+            { "Id": 1, "Name": "A", "RangeFrom": 0, "RangeTo": 1, "Formula": "pow(var(1), 2)" }
+        ] } }"#;
+        assert!(BooleanNetwork::try_from_bma_json(model).is_err());
+        assert!(BooleanNetwork::try_from_bma_json("{}").is_err());
+        assert!(BooleanNetwork::try_from_bma_json("not json").is_err());
+
+        // Non-Boolean expressions with too many inputs cannot be evaluated.
+        let inputs = (0..70)
+            .map(|i| BmaExpression::Var(VariableId::from_index(i)))
+            .collect::<Vec<_>>();
+        let expression = BmaExpression::Call("avg".to_string(), inputs);
+        assert!(expression.to_fn_update().is_err());
+    }
+
+    #[test]
+    fn test_bma_round_trip() {
+        let (bn, layout, annotation) = BooleanNetwork::try_from_bma_json(BMA_MODEL).unwrap();
+        let json = bn.to_bma_json(Some(&layout), Some(&annotation)).unwrap();
+        let (bn2, layout2, annotation2) = BooleanNetwork::try_from_bma_json(&json).unwrap();
+        assert_eq!(bn, bn2);
+        assert_eq!(layout2.get("A"), layout.get("A"));
+        assert_eq!(annotation, annotation2);
+
+        let bn = BooleanNetwork::try_from("a -> b \n $b: f(a)").unwrap();
+        assert!(bn.to_bma_json(None, None).is_err());
+        let bn = BooleanNetwork::try_from("a -> b \n b -? b \n $b: a ^ b").unwrap();
+        let json = bn.to_bma_json(None, None).unwrap();
+        let (bn2, _, _) = BooleanNetwork::try_from_bma_json(&json).unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let f = bn.get_update_function(b).as_ref().unwrap();
+        let f2 = bn2.get_update_function(b).as_ref().unwrap();
+        for row in 0..4 {
+            let valuation = bn
+                .variables()
+                .map(|v| (v, (row >> v.to_index()) & 1 == 1))
+                .collect::<HashMap<_, _>>();
+            assert_eq!(f.evaluate(&valuation), f2.evaluate(&valuation));
+        }
+    }
+}
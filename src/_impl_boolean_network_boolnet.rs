@@ -0,0 +1,266 @@
+use crate::_aeon_parser::FnUpdateTemp;
+use crate::_impl_boolean_network_to_bnet::{fn_update_to_bnet_string, BnetSyntax};
+use crate::{BooleanNetwork, RegulatoryGraph};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+impl BooleanNetwork {
+    /// Try to load a Boolean network from a BoolNet (R package) model file.
+    ///
+    /// The format is similar to `.bnet`: a mandatory `targets, factors` header, followed by
+    /// one `target, function` line per variable. Functions can use `!`, `&`, `|`, parentheses
+    /// and the constants `0`, `1`, `TRUE` and `FALSE`.
+    ///
+    /// Probabilistic networks (a `probabilities` column with multiple functions per target),
+    /// temporal networks and special BoolNet functions (e.g. `sumgt` or `maj`) are not
+    /// supported and result in an error. A probability column is accepted as long as each
+    /// target has exactly one function.
+    pub fn try_from_boolnet(model_string: &str) -> Result<BooleanNetwork, String> {
+        let true_re = Regex::new(r"\bTRUE\b").unwrap();
+        let false_re = Regex::new(r"\bFALSE\b").unwrap();
+        let special_re =
+            Regex::new(r"\[|\]|\b(sumis|sumgt|sumlt|timeis|timegt|timelt|all|any|maj)\s*\(")
+                .unwrap();
+
+        let mut model_map: HashMap<String, FnUpdateTemp> = HashMap::new();
+        let mut variables = HashSet::new();
+        let mut header = None;
+        for line in model_string.lines() {
+            let line = line.trim();
+            if line.starts_with('#') || line.is_empty() {
+                continue; // Skip comments and empty lines.
+            }
+
+            let segments = line.split(',').map(|it| it.trim()).collect::<Vec<_>>();
+            if header.is_none() {
+                let lowercase = segments
+                    .iter()
+                    .map(|it| it.to_ascii_lowercase())
+                    .collect::<Vec<_>>();
+                if lowercase.len() == 2 && lowercase[0] == "targets" && lowercase[1] == "factors" {
+                    header = Some(2);
+                } else if lowercase.len() == 3
+                    && lowercase[0] == "targets"
+                    && lowercase[1] == "factors"
+                    && lowercase[2] == "probabilities"
+                {
+                    header = Some(3);
+                } else {
+                    return Err(format!(
+                        "Expected `targets, factors` header, but found `{}`.",
+                        line
+                    ));
+                }
+                continue;
+            }
+
+            if Some(segments.len()) != header {
+                return Err(format!("Unexpected line: `{}`", line));
+            }
+            if segments.len() == 3 {
+                let probability = segments[2]
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid probability in line: `{}`", line))?;
+                if probability != 1.0 {
+                    return Err(format!(
+                        "Probabilistic BoolNet networks are not supported (line `{}`).",
+                        line
+                    ));
+                }
+            }
+
+            let variable_name = segments[0].to_string();
+            if model_map.contains_key(&variable_name) {
+                return Err(format!(
+                    "Duplicate function declaration for `{}`. Probabilistic BoolNet networks are not supported.",
+                    variable_name
+                ));
+            }
+
+            let function_string = segments[1];
+            if special_re.is_match(function_string) {
+                return Err(format!(
+                    "Unsupported BoolNet function (temporal predicates and special functions are not supported): `{}`",
+                    function_string
+                ));
+            }
+            let function_string = true_re.replace_all(function_string, "true");
+            let function_string = false_re.replace_all(&function_string, "false");
+            let function_template = FnUpdateTemp::try_from(function_string.as_ref())?;
+
+            // Also scan regulators for variable names.
+            variables.insert(variable_name.clone());
+            function_template.dump_variables(&mut variables);
+            model_map.insert(variable_name, function_template);
+        }
+
+        if header.is_none() {
+            return Err("Missing `targets, factors` header.".to_string());
+        }
+
+        let mut variables = variables.into_iter().collect::<Vec<_>>();
+        variables.sort();
+        let mut graph = RegulatoryGraph::new(variables.clone());
+
+        // First, build graph.
+        for variable in &variables {
+            if let Some(function_template) = model_map.get(variable) {
+                let mut regulators = HashSet::new();
+                function_template.dump_variables(&mut regulators);
+                let mut regulators = regulators.iter().collect::<Vec<_>>();
+                regulators.sort();
+                for regulator in regulators {
+                    graph.add_regulation(regulator.as_str(), variable.as_str(), true, None)?;
+                }
+            }
+        }
+
+        let mut network = BooleanNetwork::new(graph);
+
+        // Then add functions.
+        for (variable, function_template) in model_map.into_iter() {
+            network.add_template_update_function(variable.as_str(), function_template)?;
+        }
+
+        Ok(network)
+    }
+
+    /// Produce a BoolNet (R package) string representation of this model.
+    ///
+    /// Constants are written as `0` and `1`. Since BoolNet requires a function for every
+    /// variable, inputs without an update function (and without regulators) are written
+    /// as identity functions (e.g. `A, A`).
+    ///
+    /// Returns an error if the network is parametrised. Similar to [BooleanNetwork::to_bnet],
+    /// invalid names result in an error, unless `rename_if_necessary` is set, in which case
+    /// they are prefixed with `_`.
+    pub fn to_boolnet(&self, rename_if_necessary: bool) -> Result<String, String> {
+        let mut network = self.clone();
+        // A regex which only matches valid BoolNet names.
+        let name_re = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
+        for var in network.variables() {
+            let name = network.get_variable_name(var);
+            if !name_re.is_match(name) {
+                if rename_if_necessary {
+                    let new_name = format!("_{}", name);
+                    network.as_graph_mut().set_variable_name(var, &new_name)?;
+                } else {
+                    return Err(format!(
+                        "Variable {} cannot be exported to BoolNet. Please rename it first.",
+                        name
+                    ));
+                }
+            }
+        }
+
+        let mut model = "targets, factors\n".to_string();
+        for v in network.variables() {
+            let name = network.get_variable_name(v);
+            if let Some(function) = network.get_update_function(v) {
+                let function_string =
+                    fn_update_to_bnet_string(v, function, &network, &BOOLNET_SYNTAX)?;
+                model.push_str(format!("{}, {}\n", name, function_string).as_str());
+            } else if network.regulators(v).is_empty() {
+                model.push_str(format!("{}, {}\n", name, name).as_str());
+            } else {
+                return Err("Parametrised network cannot be converted to BoolNet.".to_string());
+            }
+        }
+
+        Ok(model)
+    }
+}
+
+/// **(internal)** The syntax of BoolNet expressions (a `.bnet`-like format with constants
+/// `0` and `1`, but without parameters).
+const BOOLNET_SYNTAX: BnetSyntax = BnetSyntax {
+    format: "BoolNet",
+    not: "!",
+    and: "&",
+    or: "|",
+    constants: Some(("0", "1")),
+    allow_parameters: false,
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    const BOOLNET_MODEL: &str = r"
+# Cell cycle example
+targets, factors
+CycD, CycD
+Rb, (!CycA & !CycB & !CycD & !CycE) | (p27 & !CycB & !CycD)
+E2F, (!Rb & !CycA & !CycB) | (p27 & !Rb & !CycB)
+CycE, (E2F & !Rb)
+CycA, (E2F & !Rb & !Cdc20 & !(Cdh1 & UbcH10)) | (CycA & !Rb & !Cdc20 & !(Cdh1 & UbcH10))
+p27, (!CycD & !CycE & !CycA & !CycB) | (p27 & !(CycE & CycA) & !CycB & !CycD)
+Cdc20, CycB
+Cdh1, (!CycA & !CycB) | (Cdc20) | (p27 & !CycB)
+UbcH10, !Cdh1 | (Cdh1 & UbcH10 & (Cdc20 | CycA | CycB))
+CycB, !Cdc20 & !Cdh1
+Const, TRUE
+";
+
+    #[test]
+    fn test_read_boolnet() {
+        let network = BooleanNetwork::try_from_boolnet(BOOLNET_MODEL).unwrap();
+        assert_eq!(11, network.num_vars());
+        for v in network.variables() {
+            assert!(network.get_update_function(v).is_some());
+        }
+        let c = network.as_graph().find_variable("Const").unwrap();
+        assert_eq!(
+            network.get_update_function(c).as_ref().unwrap().as_const(),
+            Some(true)
+        );
+        let e2f = network.as_graph().find_variable("E2F").unwrap();
+        assert_eq!(network.regulators(e2f).len(), 4);
+    }
+
+    #[test]
+    fn test_read_boolnet_invalid() {
+        // Missing header.
+        assert!(BooleanNetwork::try_from_boolnet("A, B\nB, A").is_err());
+        // Probabilistic network.
+        let model = "targets, factors, probabilities\nA, B, 0.5\nA, !B, 0.5\nB, A, 1";
+        assert!(BooleanNetwork::try_from_boolnet(model).is_err());
+        let model = "targets, factors, probabilities\nA, B, 1\nB, A, 1.0";
+        assert!(BooleanNetwork::try_from_boolnet(model).is_ok());
+        // Temporal and special functions.
+        assert!(BooleanNetwork::try_from_boolnet("targets, factors\nA, B[-2]\nB, A").is_err());
+        assert!(BooleanNetwork::try_from_boolnet("targets, factors\nA, sumgt(A, B, 1)").is_err());
+    }
+
+    #[test]
+    fn test_network_to_boolnet() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| c
+            c -? a
+            a -? c
+            e -> c
+            $a: c
+            $b: a => true
+            $c: a ^ !b | e
+            $d: false
+        ",
+        )
+        .unwrap();
+        let model = bn.to_boolnet(false).unwrap();
+        assert!(model.contains("d, 0\n"));
+        assert!(model.contains("e, e\n"));
+        let bn_after = BooleanNetwork::try_from_boolnet(model.as_str()).unwrap();
+        assert_eq!(bn.num_vars(), bn_after.num_vars());
+        for v in bn.variables() {
+            assert_eq!(bn.get_variable_name(v), bn_after.get_variable_name(v));
+        }
+
+        // Parametrised networks cannot be exported.
+        let bn = BooleanNetwork::try_from("A -> B \n B -> A").unwrap();
+        assert!(bn.to_boolnet(false).is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::BooleanNetwork;
+use crate::_impl_function_values::function_row_to_string;
+use crate::{BooleanNetwork, FunctionClass};
 use std::fmt::{Display, Error, Formatter};
 
 impl Display for BooleanNetwork {
@@ -10,6 +11,41 @@ impl Display for BooleanNetwork {
                 writeln!(f, "${}: {}", self[var], fun.to_string(self))?;
             }
         }
+        // print all function classes
+        let join = |classes: Vec<FunctionClass>| {
+            classes
+                .iter()
+                .map(|it| it.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        for param in self.parameters() {
+            let classes = self.get_parameter_classes(param);
+            if !classes.is_empty() {
+                writeln!(f, "@{}: {}", self[param].get_name(), join(classes))?;
+            }
+        }
+        for var in self.variables() {
+            let classes = self.get_implicit_classes(var);
+            if !classes.is_empty() && self.get_update_function(var).is_none() {
+                writeln!(f, "@{}: {}", self[var], join(classes))?;
+            }
+        }
+        // print all known function values
+        for param in self.parameters() {
+            for (args, v) in self.get_parameter_values(param) {
+                let row = function_row_to_string(self[param].get_name(), &args);
+                writeln!(f, "@{} = {}", row, u8::from(v))?;
+            }
+        }
+        for var in self.variables() {
+            if self.get_update_function(var).is_none() {
+                for (args, v) in self.get_implicit_values(var) {
+                    let row = function_row_to_string(self.get_variable_name(var), &args);
+                    writeln!(f, "@{} = {}", row, u8::from(v))?;
+                }
+            }
+        }
         Ok(())
     }
 }
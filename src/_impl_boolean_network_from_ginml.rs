@@ -0,0 +1,378 @@
+use crate::_aeon_parser::FnUpdateTemp;
+use crate::multi_valued::{Booleanization, MultiValuedVariable};
+use crate::sbml::Layout;
+use crate::{BooleanNetwork, FnUpdate};
+use regex::{Captures, Regex};
+use roxmltree::{Node, ParsingOptions};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// **(internal)** A GINML regulatory edge (only the information relevant for the logical
+/// parameters is retained).
+struct GinmlEdge {
+    id: String,
+    from: String,
+    min_value: u32,
+    max_value: Option<u32>,
+}
+
+impl BooleanNetwork {
+    /// Try to read a `BooleanNetwork` from a GINsim `.ginml` model (the XML model file, which
+    /// is also stored inside the zipped `.zginml` files).
+    ///
+    /// Multi-valued nodes are Booleanized using the Van Ham encoding (see
+    /// [crate::multi_valued]). The returned [Booleanization] maps the original nodes to
+    /// the Boolean variables. Note that only the admissible states of the network correspond
+    /// to the states of the original model (see [Booleanization::mk_admissible_states]).
+    ///
+    /// The target level of a node is given by its `value` elements (`exp` formulas) and
+    /// its logical `parameter` elements. If multiple values apply in the same state, the
+    /// maximal one is used. If no value applies, the node targets its `basevalue`. In the
+    /// formulas, `A:k` is true when the level of `A` is at least `k` and `A` is equivalent
+    /// to `A:1`. Nodes marked as `input` keep their value.
+    ///
+    /// The node positions are returned in the `Layout`, where each Boolean variable uses
+    /// the position of its original node.
+    pub fn try_from_ginml(
+        model_file: &str,
+    ) -> Result<(BooleanNetwork, Layout, Booleanization), String> {
+        // GINML files typically reference an (external) DTD, which is ignored by the parser.
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
+        let document = roxmltree::Document::parse_with_options(model_file, options)
+            .map_err(|e| format!("XML Error: {:?}", e))?;
+        let graph = document
+            .root()
+            .descendants()
+            .find(|it| it.tag_name().name() == "graph")
+            .ok_or_else(|| "The document does not contain a <graph> element.".to_string())?;
+        if let Some(class) = graph.attribute("class") {
+            if class != "regulatory" {
+                return Err(format!(
+                    "Expected a regulatory graph, but found `{}`.",
+                    class
+                ));
+            }
+        }
+
+        let name_regex = Regex::new(r"^[a-zA-Z0-9_]+$").unwrap();
+        let nodes = child_elements(graph, "node");
+        let mut node_map = HashMap::new();
+        for node in &nodes {
+            let id = node
+                .attribute("id")
+                .ok_or_else(|| "GINML node with a missing ID.".to_string())?;
+            if !name_regex.is_match(id) {
+                return Err(format!("Unsupported GINML node ID `{}`.", id));
+            }
+            if node_map.insert(id.to_string(), *node).is_some() {
+                return Err(format!("Duplicate GINML node `{}`.", id));
+            }
+        }
+
+        // Use the declared node order if possible, otherwise keep the document order.
+        let node_order = if let Some(order) = graph.attribute("nodeorder") {
+            let order = order
+                .split_whitespace()
+                .map(|it| it.to_string())
+                .collect::<Vec<_>>();
+            if order.len() != nodes.len() || order.iter().any(|it| !node_map.contains_key(it)) {
+                return Err("The `nodeorder` does not match the GINML nodes.".to_string());
+            }
+            order
+        } else {
+            nodes
+                .iter()
+                .map(|it| it.attribute("id").unwrap().to_string())
+                .collect()
+        };
+
+        let mut variables = Vec::new();
+        for id in &node_order {
+            let max_level = read_u32(node_map[id], "maxvalue")?.unwrap_or(1);
+            if max_level == 0 {
+                return Err(format!("Node `{}` has no admissible levels.", id));
+            }
+            variables.push((id.clone(), max_level));
+        }
+        let mapping = Booleanization::new(&variables);
+
+        let mut edges: HashMap<String, Vec<GinmlEdge>> = HashMap::new();
+        for edge in child_elements(graph, "edge") {
+            let from = edge.attribute("from").unwrap_or_default();
+            let to = edge.attribute("to").unwrap_or_default();
+            if !node_map.contains_key(from) || !node_map.contains_key(to) {
+                return Err(format!("Invalid GINML edge from `{}` to `{}`.", from, to));
+            }
+            let id = edge
+                .attribute("id")
+                .map(|it| it.to_string())
+                .unwrap_or_else(|| format!("{}:{}", from, to));
+            edges.entry(to.to_string()).or_default().push(GinmlEdge {
+                id,
+                from: from.to_string(),
+                min_value: read_u32(edge, "minvalue")?.unwrap_or(1),
+                // GINsim can also use `max` as a value, which is the same as no limit.
+                max_value: edge
+                    .attribute("maxvalue")
+                    .and_then(|it| it.parse::<u32>().ok()),
+            });
+        }
+
+        // A network that is only used to resolve variable names in formulas.
+        let names = mapping.mk_template_network();
+        let literal_regex = Regex::new(r"([a-zA-Z0-9_]+)(:([0-9]+))?").unwrap();
+
+        let mut functions = Vec::new();
+        let mut layout = Layout::new();
+        for id in &node_order {
+            let node = node_map[id];
+            let variable = mapping.find_variable(id).unwrap();
+            let empty = Vec::new();
+            let node_edges = edges.get(id).unwrap_or(&empty);
+
+            let mut terms = Vec::new();
+            for value in child_elements(node, "value") {
+                let level = read_u32(value, "val")?.unwrap_or(0);
+                let mut conditions = Vec::new();
+                for exp in child_elements(value, "exp") {
+                    let formula = exp.attribute("str").unwrap_or_default();
+                    // Replace every `A` or `A:k` literal with the corresponding Boolean variable.
+                    let formula = literal_regex.replace_all(formula, |c: &Captures| {
+                        if let Some(regulator) = mapping.find_variable(&c[1]) {
+                            let k = c
+                                .get(3)
+                                .and_then(|it| it.as_str().parse::<u32>().ok())
+                                .unwrap_or(1);
+                            match regulator.mk_level_geq(k) {
+                                FnUpdate::Var(v) => names.get_variable_name(v).clone(),
+                                FnUpdate::Const(value) => value.to_string(),
+                                _ => unreachable!(),
+                            }
+                        } else {
+                            c[0].to_string()
+                        }
+                    });
+                    let template = FnUpdateTemp::try_from(formula.as_ref())?;
+                    conditions.push(*template.into_fn_update(&names)?);
+                }
+                if !conditions.is_empty() {
+                    terms.push((level, FnUpdate::mk_nary_disjunction(&conditions)));
+                }
+            }
+
+            for parameter in child_elements(node, "parameter") {
+                let level = read_u32(parameter, "val")?.unwrap_or(0);
+                let active = parameter
+                    .attribute("idActiveInteractions")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .collect::<Vec<_>>();
+                if let Some(unknown) = active
+                    .iter()
+                    .find(|it| !node_edges.iter().any(|e| e.id == **it))
+                {
+                    return Err(format!("Unknown interaction `{}` of `{}`.", unknown, id));
+                }
+                let literals = node_edges
+                    .iter()
+                    .map(|edge| {
+                        let condition = edge_condition(&mapping, edge);
+                        if active.contains(&edge.id.as_str()) {
+                            condition
+                        } else {
+                            condition.negation()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                terms.push((level, FnUpdate::mk_nary_conjunction(&literals)));
+            }
+
+            if let Some((level, _)) = terms.iter().find(|(l, _)| *l > variable.get_max_level()) {
+                return Err(format!("Invalid level {} of node `{}`.", level, id));
+            }
+
+            let default = read_u32(node, "basevalue")?.unwrap_or(0);
+            let is_input = node.attribute("input") == Some("true");
+            let updates = variable.mk_update_functions(|k| {
+                if is_input {
+                    variable.mk_level_geq(k)
+                } else {
+                    MultiValuedVariable::mk_target_geq(&terms, default, k)
+                }
+            });
+            functions.extend(variable.encoding().iter().cloned().zip(updates));
+
+            if let Some(position) = read_position(node)? {
+                for var in variable.encoding() {
+                    layout.insert(names.get_variable_name(*var).clone(), position);
+                }
+            }
+        }
+
+        let network = mapping.build_network(&names, functions)?;
+        Ok((network, layout, mapping))
+    }
+}
+
+/// **(internal)** The condition under which the given GINML `edge` is active.
+fn edge_condition(mapping: &Booleanization, edge: &GinmlEdge) -> FnUpdate {
+    let source = mapping.find_variable(&edge.from).unwrap();
+    let condition = source.mk_level_geq(edge.min_value);
+    if let Some(max_value) = edge.max_value {
+        condition.and(source.mk_level_geq(max_value + 1).negation())
+    } else {
+        condition
+    }
+}
+
+/// **(internal)** Find all child elements with the given (local) tag name.
+fn child_elements<'a, 'input: 'a>(parent: Node<'a, 'input>, name: &str) -> Vec<Node<'a, 'input>> {
+    parent
+        .children()
+        .filter(|it| it.is_element() && it.tag_name().name() == name)
+        .collect()
+}
+
+/// **(internal)** Read an optional numeric attribute.
+fn read_u32(node: Node, attribute: &str) -> Result<Option<u32>, String> {
+    if let Some(value) = node.attribute(attribute) {
+        value
+            .parse::<u32>()
+            .map(Some)
+            .map_err(|_| format!("Invalid value of `{}`: {}", attribute, value))
+    } else {
+        Ok(None)
+    }
+}
+
+/// **(internal)** Read the position of a GINML node. The position is either given directly
+/// in the `nodevisualsetting` element, or in its child (e.g. `rect` or `ellipse`).
+fn read_position(node: Node) -> Result<Option<(f64, f64)>, String> {
+    let settings = if let Some(settings) = child_elements(node, "nodevisualsetting").first() {
+        *settings
+    } else {
+        return Ok(None);
+    };
+    let element = if settings.has_attribute("x") {
+        Some(settings)
+    } else {
+        settings
+            .children()
+            .find(|it| it.is_element() && it.has_attribute("x"))
+    };
+    if let Some(element) = element {
+        let read = |attribute: &str| -> Result<f64, String> {
+            let value = element.attribute(attribute).unwrap_or("0");
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid numeric value: {}.", value))
+        };
+        Ok(Some((read("x")?, read("y")?)))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::biodivine_std::bitvector::BitVector;
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::BooleanNetwork;
+    use std::collections::HashMap;
+
+    pub const GINML_MODEL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE gxl SYSTEM "http://ginsim.org/GINML_2_2.dtd">
+<gxl xmlns:xlink="http://www.w3.org/1999/xlink">
+  <graph class="regulatory" id="example" nodeorder="A B C">
+    <node id="A" maxvalue="2" input="true">
+      <nodevisualsetting x="10" y="20" style=""/>
+    </node>
+    <node id="B" maxvalue="2">
+      <value val="1">
+        <exp str="A &amp; !C"/>
+      </value>
+      <value val="2">
+        <exp str="A:2 &amp; !C"/>
+      </value>
+      <nodevisualsetting>
+        <ellipse x="30" y="40" width="45" height="25"/>
+      </nodevisualsetting>
+    </node>
+    <node id="C" basevalue="1">
+      <parameter idActiveInteractions="B:C" val="0"/>
+    </node>
+    <edge id="A:B" from="A" to="B" minvalue="1" sign="positive"/>
+    <edge id="C:B" from="C" to="B" minvalue="1" sign="negative"/>
+    <edge id="B:C" from="B" to="C" minvalue="2" sign="negative"/>
+  </graph>
+</gxl>
+"#;
+
+    #[test]
+    fn test_read_ginml() {
+        let (network, layout, mapping) = BooleanNetwork::try_from_ginml(GINML_MODEL).unwrap();
+        assert_eq!(network.num_vars(), 5);
+        assert_eq!(
+            mapping.boolean_variable_names(),
+            vec!["A_b1", "A_b2", "B_b1", "B_b2", "C"]
+        );
+        assert_eq!(layout["A_b2"], (10.0, 20.0));
+        assert_eq!(layout["B_b1"], (30.0, 40.0));
+        assert!(!layout.contains_key("C"));
+
+        // Check the multi-valued target levels in a few admissible states.
+        let b = mapping.find_variable("B").unwrap();
+        let c = mapping.find_variable("C").unwrap();
+        let step = |levels: &[u32], var: &str| -> Vec<bool> {
+            let state = mapping.encode_state(levels);
+            let valuation: HashMap<_, _> = network
+                .variables()
+                .map(|v| (v, state.get(v.to_index())))
+                .collect();
+            mapping
+                .find_variable(var)
+                .unwrap()
+                .encoding()
+                .iter()
+                .map(|v| {
+                    let function = network.get_update_function(*v).as_ref().unwrap();
+                    function.evaluate(&valuation).unwrap()
+                })
+                .collect()
+        };
+        // A = 2, B = 0, C = 0: B should increase to 1 (and then 2).
+        assert_eq!(step(&[2, 0, 0], "B"), vec![true, false]);
+        assert_eq!(step(&[2, 1, 0], "B"), vec![true, true]);
+        // A = 1, B = 2, C = 0: B should decrease to 1.
+        assert_eq!(step(&[1, 2, 0], "B"), vec![true, false]);
+        // C is inhibited by B = 2, otherwise it goes to its base value.
+        assert_eq!(step(&[0, 2, 1], "C"), vec![false]);
+        assert_eq!(step(&[0, 1, 0], "C"), vec![true]);
+        // A is an input.
+        assert_eq!(step(&[1, 0, 0], "A"), vec![true, false]);
+        assert_eq!(b.get_max_level(), 2);
+        assert_eq!(c.encoding().len(), 1);
+
+        let stg = SymbolicAsyncGraph::new(&network).unwrap();
+        let admissible = mapping.mk_admissible_states(&stg);
+        assert_eq!(admissible.approx_cardinality(), 18.0);
+        assert!(stg.can_post_out(&admissible).is_empty());
+    }
+
+    #[test]
+    fn test_read_ginml_invalid() {
+        assert!(BooleanNetwork::try_from_ginml("<gxl></gxl>").is_err());
+        let model = r#"<gxl><graph class="regulatory">
+            <node id="A"><value val="2"><exp str="A"/></value></node>
+        </graph></gxl>"#;
+        assert!(BooleanNetwork::try_from_ginml(model).is_err());
+        let model = r#"<gxl><graph class="regulatory">
+            <node id="A"/><edge from="A" to="B"/>
+        </graph></gxl>"#;
+        assert!(BooleanNetwork::try_from_ginml(model).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{BinaryOp, BooleanNetwork, FnUpdate, VariableId};
+use crate::{BinaryOp, BooleanNetwork, FnUpdate, NaryOp, VariableId};
 use regex::Regex;
 
 impl BooleanNetwork {
@@ -32,7 +32,7 @@ impl BooleanNetwork {
         for v in network.variables() {
             let name = network.get_variable_name(v);
             if let Some(function) = network.get_update_function(v) {
-                let function_string = fn_update_to_bnet_string(v, function, self)?;
+                let function_string = fn_update_to_bnet_string(v, function, self, &BNET_SYNTAX)?;
                 let line = format!("{}, {}\n", name, function_string);
                 model.push_str(line.as_str());
             } else {
@@ -49,50 +49,113 @@ impl BooleanNetwork {
     }
 }
 
-fn fn_update_to_bnet_string(
+/// **(internal)** The syntax of a `.bnet`-like format, as used by [fn_update_to_bnet_string].
+pub(crate) struct BnetSyntax {
+    /// Name of the format (used in error messages).
+    pub format: &'static str,
+    pub not: &'static str,
+    pub and: &'static str,
+    pub or: &'static str,
+    /// The `(false, true)` constants. If not available, constants are simulated using
+    /// the updated variable (e.g. `(x | !x)`).
+    pub constants: Option<(&'static str, &'static str)>,
+    /// Whether parameters without arguments can be written using their names.
+    pub allow_parameters: bool,
+}
+
+/// **(internal)** The syntax of the `.bnet` format.
+pub(crate) const BNET_SYNTAX: BnetSyntax = BnetSyntax {
+    format: ".bnet",
+    not: "!",
+    and: "&",
+    or: "|",
+    constants: None,
+    allow_parameters: true,
+};
+
+/// **(internal)** Convert the update `function` of `var` into an expression of a `.bnet`-like
+/// format. Operators which are not supported by the `syntax` are expanded.
+pub(crate) fn fn_update_to_bnet_string(
     var: VariableId,
     function: &FnUpdate,
     network: &BooleanNetwork,
+    syntax: &BnetSyntax,
 ) -> Result<String, String> {
+    let (not, and, or) = (syntax.not, syntax.and, syntax.or);
     Ok(match function {
         FnUpdate::Var(id) => network.get_variable_name(*id).clone(),
         FnUpdate::Param(id, args) => {
-            if args.is_empty() {
+            if args.is_empty() && syntax.allow_parameters {
                 network.get_parameter(*id).get_name().to_string()
             } else {
-                return Err(
-                    "Networks with free functions cannot be converted to .bnet.".to_string()
-                );
+                return Err(format!(
+                    "Networks with free functions cannot be converted to {}.",
+                    syntax.format
+                ));
             }
         }
         FnUpdate::Const(value) => {
-            // .bnet does not have constants, but we can simulate a constant like this:
-            let name = network.get_variable_name(var);
-            if *value {
-                format!("({} | !{})", name, name)
+            if let Some((false_constant, true_constant)) = syntax.constants {
+                if *value {
+                    true_constant.to_string()
+                } else {
+                    false_constant.to_string()
+                }
             } else {
-                format!("({} & !{})", name, name)
+                // .bnet does not have constants, but we can simulate a constant like this:
+                let name = network.get_variable_name(var);
+                if *value {
+                    format!("({} {} {}{})", name, or, not, name)
+                } else {
+                    format!("({} {} {}{})", name, and, not, name)
+                }
             }
         }
         FnUpdate::Not(inner) => {
-            format!("!{}", fn_update_to_bnet_string(var, inner, network)?)
+            let inner = fn_update_to_bnet_string(var, inner, network, syntax)?;
+            format!("{}{}", not, inner)
         }
         FnUpdate::Binary(op, left, right) => {
-            let left = fn_update_to_bnet_string(var, left, network)?;
-            let right = fn_update_to_bnet_string(var, right, network)?;
+            let l = fn_update_to_bnet_string(var, left, network, syntax)?;
+            let r = fn_update_to_bnet_string(var, right, network, syntax)?;
             match *op {
-                BinaryOp::And => format!("({} & {})", left, right),
-                BinaryOp::Or => format!("({} | {})", left, right),
-                BinaryOp::Imp => format!("(!{} | {})", left, right),
-                BinaryOp::Iff => format!("(({} & {}) | (!{} & !{}))", left, right, left, right),
-                BinaryOp::Xor => format!("(({} & !{}) | (!{} & {}))", left, right, left, right),
+                BinaryOp::And => format!("({} {} {})", l, and, r),
+                BinaryOp::Or => format!("({} {} {})", l, or, r),
+                BinaryOp::Imp => format!("({}{} {} {})", not, l, or, r),
+                BinaryOp::Iff => format!(
+                    "(({} {} {}) {} ({}{} {} {}{}))",
+                    l, and, r, or, not, l, and, not, r
+                ),
+                BinaryOp::Xor => format!(
+                    "(({} {} {}{}) {} ({}{} {} {}))",
+                    l, and, not, r, or, not, l, and, r
+                ),
+            }
+        }
+        FnUpdate::Threshold(_, _) => {
+            fn_update_to_bnet_string(var, &function.expand_thresholds(), network, syntax)?
+        }
+        FnUpdate::Nary(op, args) => {
+            if args.is_empty() {
+                let value = FnUpdate::Const(op.neutral_value());
+                return fn_update_to_bnet_string(var, &value, network, syntax);
             }
+            let args = args
+                .iter()
+                .map(|it| fn_update_to_bnet_string(var, it, network, syntax))
+                .collect::<Result<Vec<_>, _>>()?;
+            let op = match op {
+                NaryOp::And => and,
+                NaryOp::Or => or,
+            };
+            format!("({})", args.join(format!(" {} ", op).as_str()))
         }
     })
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
     use crate::BooleanNetwork;
     use std::convert::TryFrom;
 
@@ -128,6 +191,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_threshold_to_bnet() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> a
+            b -> b
+            a -> c
+            b -> c
+            c -| c
+            $a: a
+            $b: b
+            $c: [a + b - c >= 1]
+        ",
+        )
+        .unwrap();
+        let bnet = bn.to_bnet(false).unwrap();
+        assert!(!bnet.contains('['));
+        let bn_after = BooleanNetwork::try_from_bnet(bnet.as_str()).unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let stg_after = SymbolicAsyncGraph::new(&bn_after).unwrap();
+        assert_eq!(
+            stg.get_symbolic_fn_update(c),
+            stg_after.get_symbolic_fn_update(c)
+        );
+    }
+
     #[test]
     fn test_network_to_bnet_invalid() {
         let bn = BooleanNetwork::try_from("A -> B \n B -> A").unwrap();
@@ -0,0 +1,487 @@
+use crate::symbolic_async_graph::_impl_function_class_constraint::mk_function_class_constraints;
+use crate::symbolic_async_graph::_impl_function_value_constraint::mk_function_value_constraints;
+use crate::symbolic_async_graph::_impl_regulation_constraint::mk_regulation_static_constraints;
+use crate::symbolic_async_graph::{FunctionTable, SymbolicAsyncGraph, SymbolicContext};
+use crate::trap_spaces::SymbolicSpaceContext;
+use crate::{BinaryOp, BooleanNetwork, FnUpdate, NaryOp, SolverEncoding};
+use biodivine_lib_bdd::{Bdd, BddVariable};
+use std::collections::HashMap;
+
+/// **(internal)** Incrementally builds a CNF formula over the symbolic variables
+/// of a `SymbolicContext` (plus auxiliary Tseitin variables).
+struct CnfBuilder<'a> {
+    context: &'a SymbolicContext,
+    literals: HashMap<BddVariable, i64>,
+    names: Vec<(i64, String)>,
+    clauses: Vec<Vec<i64>>,
+    var_count: i64,
+    true_literal: Option<i64>,
+}
+
+impl<'a> CnfBuilder<'a> {
+    fn new(context: &'a SymbolicContext) -> CnfBuilder<'a> {
+        CnfBuilder {
+            context,
+            literals: HashMap::new(),
+            names: Vec::new(),
+            clauses: Vec::new(),
+            var_count: 0,
+            true_literal: None,
+        }
+    }
+
+    /// Create a fresh auxiliary variable.
+    fn fresh(&mut self) -> i64 {
+        self.var_count += 1;
+        self.var_count
+    }
+
+    /// Register a named DIMACS variable for the given symbolic variable.
+    fn declare(&mut self, var: BddVariable, name: String) -> i64 {
+        let literal = self.fresh();
+        self.literals.insert(var, literal);
+        self.names.push((literal, name));
+        literal
+    }
+
+    /// Get the DIMACS variable of a symbolic variable (declaring it if needed).
+    fn literal(&mut self, var: BddVariable) -> i64 {
+        if let Some(literal) = self.literals.get(&var) {
+            *literal
+        } else {
+            let name = self.context.bdd_variable_set().name_of(var);
+            self.declare(var, name)
+        }
+    }
+
+    /// Add clauses which are equivalent to the given `Bdd`.
+    fn add_bdd(&mut self, bdd: &Bdd) {
+        // Each conjunction in the DNF of the negation is one blocking clause.
+        for conjunction in bdd.not().to_optimized_dnf() {
+            let clause = conjunction
+                .to_values()
+                .into_iter()
+                .map(|(var, value)| {
+                    let literal = self.literal(var);
+                    if value {
+                        -literal
+                    } else {
+                        literal
+                    }
+                })
+                .collect();
+            self.clauses.push(clause);
+        }
+    }
+
+    /// Add a Tseitin encoding of the given update function, returning a literal which is
+    /// equivalent to the function.
+    fn add_fn_update(&mut self, function: &FnUpdate) -> i64 {
+        match function {
+            FnUpdate::Const(value) => self.add_constant(*value),
+            FnUpdate::Var(id) => self.literal(self.context.get_state_variable(*id)),
+            FnUpdate::Param(id, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| self.add_fn_update(it))
+                    .collect::<Vec<_>>();
+                let table = self.context.get_explicit_function_table(*id);
+                self.add_function_table(table, &args)
+            }
+            FnUpdate::Not(inner) => -self.add_fn_update(inner),
+            FnUpdate::Binary(op, left, right) => {
+                let left = self.add_fn_update(left);
+                let right = self.add_fn_update(right);
+                let output = self.fresh();
+                match op {
+                    BinaryOp::And => self.add_and(output, &[left, right]),
+                    BinaryOp::Or => self.add_and(-output, &[-left, -right]),
+                    BinaryOp::Imp => self.add_and(-output, &[left, -right]),
+                    BinaryOp::Iff => self.add_iff(output, left, right),
+                    BinaryOp::Xor => self.add_iff(-output, left, right),
+                }
+                output
+            }
+            FnUpdate::Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(weight, arg)| (i64::from(*weight), self.add_fn_update(arg)))
+                    .collect::<Vec<_>>();
+                // The smallest and largest sum that can be achieved by each suffix of `args`.
+                let mut bounds = vec![(0i64, 0i64); args.len() + 1];
+                for (i, (weight, _)) in args.iter().enumerate().rev() {
+                    let (min, max) = bounds[i + 1];
+                    bounds[i] = (min + (*weight).min(0), max + (*weight).max(0));
+                }
+                let mut cache = HashMap::new();
+                self.add_threshold(&args, &bounds, (0, i64::from(*threshold)), &mut cache)
+            }
+            FnUpdate::Nary(op, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| self.add_fn_update(it))
+                    .collect::<Vec<_>>();
+                let output = self.fresh();
+                match op {
+                    NaryOp::And => self.add_and(output, &args),
+                    NaryOp::Or => {
+                        let negated = args.iter().map(|it| -it).collect::<Vec<_>>();
+                        self.add_and(-output, &negated)
+                    }
+                }
+                output
+            }
+        }
+    }
+
+    /// Get a literal which is equivalent to the given constant.
+    fn add_constant(&mut self, value: bool) -> i64 {
+        let literal = if let Some(literal) = self.true_literal {
+            literal
+        } else {
+            let literal = self.fresh();
+            self.clauses.push(vec![literal]);
+            self.true_literal = Some(literal);
+            literal
+        };
+        if value {
+            literal
+        } else {
+            -literal
+        }
+    }
+
+    /// Add a counter encoding of a threshold operator, returning a literal which is true when
+    /// the weights of the true `args` (starting at `index`) sum to at least `threshold`.
+    ///
+    /// Every `(index, threshold)` state of the counter is encoded only once (using `cache`),
+    /// hence the encoding is polynomial in the number of distinct partial sums. The `bounds`
+    /// contain the smallest and largest achievable sum of each suffix of `args`.
+    fn add_threshold(
+        &mut self,
+        args: &[(i64, i64)],
+        bounds: &[(i64, i64)],
+        state: (usize, i64),
+        cache: &mut HashMap<(usize, i64), i64>,
+    ) -> i64 {
+        let (index, threshold) = state;
+        let (min, max) = bounds[index];
+        if min >= threshold {
+            return self.add_constant(true);
+        }
+        if max < threshold {
+            return self.add_constant(false);
+        }
+        if let Some(literal) = cache.get(&state) {
+            return *literal;
+        }
+        // Otherwise, there is at least one remaining argument with a non-zero weight.
+        let (weight, arg) = args[index];
+        let high = self.add_threshold(args, bounds, (index + 1, threshold - weight), cache);
+        let low = self.add_threshold(args, bounds, (index + 1, threshold), cache);
+        let output = self.fresh();
+        // output <=> (arg ? high : low)
+        self.clauses.push(vec![-output, -arg, high]);
+        self.clauses.push(vec![-output, arg, low]);
+        self.clauses.push(vec![output, -arg, -high]);
+        self.clauses.push(vec![output, arg, -low]);
+        cache.insert(state, output);
+        output
+    }
+
+    /// Add clauses for `output <=> (inputs[0] & ... & inputs[k])`.
+    fn add_and(&mut self, output: i64, inputs: &[i64]) {
+        for input in inputs {
+            self.clauses.push(vec![-output, *input]);
+        }
+        let mut clause = vec![output];
+        clause.extend(inputs.iter().map(|it| -it));
+        self.clauses.push(clause);
+    }
+
+    /// Add clauses for `output <=> (left <=> right)`.
+    fn add_iff(&mut self, output: i64, left: i64, right: i64) {
+        self.clauses.push(vec![-output, -left, right]);
+        self.clauses.push(vec![-output, left, -right]);
+        self.clauses.push(vec![output, left, right]);
+        self.clauses.push(vec![output, -left, -right]);
+    }
+
+    /// Add a Tseitin encoding of a function table applied to the given argument literals,
+    /// returning a literal which is equivalent to the function value.
+    fn add_function_table(&mut self, table: &FunctionTable, args: &[i64]) -> i64 {
+        if args.is_empty() {
+            let (_, var) = table.into_iter().next().unwrap();
+            return self.literal(var);
+        }
+        let output = self.fresh();
+        for (row, var) in table {
+            let row_literal = self.literal(var);
+            // If the arguments match this row, the output is equal to the row variable.
+            let mut clause = row
+                .iter()
+                .zip(args)
+                .map(|(value, arg)| if *value { -*arg } else { *arg })
+                .collect::<Vec<_>>();
+            clause.push(-row_literal);
+            clause.push(output);
+            self.clauses.push(clause.clone());
+            let length = clause.len();
+            clause[length - 2] = row_literal;
+            clause[length - 1] = -output;
+            self.clauses.push(clause);
+        }
+        output
+    }
+
+    /// Write the formula in the DIMACS format, including a comment line `c var <index> <name>`
+    /// for every named variable.
+    fn to_dimacs(&self, description: &str) -> String {
+        let mut result = format!("c {}\n", description);
+        for (literal, name) in &self.names {
+            result.push_str(format!("c var {} {}\n", literal, name).as_str());
+        }
+        result.push_str(format!("p cnf {} {}\n", self.var_count, self.clauses.len()).as_str());
+        for clause in &self.clauses {
+            for literal in clause {
+                result.push_str(format!("{} ", literal).as_str());
+            }
+            result.push_str("0\n");
+        }
+        result
+    }
+}
+
+impl BooleanNetwork {
+    /// Produce a CNF formula in the DIMACS format which encodes the given problem for this
+    /// network.
+    ///
+    /// Network parameters are represented by the rows of their function tables (named the same
+    /// way as the symbolic variables of a `SymbolicContext`), and the names of all such "input"
+    /// variables are listed in comments of the form `c var <index> <name>`. The remaining
+    /// variables are auxiliary and are uniquely determined by the input variables.
+    ///
+    /// Fixed points use one variable per network variable and a Tseitin encoding of each update
+    /// function. Trap spaces use variables `_space_1_x` and `_space_0_x` (true when the space
+    /// admits the respective value of `x`), and the local trap condition of each update
+    /// function is translated into clauses through its symbolic representation.
+    ///
+    /// Returns an error if the network cannot be represented symbolically (e.g. it contains
+    /// unused parameters).
+    pub fn to_dimacs(&self, encoding: SolverEncoding) -> Result<String, String> {
+        let description = format!("{:?} of a Boolean network.", encoding);
+        let context = SymbolicContext::new(self)?;
+        match encoding {
+            SolverEncoding::ParameterConstraints => {
+                let mut cnf = CnfBuilder::new(&context);
+                self.add_parameter_constraints(&mut cnf)?;
+                Ok(cnf.to_dimacs(&description))
+            }
+            SolverEncoding::FixedPoints => {
+                let mut cnf = CnfBuilder::new(&context);
+                for var in self.variables() {
+                    cnf.literal(context.get_state_variable(var));
+                }
+                self.add_parameter_constraints(&mut cnf)?;
+                for var in self.variables() {
+                    let update = if let Some(function) = self.get_update_function(var) {
+                        cnf.add_fn_update(function)
+                    } else {
+                        let args = self
+                            .regulators(var)
+                            .into_iter()
+                            .map(|it| cnf.literal(context.get_state_variable(it)))
+                            .collect::<Vec<_>>();
+                        let table = context.get_implicit_function_table(var).unwrap();
+                        cnf.add_function_table(table, &args)
+                    };
+                    let state = cnf.literal(context.get_state_variable(var));
+                    cnf.clauses.push(vec![-state, update]);
+                    cnf.clauses.push(vec![state, -update]);
+                }
+                Ok(cnf.to_dimacs(&description))
+            }
+            SolverEncoding::TrapSpaces => {
+                let space_context = SymbolicSpaceContext::new(self);
+                let graph = SymbolicAsyncGraph::with_space_context(self, &space_context)?;
+                let mut cnf = CnfBuilder::new(space_context.inner_context());
+                for var in self.variables() {
+                    let name = self.get_variable_name(var);
+                    let one = space_context.get_positive_variable(var);
+                    let zero = space_context.get_negative_variable(var);
+                    let one = cnf.declare(one, format!("_space_1_{}", name));
+                    let zero = cnf.declare(zero, format!("_space_0_{}", name));
+                    cnf.clauses.push(vec![one, zero]);
+                }
+                self.add_parameter_constraints(&mut cnf)?;
+                let bdd_vars = space_context.bdd_variable_set();
+                for var in self.variables() {
+                    let update = graph.get_symbolic_fn_update(var);
+                    let can_go_up = space_context.mk_can_go_to_true(update);
+                    let can_go_down = space_context.mk_can_go_to_true(&update.not());
+                    let one = bdd_vars.mk_var(space_context.get_positive_variable(var));
+                    let zero = bdd_vars.mk_var(space_context.get_negative_variable(var));
+                    let is_trap = can_go_up.imp(&one).and(&can_go_down.imp(&zero));
+                    cnf.add_bdd(&is_trap);
+                }
+                Ok(cnf.to_dimacs(&description))
+            }
+        }
+    }
+
+    /// **(internal)** Declare all parameter variables of the builder's context and add
+    /// the static constraints of all regulations and function classes.
+    fn add_parameter_constraints(&self, cnf: &mut CnfBuilder) -> Result<(), String> {
+        let context = cnf.context;
+        for var in context.parameter_variables() {
+            cnf.literal(*var);
+        }
+        for (_, constraint) in mk_regulation_static_constraints(self, context) {
+            cnf.add_bdd(&constraint);
+        }
+        for (_, constraint) in mk_function_class_constraints(self, context)? {
+            cnf.add_bdd(&constraint);
+        }
+        for (_, constraint) in mk_function_value_constraints(self, context)? {
+            cnf.add_bdd(&constraint);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::fixed_points::FixedPoints;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::{BooleanNetwork, SolverEncoding};
+    use num_bigint::BigInt;
+    use std::convert::TryFrom;
+
+    /// Count the models of a (small) DIMACS formula by brute force.
+    fn count_models(dimacs: &str) -> usize {
+        let mut var_count = 0;
+        let mut clauses = Vec::new();
+        for line in dimacs.lines() {
+            if line.starts_with("c ") {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix("p cnf ") {
+                var_count = header.split(' ').next().unwrap().parse::<usize>().unwrap();
+                continue;
+            }
+            let clause = line
+                .split(' ')
+                .map(|it| it.parse::<i64>().unwrap())
+                .filter(|it| *it != 0)
+                .collect::<Vec<_>>();
+            clauses.push(clause);
+        }
+        assert!(var_count <= 20);
+        (0..(1u32 << var_count))
+            .filter(|valuation| {
+                clauses.iter().all(|clause| {
+                    clause.iter().any(|literal| {
+                        let value = valuation & (1 << (literal.unsigned_abs() - 1)) != 0;
+                        value == (*literal > 0)
+                    })
+                })
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_dimacs_fixed_points() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| a
+            a -? a
+            $a: f(a) & !b
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+
+        let dimacs = bn.to_dimacs(SolverEncoding::ParameterConstraints).unwrap();
+        assert!(dimacs.starts_with("c ParameterConstraints of a Boolean network.\n"));
+        let colors = stg.unit_colors().exact_cardinality();
+        assert_eq!(BigInt::from(count_models(&dimacs)), colors);
+
+        let dimacs = bn.to_dimacs(SolverEncoding::FixedPoints).unwrap();
+        assert!(dimacs.contains("c var 1 a\nc var 2 b\n"));
+        let fixed_points = FixedPoints::symbolic(&stg, stg.unit_colored_vertices());
+        assert!(!fixed_points.is_empty());
+        assert_eq!(
+            BigInt::from(count_models(&dimacs)),
+            fixed_points.exact_cardinality()
+        );
+
+        // Unused parameters cannot be encoded.
+        let mut invalid = bn.clone();
+        invalid.add_parameter("g", 1).unwrap();
+        assert!(invalid.to_dimacs(SolverEncoding::FixedPoints).is_err());
+    }
+
+    #[test]
+    fn test_dimacs_thresholds() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> a
+            b -| a
+            c -> a
+            a -> b
+            c -| b
+            b -| c
+            $a: [2 * a - b + c >= 1]
+            $b: [a - 3 * c >= -2]
+            $c: [-b >= 0]
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let dimacs = bn.to_dimacs(SolverEncoding::FixedPoints).unwrap();
+        let fixed_points = FixedPoints::symbolic(&stg, stg.unit_colored_vertices());
+        assert!(!fixed_points.is_empty());
+        assert_eq!(
+            BigInt::from(count_models(&dimacs)),
+            fixed_points.exact_cardinality()
+        );
+    }
+
+    #[test]
+    fn test_dimacs_trap_spaces() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -| b
+            b -| a
+            b -> c
+            c -> c
+            $a: !b
+            $b: !a
+            $c: b | c
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let dimacs = bn.to_dimacs(SolverEncoding::TrapSpaces).unwrap();
+        assert!(dimacs.contains("c var 1 _space_1_a\nc var 2 _space_0_a\n"));
+
+        // Enumerate all subspaces and check which of them are traps.
+        let mut expected = 0;
+        for i in 0..27 {
+            let values = (0..3)
+                .zip(bn.variables())
+                .filter_map(|(k, var)| match (i / 3usize.pow(k)) % 3 {
+                    0 => Some((var, false)),
+                    1 => Some((var, true)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if stg.is_trap_set(&stg.mk_subspace(&values)) {
+                expected += 1;
+            }
+        }
+        assert_eq!(count_models(&dimacs), expected);
+    }
+}
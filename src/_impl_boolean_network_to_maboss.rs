@@ -0,0 +1,391 @@
+use crate::{BinaryOp, BooleanNetwork, FnUpdate, MaBossSettings, ModelAnnotation, VariableId};
+use std::collections::HashMap;
+
+/// **(internal)** Words which have a special meaning in MaBoSS and cannot be used as node names.
+const MABOSS_KEYWORDS: [&str; 8] = [
+    "Node",
+    "node",
+    "logic",
+    "rate_up",
+    "rate_down",
+    "AND",
+    "OR",
+    "NOT",
+];
+
+impl Default for MaBossSettings {
+    fn default() -> Self {
+        MaBossSettings::new()
+    }
+}
+
+impl MaBossSettings {
+    /// Create new settings where all rates are `1.0`, every variable is initially active
+    /// with probability `0.5`, and the simulation uses `time_tick = 0.5`, `max_time = 100`
+    /// and `sample_count = 10000` in continuous time.
+    pub fn new() -> MaBossSettings {
+        MaBossSettings {
+            default_rates: (1.0, 1.0),
+            rates: HashMap::new(),
+            initial_states: HashMap::new(),
+            time_tick: 0.5,
+            max_time: 100.0,
+            sample_count: 10_000,
+            discrete_time: false,
+        }
+    }
+
+    /// Read the settings from a `ModelAnnotation` of the given `network`. Values which are
+    /// not present in the annotation keep their defaults (see [MaBossSettings::new]).
+    ///
+    /// The supported annotations are (with values being numbers):
+    ///  - `maboss:rate_up` and `maboss:rate_down` set the default rates;
+    ///  - `maboss:rate_up:<variable>` and `maboss:rate_down:<variable>` set variable rates;
+    ///  - `maboss:istate:<variable>` sets the initial probability that a variable is active;
+    ///  - `maboss:time_tick`, `maboss:max_time` and `maboss:sample_count` set the simulation
+    ///    parameters, and `maboss:discrete_time` can be set to `1` (or `true`).
+    ///
+    /// Returns an error if a value is not a valid number, or if a variable does not exist.
+    pub fn from_annotation(
+        network: &BooleanNetwork,
+        annotation: &ModelAnnotation,
+    ) -> Result<MaBossSettings, String> {
+        let mut settings = MaBossSettings::new();
+        let maboss = if let Some(maboss) = annotation.get_child(&["maboss"]) {
+            maboss
+        } else {
+            return Ok(settings);
+        };
+
+        let number = |annotation: &ModelAnnotation, key: &str| -> Result<Option<f64>, String> {
+            if let Some(value) = annotation.value() {
+                let value = value.trim();
+                value
+                    .parse::<f64>()
+                    .map(Some)
+                    .map_err(|_| format!("Invalid number `{}` in `maboss:{}`.", value, key))
+            } else {
+                Ok(None)
+            }
+        };
+        let find_variable = |name: &str| {
+            network
+                .as_graph()
+                .find_variable(name)
+                .ok_or_else(|| format!("Unknown variable `{}` in MaBoSS annotation.", name))
+        };
+
+        let rate_up = maboss.get_child(&["rate_up"]);
+        let rate_down = maboss.get_child(&["rate_down"]);
+        if let Some(rate) = rate_up
+            .map(|it| number(it, "rate_up"))
+            .transpose()?
+            .flatten()
+        {
+            settings.default_rates.0 = rate;
+        }
+        if let Some(rate) = rate_down
+            .map(|it| number(it, "rate_down"))
+            .transpose()?
+            .flatten()
+        {
+            settings.default_rates.1 = rate;
+        }
+        let mut rates: HashMap<VariableId, (Option<f64>, Option<f64>)> = HashMap::new();
+        if let Some(rate_up) = rate_up {
+            for (name, child) in rate_up.children() {
+                let var = find_variable(name)?;
+                rates.entry(var).or_default().0 = number(child, "rate_up")?;
+            }
+        }
+        if let Some(rate_down) = rate_down {
+            for (name, child) in rate_down.children() {
+                let var = find_variable(name)?;
+                rates.entry(var).or_default().1 = number(child, "rate_down")?;
+            }
+        }
+        for (var, (up, down)) in rates {
+            let up = up.unwrap_or(settings.default_rates.0);
+            let down = down.unwrap_or(settings.default_rates.1);
+            settings.set_rates(var, up, down);
+        }
+        if let Some(istate) = maboss.get_child(&["istate"]) {
+            for (name, child) in istate.children() {
+                let var = find_variable(name)?;
+                if let Some(probability) = number(child, "istate")? {
+                    settings.set_initial_probability(var, probability)?;
+                }
+            }
+        }
+
+        if let Some(child) = maboss.get_child(&["time_tick"]) {
+            if let Some(value) = number(child, "time_tick")? {
+                settings.set_time_tick(value);
+            }
+        }
+        if let Some(child) = maboss.get_child(&["max_time"]) {
+            if let Some(value) = number(child, "max_time")? {
+                settings.set_max_time(value);
+            }
+        }
+        if let Some(child) = maboss.get_child(&["sample_count"]) {
+            if let Some(value) = number(child, "sample_count")? {
+                settings.set_sample_count(value as u64);
+            }
+        }
+        if let Some(value) = maboss.get_value(&["discrete_time"]) {
+            settings.set_discrete_time(matches!(value.trim(), "1" | "true"));
+        }
+
+        Ok(settings)
+    }
+
+    /// Set the default activation and deactivation rates of all variables.
+    pub fn set_default_rates(&mut self, rate_up: f64, rate_down: f64) {
+        self.default_rates = (rate_up, rate_down);
+    }
+
+    /// Set the activation and deactivation rates of a single variable.
+    pub fn set_rates(&mut self, variable: VariableId, rate_up: f64, rate_down: f64) {
+        self.rates.insert(variable, (rate_up, rate_down));
+    }
+
+    /// Get the activation and deactivation rates of a variable.
+    pub fn get_rates(&self, variable: VariableId) -> (f64, f64) {
+        self.rates
+            .get(&variable)
+            .cloned()
+            .unwrap_or(self.default_rates)
+    }
+
+    /// Set the probability that the `variable` is initially active. Returns an error if
+    /// the probability is not within `[0, 1]`.
+    pub fn set_initial_probability(
+        &mut self,
+        variable: VariableId,
+        probability: f64,
+    ) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(format!("Invalid initial probability {}.", probability));
+        }
+        self.initial_states.insert(variable, probability);
+        Ok(())
+    }
+
+    /// Get the probability that the `variable` is initially active.
+    pub fn get_initial_probability(&self, variable: VariableId) -> f64 {
+        self.initial_states.get(&variable).cloned().unwrap_or(0.5)
+    }
+
+    /// Set the time step used to compute the MaBoSS trajectory statistics.
+    pub fn set_time_tick(&mut self, time_tick: f64) {
+        self.time_tick = time_tick;
+    }
+
+    /// Set the maximal simulation time.
+    pub fn set_max_time(&mut self, max_time: f64) {
+        self.max_time = max_time;
+    }
+
+    /// Set the number of simulated trajectories.
+    pub fn set_sample_count(&mut self, sample_count: u64) {
+        self.sample_count = sample_count;
+    }
+
+    /// Use discrete instead of continuous time.
+    pub fn set_discrete_time(&mut self, discrete_time: bool) {
+        self.discrete_time = discrete_time;
+    }
+}
+
+impl BooleanNetwork {
+    /// Produce a MaBoSS model of this network, returned as the contents of the `.bnd`
+    /// (network) and `.cfg` (configuration) files.
+    ///
+    /// Each node is activated with rate `$u_<name>` when its logic is true, and deactivated
+    /// with rate `$d_<name>` otherwise. The values of these rates, the initial state
+    /// distribution and the simulation parameters are written into the configuration based
+    /// on the given `settings`. Variables without regulators and without an update function
+    /// keep their initial value.
+    ///
+    /// The network must be fully specified. To export one instantiation of a parametrised
+    /// network, use e.g. [crate::symbolic_async_graph::SymbolicAsyncGraph::pick_witness]
+    /// first. Names which cannot be used in MaBoSS are prefixed with `_`.
+    pub fn to_maboss(&self, settings: &MaBossSettings) -> Result<(String, String), String> {
+        let names = self
+            .variables()
+            .map(|var| {
+                let name = self.get_variable_name(var);
+                if name.starts_with(|c: char| c.is_ascii_digit())
+                    || MABOSS_KEYWORDS.contains(&name.as_str())
+                {
+                    format!("_{}", name)
+                } else {
+                    name.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                return Err(format!("Duplicate MaBoSS node name `{}`.", name));
+            }
+        }
+
+        let mut bnd = String::new();
+        let mut cfg = String::new();
+        for var in self.variables() {
+            let name = &names[var.to_index()];
+            let logic = match self.get_update_function(var) {
+                Some(function) => fn_update_to_maboss_string(function, &names)?,
+                None if self.regulators(var).is_empty() => name.clone(),
+                None => {
+                    return Err("Parametrised network cannot be converted to MaBoSS.".to_string());
+                }
+            };
+            bnd.push_str(format!("Node {} {{\n", name).as_str());
+            bnd.push_str(format!("  logic = {};\n", logic).as_str());
+            bnd.push_str(format!("  rate_up = @logic ? $u_{} : 0;\n", name).as_str());
+            bnd.push_str(format!("  rate_down = @logic ? 0 : $d_{};\n", name).as_str());
+            bnd.push_str("}\n\n");
+
+            let (rate_up, rate_down) = settings.get_rates(var);
+            cfg.push_str(format!("$u_{} = {};\n", name, rate_up).as_str());
+            cfg.push_str(format!("$d_{} = {};\n", name, rate_down).as_str());
+        }
+        cfg.push('\n');
+        for var in self.variables() {
+            let probability = settings.get_initial_probability(var);
+            cfg.push_str(
+                format!(
+                    "{}.istate = {} [1], {} [0];\n",
+                    names[var.to_index()],
+                    probability,
+                    1.0 - probability
+                )
+                .as_str(),
+            );
+        }
+        cfg.push('\n');
+        cfg.push_str(format!("time_tick = {};\n", settings.time_tick).as_str());
+        cfg.push_str(format!("max_time = {};\n", settings.max_time).as_str());
+        cfg.push_str(format!("sample_count = {};\n", settings.sample_count).as_str());
+        cfg.push_str(format!("discrete_time = {};\n", u8::from(settings.discrete_time)).as_str());
+
+        Ok((bnd, cfg))
+    }
+}
+
+/// **(internal)** Convert a `FnUpdate` to a MaBoSS logical expression.
+fn fn_update_to_maboss_string(function: &FnUpdate, names: &[String]) -> Result<String, String> {
+    Ok(match function {
+        FnUpdate::Const(value) => if *value { "1" } else { "0" }.to_string(),
+        FnUpdate::Var(id) => names[id.to_index()].clone(),
+        FnUpdate::Param(_, _) => {
+            return Err("Parametrised network cannot be converted to MaBoSS.".to_string());
+        }
+        FnUpdate::Not(inner) => format!("!{}", fn_update_to_maboss_string(inner, names)?),
+        FnUpdate::Binary(op, left, right) => {
+            let left = fn_update_to_maboss_string(left, names)?;
+            let right = fn_update_to_maboss_string(right, names)?;
+            match op {
+                BinaryOp::And => format!("({} & {})", left, right),
+                BinaryOp::Or => format!("({} | {})", left, right),
+                BinaryOp::Xor => format!("({} ^ {})", left, right),
+                BinaryOp::Imp => format!("(!{} | {})", left, right),
+                BinaryOp::Iff => format!("!({} ^ {})", left, right),
+            }
+        }
+        FnUpdate::Threshold(_, _) => {
+            fn_update_to_maboss_string(&function.expand_thresholds(), names)?
+        }
+        FnUpdate::Nary(op, args) => {
+            if args.is_empty() {
+                return fn_update_to_maboss_string(&FnUpdate::Const(op.neutral_value()), names);
+            }
+            let args = args
+                .iter()
+                .map(|it| fn_update_to_maboss_string(it, names))
+                .collect::<Result<Vec<_>, _>>()?;
+            format!("({})", args.join(format!(" {} ", op).as_str()))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::{BooleanNetwork, MaBossSettings, ModelAnnotation};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_network_to_maboss() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| c
+            c -? a
+            $a: c
+            $b: a => true
+            $c: !b
+            $d: true
+        ",
+        )
+        .unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let mut settings = MaBossSettings::new();
+        settings.set_rates(a, 2.0, 0.5);
+        settings.set_initial_probability(a, 1.0).unwrap();
+        assert!(settings.set_initial_probability(a, 1.5).is_err());
+        settings.set_max_time(10.0);
+
+        let (bnd, cfg) = bn.to_maboss(&settings).unwrap();
+        assert!(bnd.contains("Node c {\n  logic = !b;\n"));
+        assert!(bnd.contains("  rate_up = @logic ? $u_b : 0;\n"));
+        assert!(bnd.contains("  logic = 1;\n"));
+        assert!(cfg.contains("$u_a = 2;\n$d_a = 0.5;\n"));
+        assert!(cfg.contains("$u_b = 1;\n"));
+        assert!(cfg.contains("a.istate = 1 [1], 0 [0];\n"));
+        assert!(cfg.contains("b.istate = 0.5 [1], 0.5 [0];\n"));
+        assert!(cfg.contains("max_time = 10;\n"));
+
+        // Parametrised networks must be instantiated first.
+        let bn = BooleanNetwork::try_from("a -> b \n b -| a \n $a: !b").unwrap();
+        assert!(bn.to_maboss(&settings).is_err());
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let colors = stg.mk_unit_colors();
+        assert!(!colors.is_empty());
+        let witness = stg.pick_witness(&colors);
+        let (bnd, _) = witness.to_maboss(&MaBossSettings::default()).unwrap();
+        assert!(bnd.contains("Node b {\n  logic = a;\n"));
+    }
+
+    #[test]
+    fn test_maboss_settings_from_annotation() {
+        let bn = BooleanNetwork::try_from("a -> b \n $b: a").unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let annotation = ModelAnnotation::from_model_string(
+            r"
+            #! maboss:rate_up: 3
+            #! maboss:rate_down:b: 0.25
+            #! maboss:istate:a: 0.1
+            #! maboss:sample_count: 500
+            #! maboss:discrete_time: true
+        ",
+        );
+        let settings = MaBossSettings::from_annotation(&bn, &annotation).unwrap();
+        assert_eq!(settings.get_rates(a), (3.0, 1.0));
+        assert_eq!(settings.get_rates(b), (3.0, 0.25));
+        assert_eq!(settings.get_initial_probability(a), 0.1);
+        assert_eq!(settings.get_initial_probability(b), 0.5);
+        let (_, cfg) = bn.to_maboss(&settings).unwrap();
+        assert!(cfg.contains("sample_count = 500;\n"));
+        assert!(cfg.contains("discrete_time = 1;\n"));
+
+        let annotation = ModelAnnotation::from_model_string("#! maboss:rate_up:x: 1");
+        assert!(MaBossSettings::from_annotation(&bn, &annotation).is_err());
+        let annotation = ModelAnnotation::from_model_string("#! maboss:rate_up: fast");
+        assert!(MaBossSettings::from_annotation(&bn, &annotation).is_err());
+    }
+}
@@ -0,0 +1,345 @@
+use crate::symbolic_async_graph::_impl_function_class_constraint::mk_function_class_constraints;
+use crate::symbolic_async_graph::_impl_function_value_constraint::mk_function_value_constraints;
+use crate::symbolic_async_graph::_impl_regulation_constraint::{
+    apply_regulation_constraints, mk_regulation_static_constraints,
+};
+use crate::symbolic_async_graph::{FunctionTable, SymbolicContext};
+use crate::{BinaryOp, BooleanNetwork, FnUpdate};
+use biodivine_lib_bdd::{Bdd, BddVariable};
+use std::collections::{HashMap, HashSet};
+
+/// **(internal)** Keywords of the NuSMV/nuXmv input language which cannot be used
+/// as identifiers.
+const NUSMV_KEYWORDS: &str = "MODULE DEFINE MDEFINE CONSTANTS VAR IVAR FROZENVAR INIT TRANS \
+    INVAR SPEC CTLSPEC LTLSPEC PSLSPEC COMPUTE NAME INVARSPEC FAIRNESS JUSTICE COMPASSION ISA \
+    ASSIGN CONSTRAINT SIMPWFF CTLWFF LTLWFF PSLWFF COMPWFF IN MIN MAX MIRROR PRED PREDICATES \
+    process array of boolean integer real word word1 bool signed unsigned extend resize sizeof \
+    uwconst swconst EX AX EF AF EG AG E F O G H X Y Z A U S V T BU EBF ABF EBG ABG case esac \
+    mod next init union in xor xnor self TRUE FALSE count abs";
+
+/// **(internal)** Assigns unique NuSMV identifiers to the symbols of the exported model.
+struct NuSmvNames {
+    used: HashSet<String>,
+}
+
+impl NuSmvNames {
+    /// Make a valid identifier from `name` which is distinct from all previously
+    /// created identifiers.
+    fn make(&mut self, name: &str) -> String {
+        let mut name = name
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect::<String>();
+        if name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || NUSMV_KEYWORDS.split_whitespace().any(|it| it == name)
+        {
+            name = format!("_{}", name);
+        }
+        while self.used.contains(&name) {
+            name.push('_');
+        }
+        self.used.insert(name.clone());
+        name
+    }
+}
+
+impl BooleanNetwork {
+    /// Produce a NuSMV (or nuXmv) model of this network.
+    ///
+    /// By default, the model uses the asynchronous semantics: in every step, an input
+    /// variable `update` (with values `0..n-1`) selects the network variable which is updated.
+    /// If `synchronous` is set, all variables are updated at the same time instead. The initial
+    /// state is not constrained.
+    ///
+    /// Uninterpreted functions (explicit parameters and implicit update functions) are
+    /// represented by their function tables: every row of a table becomes a `FROZENVAR`
+    /// Boolean variable (named after the parameter, or `f_<variable>` for implicit functions,
+    /// followed by the row values). The existence of an uncertain regulation from `a` to `b`
+    /// is also a `FROZENVAR` variable, named `exists_a_b`. The admissible instantiations of
+    /// these tables are restricted by one `INIT` constraint per non-trivial static constraint
+    /// (see [crate::symbolic_async_graph::StaticConstraint]), exactly as in the colors of
+    /// a `SymbolicAsyncGraph` (i.e. observability, monotonicity, function classes and known
+    /// values). As such, every valuation of the frozen variables corresponds to one color
+    /// of the network.
+    ///
+    /// Names which are not valid NuSMV identifiers (e.g. keywords such as `A` or `next`) are
+    /// prefixed with `_`. Returns an error if the regulation constraints cannot be satisfied
+    /// (or if the network cannot be symbolically encoded, e.g. due to unused parameters).
+    pub fn to_nusmv(&self, synchronous: bool) -> Result<String, String> {
+        let context = SymbolicContext::new(self)?;
+        apply_regulation_constraints(context.mk_constant(true), self, &context)?;
+
+        let mut names = NuSmvNames {
+            used: HashSet::new(),
+        };
+        let variable_names = self
+            .variables()
+            .map(|var| names.make(self.get_variable_name(var)))
+            .collect::<Vec<_>>();
+
+        // Name every symbolic variable of the function tables.
+        let mut tables: Vec<&FunctionTable> = Vec::new();
+        let mut table_names: Vec<String> = Vec::new();
+        for parameter in self.parameters() {
+            tables.push(context.get_explicit_function_table(parameter));
+            table_names.push(self.get_parameter(parameter).get_name().clone());
+        }
+        for var in self.variables() {
+            if let Some(table) = context.get_implicit_function_table(var) {
+                tables.push(table);
+                table_names.push(format!("f_{}", self.get_variable_name(var)));
+            }
+        }
+        let mut bdd_names: HashMap<BddVariable, String> = HashMap::new();
+        let mut frozen = Vec::new();
+        for (table, name) in tables.iter().zip(&table_names) {
+            for (row, bdd_var) in table.into_iter() {
+                let full_name = if row.is_empty() {
+                    name.clone()
+                } else {
+                    let bits = row
+                        .iter()
+                        .map(|it| if *it { '1' } else { '0' })
+                        .collect::<String>();
+                    format!("{}_{}", name, bits)
+                };
+                let full_name = names.make(full_name.as_str());
+                frozen.push(full_name.clone());
+                bdd_names.insert(bdd_var, full_name);
+            }
+        }
+        for (regulator, target, bdd_var) in context.regulation_variables() {
+            let name = format!(
+                "exists_{}_{}",
+                self.get_variable_name(*regulator),
+                self.get_variable_name(*target)
+            );
+            let name = names.make(name.as_str());
+            frozen.push(name.clone());
+            bdd_names.insert(*bdd_var, name);
+        }
+        for var in self.variables() {
+            bdd_names.insert(
+                context.get_state_variable(var),
+                variable_names[var.to_index()].clone(),
+            );
+        }
+
+        let mut model = "MODULE main\n".to_string();
+        model.push_str("VAR\n");
+        for name in &variable_names {
+            model.push_str(format!("    {} : boolean;\n", name).as_str());
+        }
+        if !frozen.is_empty() {
+            model.push_str("FROZENVAR\n");
+            for name in &frozen {
+                model.push_str(format!("    {} : boolean;\n", name).as_str());
+            }
+        }
+        let update = names.make("update");
+        if !synchronous && self.num_vars() > 0 {
+            model.push_str("IVAR\n");
+            model.push_str(format!("    {} : 0..{};\n", update, self.num_vars() - 1).as_str());
+        }
+
+        // Write the regulation constraints on the function tables.
+        let mut constraints = mk_regulation_static_constraints(self, &context);
+        constraints.extend(mk_function_class_constraints(self, &context)?);
+        constraints.extend(mk_function_value_constraints(self, &context)?);
+        for (constraint, bdd) in constraints {
+            if bdd.is_true() {
+                continue;
+            }
+            model.push_str(format!("-- {}\n", constraint.to_string(self)).as_str());
+            model.push_str(format!("INIT\n    {};\n", bdd_to_nusmv(&bdd, &bdd_names)).as_str());
+        }
+
+        model.push_str("ASSIGN\n");
+        for var in self.variables() {
+            let function = if let Some(function) = self.get_update_function(var) {
+                fn_update_to_nusmv(function, &variable_names, &context, &bdd_names)
+            } else {
+                let regulators = self
+                    .regulators(var)
+                    .into_iter()
+                    .map(FnUpdate::mk_var)
+                    .collect::<Vec<_>>();
+                let table = context.get_implicit_function_table(var).unwrap();
+                function_table_to_nusmv(table, &regulators, &variable_names, &context, &bdd_names)
+            };
+            let name = &variable_names[var.to_index()];
+            if synchronous {
+                model.push_str(format!("    next({}) := {};\n", name, function).as_str());
+            } else {
+                model.push_str(
+                    format!(
+                        "    next({}) := case\n        {} = {} : {};\n        TRUE : {};\n    esac;\n",
+                        name,
+                        update,
+                        var.to_index(),
+                        function,
+                        name
+                    )
+                    .as_str(),
+                );
+            }
+        }
+
+        Ok(model)
+    }
+}
+
+/// **(internal)** Write a `FnUpdate` as a NuSMV expression. Uninterpreted functions are
+/// expanded into their function tables.
+fn fn_update_to_nusmv(
+    function: &FnUpdate,
+    variable_names: &[String],
+    context: &SymbolicContext,
+    bdd_names: &HashMap<BddVariable, String>,
+) -> String {
+    let rec = |it: &FnUpdate| fn_update_to_nusmv(it, variable_names, context, bdd_names);
+    match function {
+        FnUpdate::Const(value) => if *value { "TRUE" } else { "FALSE" }.to_string(),
+        FnUpdate::Var(id) => variable_names[id.to_index()].clone(),
+        FnUpdate::Param(id, args) => {
+            let table = context.get_explicit_function_table(*id);
+            function_table_to_nusmv(table, args, variable_names, context, bdd_names)
+        }
+        FnUpdate::Not(inner) => format!("!{}", rec(inner)),
+        FnUpdate::Binary(op, left, right) => {
+            let op = match op {
+                BinaryOp::And => "&",
+                BinaryOp::Or => "|",
+                BinaryOp::Imp => "->",
+                BinaryOp::Iff => "<->",
+                BinaryOp::Xor => "xor",
+            };
+            format!("({} {} {})", rec(left), op, rec(right))
+        }
+        FnUpdate::Threshold(_, _) => rec(&function.expand_thresholds()),
+        FnUpdate::Nary(op, args) => {
+            if args.is_empty() {
+                return rec(&FnUpdate::Const(op.neutral_value()));
+            }
+            let args = args.iter().map(rec).collect::<Vec<_>>();
+            format!("({})", args.join(format!(" {} ", op).as_str()))
+        }
+    }
+}
+
+/// **(internal)** Write an application of an uninterpreted function (given by its `table`)
+/// to the given `args` as a disjunction over the rows of the table.
+fn function_table_to_nusmv(
+    table: &FunctionTable,
+    args: &[FnUpdate],
+    variable_names: &[String],
+    context: &SymbolicContext,
+    bdd_names: &HashMap<BddVariable, String>,
+) -> String {
+    let args = args
+        .iter()
+        .map(|it| fn_update_to_nusmv(it, variable_names, context, bdd_names))
+        .collect::<Vec<_>>();
+    let rows = table
+        .into_iter()
+        .map(|(row, bdd_var)| {
+            let mut literals = args
+                .iter()
+                .zip(row)
+                .map(|(arg, value)| {
+                    if value {
+                        arg.clone()
+                    } else {
+                        format!("!{}", arg)
+                    }
+                })
+                .collect::<Vec<_>>();
+            literals.push(bdd_names[&bdd_var].clone());
+            literals.join(" & ")
+        })
+        .collect::<Vec<_>>();
+    if rows.len() == 1 {
+        rows[0].clone()
+    } else {
+        format!("(({}))", rows.join(") | ("))
+    }
+}
+
+/// **(internal)** Write a `Bdd` as a NuSMV expression in disjunctive normal form.
+fn bdd_to_nusmv(bdd: &Bdd, bdd_names: &HashMap<BddVariable, String>) -> String {
+    if bdd.is_false() {
+        return "FALSE".to_string();
+    }
+    if bdd.is_true() {
+        return "TRUE".to_string();
+    }
+    let clauses = bdd
+        .to_optimized_dnf()
+        .into_iter()
+        .map(|clause| {
+            let literals = clause
+                .to_values()
+                .into_iter()
+                .map(|(var, value)| {
+                    if value {
+                        bdd_names[&var].clone()
+                    } else {
+                        format!("!{}", bdd_names[&var])
+                    }
+                })
+                .collect::<Vec<_>>();
+            if literals.is_empty() {
+                "TRUE".to_string()
+            } else {
+                format!("({})", literals.join(" & "))
+            }
+        })
+        .collect::<Vec<_>>();
+    clauses.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_network_to_nusmv() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            A -> B
+            B -| A
+            next -> B
+            A -?? next
+            $A: !B
+            $B: f(A, next)
+        ",
+        )
+        .unwrap();
+        let model = bn.to_nusmv(false).unwrap();
+        // Keywords are renamed.
+        assert!(model.contains("    _A : boolean;\n"));
+        assert!(model.contains("    _next : boolean;\n"));
+        assert!(model.contains("    B : boolean;\n"));
+        // Function tables are frozen variables.
+        assert!(model.contains("FROZENVAR\n    f_00 : boolean;\n"));
+        assert!(model.contains("    f_next_1 : boolean;\n"));
+        assert!(model.contains("IVAR\n    update : 0..2;\n"));
+        // Both `A -> B` and `next -> B` constrain `f`, `A -?? next` does not constrain anything.
+        assert!(model.contains("-- A -> B (observability)\nINIT\n"));
+        assert!(model.contains("-- A -> B (monotonicity)\nINIT\n"));
+        assert!(model.contains("-- next -> B (monotonicity)\nINIT\n"));
+        assert!(!model.contains("-- A -?? next"));
+        assert!(model.contains("next(B) := case\n        update = 1 : "));
+        assert!(model.contains("        TRUE : B;\n"));
+
+        let model = bn.to_nusmv(true).unwrap();
+        assert!(!model.contains("IVAR"));
+        assert!(model.contains("    next(_A) := !B;\n"));
+
+        // Unsatisfiable constraints are reported.
+        let bn = BooleanNetwork::try_from("A -> B \n $B: !A").unwrap();
+        assert!(bn.to_nusmv(false).is_err());
+    }
+}
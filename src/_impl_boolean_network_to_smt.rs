@@ -0,0 +1,521 @@
+use crate::_impl_function_values::function_row_to_string;
+use crate::symbolic_async_graph::_impl_function_class_constraint::{
+    function_class_table_size, mk_function_class_rows,
+};
+use crate::{
+    BinaryOp, BooleanNetwork, FnUpdate, Monotonicity, NaryOp, Regulation, SolverEncoding,
+    VariableId,
+};
+use biodivine_lib_bdd::{Bdd, BddPointer, BddVariableSet};
+
+/// **(internal)** Symbols with a special meaning in SMT-LIB2 that cannot be used
+/// as plain identifiers.
+const SMT_RESERVED: &str = "true false not and or xor ite let forall exists distinct par \
+    as match assert declare-fun define-fun check-sat";
+
+impl BooleanNetwork {
+    /// Produce an SMT-LIB2 script which encodes the given problem for this network.
+    ///
+    /// The encoding is the same as the one used by `BnSolverContext` and `BnSolver`: network
+    /// variables are Boolean constants, explicit parameters are uninterpreted functions of
+    /// the same name, and every implicit update function of a variable `x` is an uninterpreted
+    /// function `_update_x`. The existence of an uncertain regulation from `a` to `b` is
+    /// represented by the constant `_exists_a->b`. Regulation constraints use quantifiers,
+    /// so the script uses the `UF` logic (or `UFLIA` if the network contains threshold
+    /// operators, which are encoded using integer sums). Trap spaces are encoded using the constants
+    /// `_space_1_x` and `_space_0_x`, which are true when the space admits the respective
+    /// value of `x`.
+    ///
+    /// The script ends with a `(check-sat)` command.
+    ///
+    /// Returns an error if the network uses a function class that is not supported for
+    /// the arity of the associated function (see [crate::FunctionClass]).
+    pub fn to_smt_lib(&self, encoding: SolverEncoding) -> Result<String, String> {
+        let names = self
+            .variables()
+            .map(|it| smt_symbol(self.get_variable_name(it)))
+            .collect::<Vec<_>>();
+
+        let mut script = format!("; {:?} of a Boolean network.\n", encoding);
+        let mut has_threshold = false;
+        for var in self.variables() {
+            if let Some(function) = self.get_update_function(var) {
+                function.walk_postorder(&mut |it| {
+                    has_threshold = has_threshold || it.as_threshold().is_some();
+                });
+            }
+        }
+        if has_threshold {
+            script.push_str("(set-logic UFLIA)\n");
+        } else {
+            script.push_str("(set-logic UF)\n");
+        }
+
+        if encoding == SolverEncoding::FixedPoints {
+            for name in &names {
+                script.push_str(format!("(declare-fun {} () Bool)\n", name).as_str());
+            }
+        }
+        if encoding == SolverEncoding::TrapSpaces {
+            for var in self.variables() {
+                let (one, zero) = self.space_symbols(var);
+                script.push_str(format!("(declare-fun {} () Bool)\n", one).as_str());
+                script.push_str(format!("(declare-fun {} () Bool)\n", zero).as_str());
+            }
+        }
+        for param in self.parameters() {
+            let param = self.get_parameter(param);
+            let name = smt_symbol(param.get_name());
+            let domain = vec!["Bool"; param.get_arity() as usize].join(" ");
+            script.push_str(format!("(declare-fun {} ({}) Bool)\n", name, domain).as_str());
+        }
+        for var in self.variables() {
+            if self.get_update_function(var).is_none() {
+                let name = implicit_symbol(self, var);
+                let domain = vec!["Bool"; self.regulators(var).len()].join(" ");
+                script.push_str(format!("(declare-fun {} ({}) Bool)\n", name, domain).as_str());
+            }
+        }
+        for regulation in self.as_graph().regulations() {
+            if regulation.is_uncertain() {
+                let name = exists_symbol(self, regulation);
+                script.push_str(format!("(declare-fun {} () Bool)\n", name).as_str());
+            }
+        }
+
+        // Static constraints of regulations.
+        for regulation in self.as_graph().regulations() {
+            let source = regulation.get_regulator();
+            let target = regulation.get_target();
+            let mut positive = names.clone();
+            let mut negative = names.clone();
+            let mut bound = Vec::new();
+            for regulator in self.regulators(target) {
+                if regulator == source {
+                    positive[regulator.to_index()] = "true".to_string();
+                    negative[regulator.to_index()] = "false".to_string();
+                } else {
+                    let name = self.get_variable_name(regulator);
+                    let symbol = smt_symbol(&format!("_b_{}", name));
+                    positive[regulator.to_index()] = symbol.clone();
+                    negative[regulator.to_index()] = symbol.clone();
+                    bound.push(symbol);
+                }
+            }
+            let positive = update_to_smt(self, target, &positive);
+            let negative = update_to_smt(self, target, &negative);
+            let comment = regulation.to_string(self.as_graph());
+            // Constraints of uncertain regulations only apply if the regulation exists.
+            let exists = regulation
+                .is_uncertain()
+                .then(|| exists_symbol(self, regulation));
+            let conditional = |assertion: String| match &exists {
+                Some(exists) => format!("(=> {} {})", exists, assertion),
+                None => assertion,
+            };
+            if let Some(exists) = &exists {
+                let assertion = format!("(= {} {})", positive, negative);
+                let assertion = quantify("forall", &bound, assertion);
+                script.push_str(format!("; {} (existence)\n", comment).as_str());
+                script.push_str(format!("(assert (or {} {}))\n", exists, assertion).as_str());
+            }
+            if regulation.is_observable() {
+                let assertion = format!("(not (= {} {}))", positive, negative);
+                let assertion = quantify("exists", &bound, assertion);
+                script.push_str(format!("; {} (observability)\n", comment).as_str());
+                script.push_str(format!("(assert {})\n", conditional(assertion)).as_str());
+            }
+            let assertion = match regulation.get_monotonicity() {
+                Some(Monotonicity::Activation) => format!("(=> {} {})", negative, positive),
+                Some(Monotonicity::Inhibition) => format!("(=> {} {})", positive, negative),
+                None => continue,
+            };
+            let assertion = quantify("forall", &bound, assertion);
+            script.push_str(format!("; {} (monotonicity)\n", comment).as_str());
+            script.push_str(format!("(assert {})\n", conditional(assertion)).as_str());
+        }
+
+        // Function class constraints.
+        let mut classes = Vec::new();
+        for param in self.parameters() {
+            let symbol = smt_symbol(self.get_parameter(param).get_name());
+            let arity = self.get_parameter(param).get_arity() as usize;
+            for class in self.get_parameter_classes(param) {
+                classes.push((
+                    self.get_parameter(param).get_name().clone(),
+                    symbol.clone(),
+                    arity,
+                    class,
+                ));
+            }
+        }
+        for var in self.implicit_parameters() {
+            let symbol = implicit_symbol(self, var);
+            let arity = self.regulators(var).len();
+            for class in self.get_implicit_classes(var) {
+                classes.push((
+                    self.get_variable_name(var).clone(),
+                    symbol.clone(),
+                    arity,
+                    class,
+                ));
+            }
+        }
+        for (name, symbol, arity, class) in classes {
+            let table_size = function_class_table_size(arity, class)
+                .map_err(|e| format!("Invalid class of `{}`: {}", name, e))?;
+            let variables = BddVariableSet::new_anonymous(table_size);
+            let rows = variables.variables();
+            let constraint = mk_function_class_rows(&variables, &rows, class)?;
+            let rows = (0..rows.len())
+                .map(|row| table_row_to_smt(&symbol, arity, row))
+                .collect::<Vec<_>>();
+            let assertion = bdd_to_smt(&constraint, &rows);
+            script.push_str(format!("; @{}: {}\n", name, class).as_str());
+            script.push_str(format!("(assert {})\n", assertion).as_str());
+        }
+
+        // Known function values.
+        for param in self.parameters() {
+            let name = self.get_parameter(param).get_name();
+            let symbol = smt_symbol(name);
+            for (args, value) in self.get_parameter_values(param) {
+                let row = function_row_to_string(name, &args);
+                let term = function_args_to_smt(&symbol, &args);
+                script.push_str(format!("; @{} = {}\n", row, u8::from(value)).as_str());
+                script.push_str(format!("(assert (= {} {}))\n", term, value).as_str());
+            }
+        }
+        for var in self.implicit_parameters() {
+            let name = self.get_variable_name(var);
+            let symbol = implicit_symbol(self, var);
+            for (args, value) in self.get_implicit_values(var) {
+                let row = function_row_to_string(name, &args);
+                let term = function_args_to_smt(&symbol, &args);
+                script.push_str(format!("; @{} = {}\n", row, u8::from(value)).as_str());
+                script.push_str(format!("(assert (= {} {}))\n", term, value).as_str());
+            }
+        }
+
+        match encoding {
+            SolverEncoding::ParameterConstraints => (),
+            SolverEncoding::FixedPoints => {
+                for var in self.variables() {
+                    let update = update_to_smt(self, var, &names);
+                    let name = &names[var.to_index()];
+                    script.push_str(format!("(assert (= {} {}))\n", name, update).as_str());
+                }
+            }
+            SolverEncoding::TrapSpaces => {
+                for var in self.variables() {
+                    let (one, zero) = self.space_symbols(var);
+                    script.push_str(format!("(assert (or {} {}))\n", one, zero).as_str());
+                }
+                // For every state within the space, the update must stay within the space.
+                let states = self
+                    .variables()
+                    .map(|it| smt_symbol(&format!("_s_{}", self.get_variable_name(it))))
+                    .collect::<Vec<_>>();
+                for var in self.variables() {
+                    let (one, zero) = self.space_symbols(var);
+                    let update = update_to_smt(self, var, &states);
+                    let mut bound = Vec::new();
+                    let mut in_space = Vec::new();
+                    for regulator in self.regulators(var) {
+                        let state = states[regulator.to_index()].clone();
+                        let (r_one, r_zero) = self.space_symbols(regulator);
+                        in_space.push(format!("(=> {} {})", state, r_one));
+                        in_space.push(format!("(=> (not {}) {})", state, r_zero));
+                        bound.push(state);
+                    }
+                    let is_trap = format!(
+                        "(and (=> {} {}) (=> (not {}) {}))",
+                        update, one, update, zero
+                    );
+                    let assertion = if in_space.is_empty() {
+                        is_trap
+                    } else {
+                        format!("(=> (and {}) {})", in_space.join(" "), is_trap)
+                    };
+                    let assertion = quantify("forall", &bound, assertion);
+                    script.push_str(format!("(assert {})\n", assertion).as_str());
+                }
+            }
+        }
+
+        script.push_str("(check-sat)\n");
+        Ok(script)
+    }
+
+    /// **(internal)** SMT-LIB2 symbols of the "can be one" and "can be zero" constants
+    /// used in the trap space encoding.
+    fn space_symbols(&self, var: VariableId) -> (String, String) {
+        let name = self.get_variable_name(var);
+        (
+            smt_symbol(&format!("_space_1_{}", name)),
+            smt_symbol(&format!("_space_0_{}", name)),
+        )
+    }
+}
+
+/// **(internal)** Make a valid SMT-LIB2 symbol from the given `name` (quoting it if necessary).
+fn smt_symbol(name: &str) -> String {
+    let starts_with_digit = name.chars().next().map(|it| it.is_ascii_digit());
+    if starts_with_digit.unwrap_or(true) || SMT_RESERVED.split_whitespace().any(|it| it == name) {
+        format!("|{}|", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// **(internal)** The symbol of the uninterpreted function representing the implicit update
+/// function of `var`.
+fn implicit_symbol(network: &BooleanNetwork, var: VariableId) -> String {
+    smt_symbol(&format!("_update_{}", network.get_variable_name(var)))
+}
+
+/// **(internal)** The symbol of the constant which is true when the uncertain `regulation`
+/// exists. The names are separated by `->`, which cannot appear in a variable name.
+fn exists_symbol(network: &BooleanNetwork, regulation: &Regulation) -> String {
+    let regulator = network.get_variable_name(regulation.get_regulator());
+    let target = network.get_variable_name(regulation.get_target());
+    smt_symbol(&format!("_exists_{}->{}", regulator, target))
+}
+
+/// **(internal)** Write the application of the uninterpreted function `symbol` to the
+/// arguments encoded in the given truth table `row` (see [crate::ParameterInstantiation]).
+fn table_row_to_smt(symbol: &str, arity: usize, row: usize) -> String {
+    let args = (0..arity).map(|i| row & (1 << i) != 0).collect::<Vec<_>>();
+    function_args_to_smt(symbol, &args)
+}
+
+/// **(internal)** Write the application of the uninterpreted function `symbol` to the
+/// given constant arguments.
+fn function_args_to_smt(symbol: &str, args: &[bool]) -> String {
+    if args.is_empty() {
+        symbol.to_string()
+    } else {
+        let args = args.iter().map(|it| it.to_string()).collect::<Vec<_>>();
+        format!("({} {})", symbol, args.join(" "))
+    }
+}
+
+/// **(internal)** Write a `Bdd` as an SMT-LIB2 term, where the `i`-th BDD variable is
+/// replaced by `terms[i]`. The BDD nodes are shared using `let` bindings.
+fn bdd_to_smt(bdd: &Bdd, terms: &[String]) -> String {
+    let node = |pointer: BddPointer| match pointer.as_bool() {
+        Some(value) => value.to_string(),
+        None => format!("_node_{}", pointer.to_index()),
+    };
+    let mut result = node(bdd.root_pointer());
+    // Nodes are sorted such that children always precede their parents. Hence, we wrap
+    // the result in reverse order so that children are bound before their parents.
+    for pointer in bdd.pointers().rev().filter(|it| !it.is_terminal()) {
+        let term = &terms[bdd.var_of(pointer).to_index()];
+        let low = node(bdd.low_link_of(pointer));
+        let high = node(bdd.high_link_of(pointer));
+        result = format!(
+            "(let (({} (ite {} {} {}))) {})",
+            node(pointer),
+            term,
+            high,
+            low,
+            result
+        );
+    }
+    result
+}
+
+/// **(internal)** Write an integer constant as an SMT-LIB2 term (negative numerals
+/// are not allowed in SMT-LIB2).
+fn smt_int(value: i32) -> String {
+    if value < 0 {
+        format!("(- {})", value.unsigned_abs())
+    } else {
+        value.to_string()
+    }
+}
+
+/// **(internal)** Add a quantifier over the `bound` Boolean symbols to `body` (if non-empty).
+fn quantify(quantifier: &str, bound: &[String], body: String) -> String {
+    if bound.is_empty() {
+        body
+    } else {
+        let bound = bound
+            .iter()
+            .map(|it| format!("({} Bool)", it))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("({} ({}) {})", quantifier, bound, body)
+    }
+}
+
+/// **(internal)** Write the update function of `var` as an SMT-LIB2 term, where `vars` are
+/// the terms that should be used in place of the individual network variables.
+fn update_to_smt(network: &BooleanNetwork, var: VariableId, vars: &[String]) -> String {
+    if let Some(function) = network.get_update_function(var) {
+        fn_update_to_smt(network, function, vars)
+    } else {
+        let name = implicit_symbol(network, var);
+        let args = network
+            .regulators(var)
+            .into_iter()
+            .map(|it| vars[it.to_index()].clone())
+            .collect::<Vec<_>>();
+        if args.is_empty() {
+            name
+        } else {
+            format!("({} {})", name, args.join(" "))
+        }
+    }
+}
+
+/// **(internal)** Write the given `FnUpdate` as an SMT-LIB2 term, where `vars` are the terms
+/// that should be used in place of the individual network variables.
+fn fn_update_to_smt(network: &BooleanNetwork, function: &FnUpdate, vars: &[String]) -> String {
+    match function {
+        FnUpdate::Const(value) => value.to_string(),
+        FnUpdate::Var(id) => vars[id.to_index()].clone(),
+        FnUpdate::Param(id, args) => {
+            let name = smt_symbol(network.get_parameter(*id).get_name());
+            if args.is_empty() {
+                name
+            } else {
+                let args = args
+                    .iter()
+                    .map(|it| fn_update_to_smt(network, it, vars))
+                    .collect::<Vec<_>>();
+                format!("({} {})", name, args.join(" "))
+            }
+        }
+        FnUpdate::Not(inner) => format!("(not {})", fn_update_to_smt(network, inner, vars)),
+        FnUpdate::Binary(op, left, right) => {
+            let op = match op {
+                BinaryOp::And => "and",
+                BinaryOp::Or => "or",
+                BinaryOp::Xor => "xor",
+                BinaryOp::Iff => "=",
+                BinaryOp::Imp => "=>",
+            };
+            let left = fn_update_to_smt(network, left, vars);
+            let right = fn_update_to_smt(network, right, vars);
+            format!("({} {} {})", op, left, right)
+        }
+        FnUpdate::Threshold(args, threshold) => {
+            // `(>= (+ (ite x_1 w_1 0) ... (ite x_n w_n 0)) k)`
+            let terms = args
+                .iter()
+                .map(|(weight, arg)| {
+                    let arg = fn_update_to_smt(network, arg, vars);
+                    format!("(ite {} {} 0)", arg, smt_int(*weight))
+                })
+                .collect::<Vec<_>>();
+            let sum = match terms.len() {
+                0 => "0".to_string(),
+                1 => terms[0].clone(),
+                _ => format!("(+ {})", terms.join(" ")),
+            };
+            format!("(>= {} {})", sum, smt_int(*threshold))
+        }
+        FnUpdate::Nary(op, args) => {
+            if args.is_empty() {
+                return op.neutral_value().to_string();
+            }
+            let op = match op {
+                NaryOp::And => "and",
+                NaryOp::Or => "or",
+            };
+            let args = args
+                .iter()
+                .map(|it| fn_update_to_smt(network, it, vars))
+                .collect::<Vec<_>>();
+            format!("({} {})", op, args.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BooleanNetwork, FunctionClass, SolverEncoding};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_smt_lib_export() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| a
+            a -? a
+            $a: f(a) & !b
+        ",
+        )
+        .unwrap();
+
+        let script = bn.to_smt_lib(SolverEncoding::ParameterConstraints).unwrap();
+        assert!(script.starts_with("; ParameterConstraints of a Boolean network.\n"));
+        assert!(script.contains("(declare-fun f (Bool) Bool)\n"));
+        assert!(script.contains("(declare-fun _update_b (Bool) Bool)\n"));
+        assert!(!script.contains("(declare-fun a () Bool)"));
+        assert!(script.contains("(assert (not (= (_update_b true) (_update_b false))))\n"));
+        assert!(script.contains("(assert (=> (_update_b false) (_update_b true)))\n"));
+        assert!(script.contains(
+            "(assert (exists ((_b_a Bool)) \
+            (not (= (and (f _b_a) (not true)) (and (f _b_a) (not false))))))\n"
+        ));
+        assert!(script.ends_with("(check-sat)\n"));
+
+        let mut with_class = bn.clone();
+        let f = bn.find_parameter("f").unwrap();
+        with_class.add_parameter_class(f, FunctionClass::Majority);
+        let script = with_class
+            .to_smt_lib(SolverEncoding::ParameterConstraints)
+            .unwrap();
+        assert!(script.contains("; @f: majority\n"));
+        assert!(script.contains(
+            "(assert (let ((_node_2 (ite (f true) false true))) \
+            (let ((_node_3 (ite (f true) true false))) \
+            (let ((_node_4 (ite (f false) _node_2 _node_3))) _node_4))))\n"
+        ));
+
+        let uncertain = BooleanNetwork::try_from("a ?-> b\n b -| a\n $a: !b").unwrap();
+        let script = uncertain
+            .to_smt_lib(SolverEncoding::ParameterConstraints)
+            .unwrap();
+        assert!(script.contains("(declare-fun _exists_a->b () Bool)\n"));
+
+        let script = bn.to_smt_lib(SolverEncoding::FixedPoints).unwrap();
+        assert!(script.contains("(declare-fun a () Bool)\n"));
+        assert!(script.contains("(assert (= a (and (f a) (not b))))\n"));
+        assert!(script.contains("(assert (= b (_update_b a)))\n"));
+
+        let script = bn.to_smt_lib(SolverEncoding::TrapSpaces).unwrap();
+        assert!(script.contains("(declare-fun _space_1_a () Bool)\n"));
+        assert!(script.contains("(assert (or _space_1_b _space_0_b))\n"));
+        assert!(script.contains(
+            "(assert (forall ((_s_a Bool)) \
+            (=> (and (=> _s_a _space_1_a) (=> (not _s_a) _space_0_a)) \
+            (and (=> (_update_b _s_a) _space_1_b) (=> (not (_update_b _s_a)) _space_0_b)))))\n"
+        ));
+
+        // Threshold operators are encoded as integer sums.
+        let threshold = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| b
+            $b: [2 * a - b >= 2]
+        ",
+        )
+        .unwrap();
+        let script = threshold.to_smt_lib(SolverEncoding::FixedPoints).unwrap();
+        assert!(script.contains("(set-logic UFLIA)\n"));
+        assert!(script.contains("(assert (= b (>= (+ (ite a 2 0) (ite b (- 1) 0)) 2)))\n"));
+
+        // Unsupported function classes are rejected.
+        let mut invalid = BooleanNetwork::try_from("a -> b\n $b: g(a, a, a, a, a, a)").unwrap();
+        let g = invalid.find_parameter("g").unwrap();
+        invalid.add_parameter_class(g, FunctionClass::Threshold);
+        assert!(invalid
+            .to_smt_lib(SolverEncoding::ParameterConstraints)
+            .is_err());
+    }
+}
@@ -0,0 +1,323 @@
+use crate::symbolic_async_graph::SymbolicContext;
+use crate::{BooleanNetwork, FnUpdate, VariableId};
+use std::collections::HashMap;
+
+/// Import and export of update functions as truth tables in a simple CSV format.
+///
+/// The header of the table contains the names of the regulators, followed by the name of
+/// the target variable. Each row then contains the `0`/`1` values of the regulators, followed
+/// by the output of the function. Rows are ordered such that the first regulator is
+/// the most significant.
+impl BooleanNetwork {
+    /// Write the update function of the given `variable` as a CSV truth table.
+    ///
+    /// The table uses all regulators of the `variable` as inputs. Returns an error if the
+    /// function is not fully specified (i.e. it is missing or uses parameters).
+    pub fn to_truth_table_csv(&self, variable: VariableId) -> Result<String, String> {
+        let name = self.get_variable_name(variable);
+        let function = self.get_update_function(variable).as_ref().ok_or_else(|| {
+            format!(
+                "Variable `{}` has no update function and cannot be exported as a truth table.",
+                name
+            )
+        })?;
+        let regulators = self.regulators(variable);
+
+        let mut header = regulators
+            .iter()
+            .map(|it| self.get_variable_name(*it).clone())
+            .collect::<Vec<_>>();
+        header.push(name.clone());
+        let mut table = header.join(",");
+        table.push('\n');
+
+        let mut valuation = HashMap::new();
+        for row in 0..row_count(regulators.len(), name)? {
+            let mut values = Vec::with_capacity(regulators.len() + 1);
+            for (i, regulator) in regulators.iter().enumerate() {
+                let value = (row >> (regulators.len() - 1 - i)) & 1 == 1;
+                valuation.insert(*regulator, value);
+                values.push(if value { "1" } else { "0" });
+            }
+            let output = function.evaluate(&valuation).ok_or_else(|| {
+                format!(
+                    "Update function of `{}` is not fully specified and cannot be exported as a truth table.",
+                    name
+                )
+            })?;
+            values.push(if output { "1" } else { "0" });
+            table.push_str(values.join(",").as_str());
+            table.push('\n');
+        }
+
+        Ok(table)
+    }
+
+    /// Read a CSV truth table and use it as the update function of the given `variable`.
+    ///
+    /// The input columns must be regulators of the `variable` (not necessarily all of them)
+    /// and the last column must be the `variable` itself. Empty lines and lines starting with
+    /// `#` are ignored.
+    ///
+    /// The output of a row can be `*`, `-` or `?`, in which case it is unknown. Rows that
+    /// are missing from the table are unknown as well. Every unknown row is then represented
+    /// by a new explicit zero-arity parameter named `f_<variable>_<row>` (e.g. `f_C_01` for
+    /// row `0,1`), such that the parameter determines the output of the function in this row.
+    /// Returns an error if the table is malformed, or if such parameter already exists.
+    ///
+    /// The resulting function is a DNF constructed using [FnUpdate::build_from_bdd].
+    pub fn set_truth_table_csv(&mut self, variable: VariableId, csv: &str) -> Result<(), String> {
+        let name = self.get_variable_name(variable).clone();
+        let mut lines = csv
+            .lines()
+            .map(|it| it.trim())
+            .filter(|it| !it.is_empty() && !it.starts_with('#'));
+
+        let header = lines
+            .next()
+            .ok_or_else(|| "Missing truth table header.".to_string())?
+            .split(',')
+            .map(|it| it.trim())
+            .collect::<Vec<_>>();
+        if header.last() != Some(&name.as_str()) {
+            return Err(format!(
+                "The last column of the truth table must be `{}`, but the header is `{}`.",
+                name,
+                header.join(",")
+            ));
+        }
+        let mut inputs = Vec::new();
+        for input_name in &header[..header.len() - 1] {
+            let input = self.as_graph().find_variable(input_name).ok_or_else(|| {
+                format!("Unknown variable `{}` in truth table header.", input_name)
+            })?;
+            if self.as_graph().find_regulation(input, variable).is_none() {
+                return Err(format!(
+                    "Variable `{}` does not regulate `{}`.",
+                    input_name, name
+                ));
+            }
+            if inputs.contains(&input) {
+                return Err(format!("Duplicate column `{}` in truth table.", input_name));
+            }
+            inputs.push(input);
+        }
+
+        // Map every row of the table to its output (`None` if unknown).
+        let mut rows: HashMap<Vec<bool>, Option<bool>> = HashMap::new();
+        for line in lines {
+            let values = line.split(',').map(|it| it.trim()).collect::<Vec<_>>();
+            if values.len() != header.len() {
+                return Err(format!("Unexpected truth table row: `{}`", line));
+            }
+            let mut row = Vec::with_capacity(inputs.len());
+            for value in &values[..inputs.len()] {
+                match *value {
+                    "0" => row.push(false),
+                    "1" => row.push(true),
+                    _ => return Err(format!("Invalid input value in row: `{}`", line)),
+                }
+            }
+            let output = match values[inputs.len()] {
+                "0" => Some(false),
+                "1" => Some(true),
+                "*" | "-" | "?" => None,
+                _ => return Err(format!("Invalid output value in row: `{}`", line)),
+            };
+            if let Some(previous) = rows.insert(row, output) {
+                if previous != output {
+                    return Err(format!("Conflicting truth table row: `{}`", line));
+                }
+            }
+        }
+
+        // Create parameters for unknown rows. We work on a copy of the network such that
+        // it is not modified when an error occurs.
+        let mut network = self.clone();
+        let mut known_rows = Vec::new();
+        let mut unknown_rows = Vec::new();
+        for index in 0..row_count(inputs.len(), &name)? {
+            let row = (0..inputs.len())
+                .map(|i| (index >> (inputs.len() - 1 - i)) & 1 == 1)
+                .collect::<Vec<_>>();
+            match rows.get(&row).cloned().flatten() {
+                Some(true) => known_rows.push(row),
+                Some(false) => (),
+                None => {
+                    let bits = row
+                        .iter()
+                        .map(|it| if *it { '1' } else { '0' })
+                        .collect::<String>();
+                    let parameter_name = if bits.is_empty() {
+                        format!("f_{}", name)
+                    } else {
+                        format!("f_{}_{}", name, bits)
+                    };
+                    let parameter = network.add_parameter(parameter_name.as_str(), 0)?;
+                    unknown_rows.push((row, parameter));
+                }
+            }
+        }
+
+        let mk_row = |row: &[bool]| {
+            let literals = inputs
+                .iter()
+                .zip(row)
+                .map(|(var, value)| {
+                    if *value {
+                        FnUpdate::mk_var(*var)
+                    } else {
+                        FnUpdate::mk_not(FnUpdate::mk_var(*var))
+                    }
+                })
+                .collect::<Vec<_>>();
+            FnUpdate::mk_nary_conjunction(&literals)
+        };
+        let mut clauses = known_rows.iter().map(|it| mk_row(it)).collect::<Vec<_>>();
+        for (row, parameter) in &unknown_rows {
+            clauses.push(mk_row(row).and(FnUpdate::mk_param(*parameter, &[])));
+        }
+        let function = if clauses.is_empty() {
+            FnUpdate::mk_false()
+        } else {
+            FnUpdate::mk_nary_disjunction(&clauses)
+        };
+        network.set_update_function(variable, Some(function.clone()))?;
+
+        // The explicit table is then simplified using its BDD representation.
+        let context = SymbolicContext::new(&network)?;
+        let function_bdd = context.mk_fn_update_true(&function);
+        let function = FnUpdate::build_from_bdd(&context, &function_bdd);
+        network.set_update_function(variable, Some(function))?;
+        *self = network;
+        Ok(())
+    }
+
+    /// Write the truth tables of all variables with a fully specified update function.
+    ///
+    /// See [BooleanNetwork::to_truth_table_csv] for details. Variables without an update
+    /// function are skipped, but an error is returned if some function uses parameters.
+    pub fn to_truth_tables_csv(&self) -> Result<HashMap<VariableId, String>, String> {
+        let mut result = HashMap::new();
+        for var in self.variables() {
+            if self.get_update_function(var).is_some() {
+                result.insert(var, self.to_truth_table_csv(var)?);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// **(internal)** The number of rows of a truth table with the given number of `inputs`,
+/// or an error if such table cannot be represented.
+fn row_count(inputs: usize, name: &str) -> Result<usize, String> {
+    u32::try_from(inputs)
+        .ok()
+        .and_then(|it| 1usize.checked_shl(it))
+        .ok_or_else(|| {
+            format!(
+                "Truth table of `{}` with {} inputs is too large.",
+                name, inputs
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BooleanNetwork, FnUpdate, RegulatoryGraph};
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_truth_table_export() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> c
+            b -| c
+            a -? d
+            $c: a & !b
+            $d: f(a)
+        ",
+        )
+        .unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let d = bn.as_graph().find_variable("d").unwrap();
+        let table = bn.to_truth_table_csv(c).unwrap();
+        assert_eq!(table, "a,b,c\n0,0,0\n0,1,0\n1,0,1\n1,1,0\n");
+        // Parametrised and missing functions cannot be exported.
+        assert!(bn.to_truth_table_csv(d).is_err());
+        let a = bn.as_graph().find_variable("a").unwrap();
+        assert!(bn.to_truth_table_csv(a).is_err());
+        assert!(bn.to_truth_tables_csv().is_err());
+
+        // Round-trip through the truth table.
+        let mut bn2 = bn.clone();
+        bn2.set_update_function(c, None).unwrap();
+        bn2.set_truth_table_csv(c, table.as_str()).unwrap();
+        let original = bn.get_update_function(c).as_ref().unwrap();
+        let imported = bn2.get_update_function(c).as_ref().unwrap();
+        for (va, vb) in [(false, false), (false, true), (true, false), (true, true)] {
+            let b = bn.as_graph().find_variable("b").unwrap();
+            let valuation = HashMap::from([(a, va), (b, vb)]);
+            assert_eq!(original.evaluate(&valuation), imported.evaluate(&valuation));
+        }
+
+        // Tables with too many inputs are rejected.
+        let names = (0..70).map(|i| format!("v{}", i)).collect::<Vec<_>>();
+        let mut graph = RegulatoryGraph::new(names.clone());
+        for name in &names {
+            graph.add_regulation(name, "v0", false, None).unwrap();
+        }
+        let mut large = BooleanNetwork::new(graph);
+        let v0 = large.as_graph().find_variable("v0").unwrap();
+        let inputs = large.variables().map(FnUpdate::mk_var).collect::<Vec<_>>();
+        let function = FnUpdate::mk_conjunction(&inputs);
+        large.set_update_function(v0, Some(function)).unwrap();
+        assert!(large.to_truth_table_csv(v0).is_err());
+    }
+
+    #[test]
+    fn test_truth_table_import_unknown_rows() {
+        let mut bn = BooleanNetwork::try_from(
+            r"
+            a -> c
+            b -? c
+        ",
+        )
+        .unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+
+        // Row `1,0` is unknown, row `0,1` is missing.
+        let table = "a,b,c\n0,0,0\n1,0,*\n1,1,1\n";
+        bn.set_truth_table_csv(c, table).unwrap();
+        assert_eq!(bn.num_parameters(), 2);
+        let p_10 = bn.find_parameter("f_c_10").unwrap();
+        let p_01 = bn.find_parameter("f_c_01").unwrap();
+        assert_eq!(bn.get_parameter(p_10).get_arity(), 0);
+        let function = bn.get_update_function(c).as_ref().unwrap();
+        assert!(function.contains_parameter(p_10));
+        assert!(function.contains_parameter(p_01));
+        let valuation = HashMap::from([(a, true), (b, true)]);
+        assert_eq!(function.evaluate(&valuation), Some(true));
+
+        // Importing the same table again fails, because the parameters already exist,
+        // and the network is left unchanged.
+        let copy = bn.clone();
+        assert!(bn.set_truth_table_csv(c, table).is_err());
+        assert_eq!(copy, bn);
+
+        // Invalid tables.
+        let mut bn = BooleanNetwork::try_from("a -> c \n b -? c").unwrap();
+        assert!(bn.set_truth_table_csv(c, "a,b\n0,0").is_err());
+        assert!(bn.set_truth_table_csv(c, "c,c\n0,0").is_err());
+        assert!(bn.set_truth_table_csv(c, "a,c\n0,2").is_err());
+        assert!(bn.set_truth_table_csv(c, "a,c\n0,0\n0,1").is_err());
+        assert!(bn.set_truth_table_csv(c, "a,c\n0,0,1").is_err());
+
+        // A table without any `1` rows is a constant.
+        bn.set_truth_table_csv(c, "a,c\n0,0\n1,0").unwrap();
+        assert_eq!(bn.get_update_function(c), &Some(FnUpdate::mk_false()));
+    }
+}
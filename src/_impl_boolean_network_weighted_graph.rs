@@ -0,0 +1,138 @@
+use crate::{BooleanNetwork, FnUpdate, Monotonicity, RegulatoryGraph};
+use std::collections::{HashMap, HashSet};
+
+impl BooleanNetwork {
+    /// Build a threshold network from a signed weighted graph.
+    ///
+    /// Every `(source, target, weight)` edge is a regulation, which is an activation when
+    /// the weight is positive and an inhibition when it is negative (zero weights are not
+    /// allowed). The update function of a variable with regulators `r_1, ..., r_k` is
+    /// `[w_1 * r_1 + ... + w_k * r_k >= t]`, where `t` is the value given in `thresholds`.
+    /// Regulations are not marked as observable, since a regulator does not need to
+    /// influence its target for every threshold.
+    ///
+    /// Every variable with regulators must have a threshold. Variables without regulators
+    /// have a constant update function when they have a threshold, and are free inputs
+    /// otherwise. Variables are sorted alphabetically.
+    pub fn try_from_weighted_graph(
+        edges: &[(String, String, i32)],
+        thresholds: &HashMap<String, i32>,
+    ) -> Result<BooleanNetwork, String> {
+        let mut names = HashSet::new();
+        for (source, target, weight) in edges {
+            if *weight == 0 {
+                return Err(format!(
+                    "Edge `{}` -> `{}` has zero weight.",
+                    source, target
+                ));
+            }
+            names.insert(source.clone());
+            names.insert(target.clone());
+        }
+        names.extend(thresholds.keys().cloned());
+        let mut names = names.into_iter().collect::<Vec<_>>();
+        names.sort();
+
+        let mut graph = RegulatoryGraph::new(names);
+        for (source, target, weight) in edges {
+            let monotonicity = if *weight > 0 {
+                Monotonicity::Activation
+            } else {
+                Monotonicity::Inhibition
+            };
+            graph.add_regulation(source, target, false, Some(monotonicity))?;
+        }
+
+        let weights = edges
+            .iter()
+            .map(|(source, target, weight)| {
+                let source = graph.find_variable(source).unwrap();
+                let target = graph.find_variable(target).unwrap();
+                ((source, target), *weight)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut network = BooleanNetwork::new(graph);
+        for var in network.variables() {
+            let name = network.get_variable_name(var);
+            let regulators = network.regulators(var);
+            let Some(threshold) = thresholds.get(name) else {
+                if regulators.is_empty() {
+                    continue;
+                }
+                return Err(format!("Missing threshold of variable `{}`.", name));
+            };
+            let args = regulators
+                .into_iter()
+                .map(|it| (weights[&(it, var)], FnUpdate::mk_var(it)))
+                .collect::<Vec<_>>();
+            let function = FnUpdate::mk_threshold(&args, *threshold);
+            network.set_update_function(var, Some(function.simplify_constants()))?;
+        }
+        Ok(network)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::BooleanNetwork;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_weighted_graph_network() {
+        let edges = vec![
+            ("a".to_string(), "c".to_string(), 2),
+            ("b".to_string(), "c".to_string(), 1),
+            ("c".to_string(), "c".to_string(), -1),
+            ("c".to_string(), "a".to_string(), 1),
+        ];
+        let mut thresholds = HashMap::new();
+        thresholds.insert("a".to_string(), 1);
+        thresholds.insert("c".to_string(), 2);
+        let bn = BooleanNetwork::try_from_weighted_graph(&edges, &thresholds).unwrap();
+
+        let expected = BooleanNetwork::try_from(
+            r"
+            a ->? c
+            b ->? c
+            c -|? c
+            c ->? a
+            $a: [c >= 1]
+            $c: [2 * a + b - c >= 2]
+        ",
+        )
+        .unwrap();
+        assert_eq!(expected, bn);
+        // `b` is a free input.
+        let b = bn.as_graph().find_variable("b").unwrap();
+        assert!(bn.get_update_function(b).is_none());
+
+        // The threshold function is `a & (b | !c)`.
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let explicit = BooleanNetwork::try_from(
+            r"
+            a ->? c
+            b ->? c
+            c -|? c
+            c ->? a
+            $a: c
+            $c: a & (b | !c)
+        ",
+        )
+        .unwrap();
+        let explicit_stg = SymbolicAsyncGraph::new(&explicit).unwrap();
+        assert_eq!(
+            stg.get_symbolic_fn_update(c),
+            explicit_stg.get_symbolic_fn_update(c)
+        );
+
+        thresholds.remove("c");
+        assert!(BooleanNetwork::try_from_weighted_graph(&edges, &thresholds).is_err());
+        thresholds.insert("c".to_string(), 2);
+        let zero = vec![("a".to_string(), "b".to_string(), 0)];
+        assert!(BooleanNetwork::try_from_weighted_graph(&zero, &thresholds).is_err());
+    }
+}
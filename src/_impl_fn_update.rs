@@ -1,10 +1,11 @@
+use crate::_aeon_parser::FnUpdateTemp;
 use crate::symbolic_async_graph::SymbolicContext;
 use crate::BinaryOp::{And, Iff, Imp, Or, Xor};
 use crate::FnUpdate::*;
-use crate::_aeon_parser::FnUpdateTemp;
-use crate::{BinaryOp, BooleanNetwork, FnUpdate, ParameterId, VariableId};
+use crate::{BinaryOp, BooleanNetwork, FnUpdate, NaryOp, ParameterId, VariableId};
 use biodivine_lib_bdd::{Bdd, BddVariable};
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
 
 /// Constructor and destructor utility methods. These mainly avoid unnecessary boxing
@@ -50,6 +51,36 @@ impl FnUpdate {
         Binary(op, Box::new(left), Box::new(right))
     }
 
+    /// Create a flat `phi_1 'op' ... 'op' phi_k` formula where `phi_1` through `phi_k` are
+    /// arguments of the n-ary `op` operator.
+    pub fn mk_nary(op: NaryOp, args: &[FnUpdate]) -> FnUpdate {
+        Nary(op, args.to_vec())
+    }
+
+    /// Create a threshold formula which is true when the sum of weights of the `args` that
+    /// are true is at least `threshold`.
+    pub fn mk_threshold(args: &[(i32, FnUpdate)], threshold: i32) -> FnUpdate {
+        Threshold(args.to_vec(), threshold)
+    }
+
+    /// Create a formula which is true when at least `k` of the `args` are true.
+    pub fn mk_at_least(k: i32, args: &[FnUpdate]) -> FnUpdate {
+        let args = args.iter().map(|it| (1, it.clone())).collect();
+        Threshold(args, k)
+    }
+
+    /// Create a formula which is true when at most `k` of the `args` are true.
+    pub fn mk_at_most(k: i32, args: &[FnUpdate]) -> FnUpdate {
+        // `x_1 + ... + x_n <= k` is the same as `-x_1 - ... - x_n >= -k`.
+        let args = args.iter().map(|it| (-1, it.clone())).collect();
+        Threshold(args, -k)
+    }
+
+    /// Create a formula which is true when exactly `k` of the `args` are true.
+    pub fn mk_exactly(k: i32, args: &[FnUpdate]) -> FnUpdate {
+        FnUpdate::mk_at_least(k, args).and(FnUpdate::mk_at_most(k, args))
+    }
+
     /// Negate this function.
     pub fn negation(self) -> FnUpdate {
         FnUpdate::mk_not(self)
@@ -120,46 +151,68 @@ impl FnUpdate {
         }
     }
 
-    /// Build an expression which is equivalent to the conjunction of the given expressions.
-    pub fn mk_conjunction(items: &[FnUpdate]) -> FnUpdate {
-        if items.is_empty() {
-            // Empty conjunction is `true`.
-            return Self::mk_true();
-        }
-        if items.len() == 1 {
-            return items[0].clone();
+    /// If `Nary`, return the operator and argument formulas, otherwise return `None`.
+    pub fn as_nary(&self) -> Option<(NaryOp, &[FnUpdate])> {
+        match self {
+            Nary(op, args) => Some((*op, args)),
+            _ => None,
         }
-        if items.len() == 2 {
-            return Self::mk_binary(And, items[0].clone(), items[1].clone());
+    }
+
+    /// If `Threshold`, return the weighted arguments and the threshold, otherwise
+    /// return `None`.
+    pub fn as_threshold(&self) -> Option<(&[(i32, FnUpdate)], i32)> {
+        match self {
+            Threshold(args, threshold) => Some((args, *threshold)),
+            _ => None,
         }
+    }
 
-        let Some(first) = items.first() else {
+    /// Build an expression which is equivalent to the conjunction of the given expressions.
+    ///
+    /// Conjunctions of three or more expressions are represented as a single n-ary node.
+    pub fn mk_conjunction(items: &[FnUpdate]) -> FnUpdate {
+        match items.len() {
             // Empty conjunction is `true`.
-            return Self::mk_true();
-        };
-        let rest = Self::mk_conjunction(&items[1..]);
-        first.clone().and(rest)
+            0 => Self::mk_true(),
+            1 => items[0].clone(),
+            2 => Self::mk_binary(And, items[0].clone(), items[1].clone()),
+            _ => Self::mk_nary(NaryOp::And, items),
+        }
     }
 
     /// Build an expression which is equivalent to the disjunction of the given expressions.
+    ///
+    /// Disjunctions of three or more expressions are represented as a single n-ary node.
+    /// Note that for backwards compatibility, the empty disjunction is `true`. Use
+    /// [FnUpdate::mk_nary_disjunction] if the empty disjunction should be `false`.
     pub fn mk_disjunction(items: &[FnUpdate]) -> FnUpdate {
         if items.is_empty() {
-            // Empty conjunction is `true`.
             return Self::mk_true();
         }
-        if items.len() == 1 {
-            return items[0].clone();
-        }
-        if items.len() == 2 {
-            return Self::mk_binary(Or, items[0].clone(), items[1].clone());
-        }
+        Self::mk_nary_disjunction(items)
+    }
 
-        let Some(first) = items.first() else {
-            // Empty conjunction is `true`.
-            return Self::mk_true();
-        };
-        let rest = Self::mk_disjunction(&items[1..]);
-        first.clone().or(rest)
+    /// Build an expression which is equivalent to the conjunction of the given expressions.
+    ///
+    /// This is the same as [FnUpdate::mk_conjunction], it only exists for symmetry with
+    /// [FnUpdate::mk_nary_disjunction].
+    pub fn mk_nary_conjunction(items: &[FnUpdate]) -> FnUpdate {
+        Self::mk_conjunction(items)
+    }
+
+    /// Build an expression which is equivalent to the disjunction of the given expressions.
+    ///
+    /// Disjunctions of three or more expressions are represented as a single n-ary node.
+    /// Unlike [FnUpdate::mk_disjunction], the empty disjunction is `false`.
+    pub fn mk_nary_disjunction(items: &[FnUpdate]) -> FnUpdate {
+        match items.len() {
+            // Empty disjunction is `false`.
+            0 => Self::mk_false(),
+            1 => items[0].clone(),
+            2 => Self::mk_binary(Or, items[0].clone(), items[1].clone()),
+            _ => Self::mk_nary(NaryOp::Or, items),
+        }
     }
 }
 
@@ -264,6 +317,16 @@ impl FnUpdate {
                     r_arguments(l, args);
                     r_arguments(r, args);
                 }
+                Nary(_, n_args) => {
+                    for fun in n_args {
+                        r_arguments(fun, args);
+                    }
+                }
+                Threshold(t_args, _) => {
+                    for (_, fun) in t_args {
+                        r_arguments(fun, args);
+                    }
+                }
             };
         }
         let mut args = HashSet::new();
@@ -291,6 +354,16 @@ impl FnUpdate {
                     r_parameters(l, params);
                     r_parameters(r, params);
                 }
+                Nary(_, args) => {
+                    for fun in args {
+                        r_parameters(fun, params);
+                    }
+                }
+                Threshold(args, _) => {
+                    for (_, fun) in args {
+                        r_parameters(fun, params);
+                    }
+                }
             };
         }
         let mut params = HashSet::new();
@@ -313,18 +386,10 @@ impl FnUpdate {
             }
             Not(inner) => format!("!{}", Self::to_string_rec(inner, context, false)),
             Binary(op, l, r) => {
-                // And/Or have special treatments so that chains don't produce
-                // unnecessary parenthesis.
-                let l_nested_and = *op == And && matches!(**l, Binary(And, _, _));
-                let r_nested_and = *op == And && matches!(**r, Binary(And, _, _));
-                let l_nested_or = *op == Or && matches!(**l, Binary(Or, _, _));
-                let r_nested_or = *op == Or && matches!(**r, Binary(Or, _, _));
-
-                let l_no_paren = l_nested_and || l_nested_or;
-                let r_no_paren = r_nested_and || r_nested_or;
-
-                let l = l.to_string_rec(context, l_no_paren);
-                let r = r.to_string_rec(context, r_no_paren);
+                // Nested binary operations are always parenthesised, because flat chains
+                // of three or more operands are parsed as n-ary operations.
+                let l = l.to_string_rec(context, false);
+                let r = r.to_string_rec(context, false);
 
                 if no_paren {
                     format!("{} {} {}", l, op, r)
@@ -332,6 +397,45 @@ impl FnUpdate {
                     format!("({} {} {})", l, op, r)
                 }
             }
+            Nary(op, args) => {
+                if args.is_empty() {
+                    return op.neutral_value().to_string();
+                }
+                if args.len() == 1 {
+                    return args[0].to_string_rec(context, no_paren);
+                }
+                let args = args
+                    .iter()
+                    .map(|it| it.to_string_rec(context, false))
+                    .collect::<Vec<_>>();
+                let separator = format!(" {} ", op);
+                if no_paren {
+                    args.join(separator.as_str())
+                } else {
+                    format!("({})", args.join(separator.as_str()))
+                }
+            }
+            Threshold(args, threshold) => {
+                // Threshold operations are always written as `[w_1 * x_1 + ... >= k]`, where
+                // unit weights are omitted.
+                let mut result = "[".to_string();
+                for (i, (weight, arg)) in args.iter().enumerate() {
+                    let arg = arg.to_string_rec(context, false);
+                    if i == 0 && *weight < 0 {
+                        result.push('-');
+                    } else if i > 0 {
+                        result.push_str(if *weight < 0 { " - " } else { " + " });
+                    }
+                    if weight.unsigned_abs() != 1 {
+                        result.push_str(format!("{} * ", weight.unsigned_abs()).as_str());
+                    }
+                    result.push_str(arg.as_str());
+                }
+                if args.is_empty() {
+                    result.push('0');
+                }
+                format!("{} >= {}]", result, threshold)
+            }
             Param(id, args) => {
                 let name = if let Some(ctx) = context {
                     ctx[*id].get_name().to_string()
@@ -410,6 +514,48 @@ impl FnUpdate {
                     },
                 }
             }
+            Nary(op, args) => {
+                // The result is determined once a single argument has the "dominant" value
+                // (`false` for conjunction, `true` for disjunction). Otherwise, all arguments
+                // must be known.
+                let neutral = op.neutral_value();
+                let mut result = Some(neutral);
+                for arg in args {
+                    match arg.evaluate(values) {
+                        Some(value) if value != neutral => return Some(value),
+                        Some(_) => (),
+                        None => result = None,
+                    }
+                }
+                result
+            }
+            Threshold(args, threshold) => {
+                // Compute the smallest and largest achievable sum, where each unknown
+                // argument can be both true and false.
+                let (mut min, mut max) = (0i64, 0i64);
+                for (weight, arg) in args {
+                    let weight = i64::from(*weight);
+                    match arg.evaluate(values) {
+                        Some(true) => {
+                            min += weight;
+                            max += weight;
+                        }
+                        Some(false) => (),
+                        None => {
+                            min += weight.min(0);
+                            max += weight.max(0);
+                        }
+                    }
+                }
+                let threshold = i64::from(*threshold);
+                if min >= threshold {
+                    Some(true)
+                } else if max < threshold {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
         }
     }
 
@@ -440,6 +586,18 @@ impl FnUpdate {
                 right.walk_postorder(action);
                 action(self);
             }
+            Nary(_, args) => {
+                for arg in args {
+                    arg.walk_postorder(action);
+                }
+                action(self);
+            }
+            Threshold(args, _) => {
+                for (_, arg) in args {
+                    arg.walk_postorder(action);
+                }
+                action(self);
+            }
         }
     }
 
@@ -471,6 +629,20 @@ impl FnUpdate {
                 let right = right.substitute_variable(var, expression);
                 FnUpdate::mk_binary(*op, left, right)
             }
+            Nary(op, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| it.substitute_variable(var, expression))
+                    .collect::<Vec<_>>();
+                Nary(*op, args)
+            }
+            Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(w, it)| (*w, it.substitute_variable(var, expression)))
+                    .collect::<Vec<_>>();
+                Threshold(args, *threshold)
+            }
         }
     }
 
@@ -502,6 +674,20 @@ impl FnUpdate {
                 let right = right.rename_all(variables, parameters);
                 FnUpdate::mk_binary(*op, left, right)
             }
+            Nary(op, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| it.rename_all(variables, parameters))
+                    .collect::<Vec<_>>();
+                Nary(*op, args)
+            }
+            Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(w, it)| (*w, it.rename_all(variables, parameters)))
+                    .collect::<Vec<_>>();
+                Threshold(args, *threshold)
+            }
         }
     }
 
@@ -571,6 +757,20 @@ impl FnUpdate {
                     }
                 }
             }
+            Nary(op, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| it.to_and_or_normal_form())
+                    .collect::<Vec<_>>();
+                Nary(*op, args)
+            }
+            Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(w, it)| (*w, it.to_and_or_normal_form()))
+                    .collect::<Vec<_>>();
+                expand_threshold(&args, *threshold)
+            }
         }
     }
 
@@ -647,6 +847,37 @@ impl FnUpdate {
                         }
                     }
                 }
+                Nary(op, args) => {
+                    let args = args
+                        .iter()
+                        .map(|it| recursion(it, invert))
+                        .collect::<Vec<_>>();
+                    if !invert {
+                        Nary(*op, args)
+                    } else {
+                        // De Morgan: !(a & b & c) = (!a | !b | !c) and vice versa.
+                        match op {
+                            NaryOp::And => Nary(NaryOp::Or, args),
+                            NaryOp::Or => Nary(NaryOp::And, args),
+                        }
+                    }
+                }
+                Threshold(args, threshold) => {
+                    if !invert {
+                        let args = args
+                            .iter()
+                            .map(|(w, it)| (*w, recursion(it, false)))
+                            .collect::<Vec<_>>();
+                        Threshold(args, *threshold)
+                    } else {
+                        // !(sum >= k) = (sum < k) = (-sum >= 1 - k)
+                        let args = args
+                            .iter()
+                            .map(|(w, it)| (-*w, recursion(it, false)))
+                            .collect::<Vec<_>>();
+                        Threshold(args, 1 - *threshold)
+                    }
+                }
             }
         }
 
@@ -718,10 +949,198 @@ impl FnUpdate {
                     },
                 }
             }
+            Nary(op, args) => {
+                // Remove neutral constants, and if a "dominant" constant is present, the whole
+                // operation is a constant.
+                let neutral = op.neutral_value();
+                let mut simplified = Vec::new();
+                for arg in args {
+                    let arg = arg.simplify_constants();
+                    match arg.as_const() {
+                        Some(value) if value != neutral => return Const(value),
+                        Some(_) => (),
+                        None => simplified.push(arg),
+                    }
+                }
+                match op {
+                    NaryOp::And => FnUpdate::mk_nary_conjunction(&simplified),
+                    NaryOp::Or => FnUpdate::mk_nary_disjunction(&simplified),
+                }
+            }
+            Threshold(args, threshold) => {
+                // Constant arguments are removed and their weights are subtracted from
+                // the threshold. If the result is decided regardless of the remaining
+                // arguments, it is a constant.
+                let mut threshold = i64::from(*threshold);
+                let (mut min, mut max) = (0i64, 0i64);
+                let mut simplified = Vec::new();
+                for (weight, arg) in args {
+                    let arg = arg.simplify_constants();
+                    match arg.as_const() {
+                        Some(true) => threshold -= i64::from(*weight),
+                        Some(false) => (),
+                        None => {
+                            min += i64::from(*weight).min(0);
+                            max += i64::from(*weight).max(0);
+                            simplified.push((*weight, arg));
+                        }
+                    }
+                }
+                if min >= threshold {
+                    Const(true)
+                } else if max < threshold {
+                    Const(false)
+                } else {
+                    // The threshold is between `min` and `max`, so it fits into `i32`.
+                    Threshold(simplified, i32::try_from(threshold).unwrap())
+                }
+            }
+        }
+    }
+
+    /// Perform a syntactic transformation which collapses all nested chains of `&` and `|`
+    /// operators (binary or n-ary) into flat n-ary operators. Chains of two operands
+    /// remain binary.
+    ///
+    /// For example, `a & (b & (c & d))` becomes a single conjunction of four arguments.
+    pub fn flatten(&self) -> FnUpdate {
+        let op = match self {
+            Const(_) | Var(_) => return self.clone(),
+            Param(id, args) => {
+                let args = args.iter().map(|it| it.flatten()).collect::<Vec<_>>();
+                return Param(*id, args);
+            }
+            Not(inner) => return inner.flatten().negation(),
+            Binary(op, left, right) => match NaryOp::try_from_binary(*op) {
+                Some(op) => op,
+                None => return FnUpdate::mk_binary(*op, left.flatten(), right.flatten()),
+            },
+            Nary(op, _) => *op,
+            Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(w, it)| (*w, it.flatten()))
+                    .collect::<Vec<_>>();
+                return Threshold(args, *threshold);
+            }
+        };
+        // Collect the operands using an explicit stack, since the chains can be very deep.
+        let mut args = Vec::new();
+        let mut stack = vec![self];
+        while let Some(update) = stack.pop() {
+            match update {
+                Binary(inner, left, right) if NaryOp::try_from_binary(*inner) == Some(op) => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+                Nary(inner, inner_args) if *inner == op => {
+                    stack.extend(inner_args.iter().rev());
+                }
+                _ => args.push(update.flatten()),
+            }
+        }
+        match op {
+            NaryOp::And => FnUpdate::mk_nary_conjunction(&args),
+            NaryOp::Or => FnUpdate::mk_nary_disjunction(&args),
         }
     }
 }
 
+impl FnUpdate {
+    /// Replace every threshold operator in this function with an equivalent formula that
+    /// only uses `!`, `&` and `|`.
+    ///
+    /// The formula is obtained by expanding the arguments one by one. Each partial expansion
+    /// is only computed once, but since `FnUpdate` cannot share sub-formulas, the size of the
+    /// result can still be exponential in the number of arguments of the threshold operator.
+    /// To translate thresholds symbolically, use [SymbolicContext::mk_fn_update_true] instead.
+    pub fn expand_thresholds(&self) -> FnUpdate {
+        match self {
+            Const(_) | Var(_) => self.clone(),
+            Param(id, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| it.expand_thresholds())
+                    .collect::<Vec<_>>();
+                Param(*id, args)
+            }
+            Not(inner) => inner.expand_thresholds().negation(),
+            Binary(op, left, right) => {
+                FnUpdate::mk_binary(*op, left.expand_thresholds(), right.expand_thresholds())
+            }
+            Nary(op, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| it.expand_thresholds())
+                    .collect::<Vec<_>>();
+                Nary(*op, args)
+            }
+            Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(w, it)| (*w, it.expand_thresholds()))
+                    .collect::<Vec<_>>();
+                expand_threshold(&args, *threshold)
+            }
+        }
+    }
+}
+
+/// **(internal)** Expand a single threshold operator with the given `args` (which do not
+/// contain any threshold operators) into a formula using `!`, `&` and `|`.
+///
+/// The expansion of every `(index, remaining threshold)` pair is only computed once.
+fn expand_threshold(args: &[(i32, FnUpdate)], threshold: i32) -> FnUpdate {
+    fn recursion(
+        args: &[(i32, FnUpdate)],
+        bounds: &[(i64, i64)],
+        state: (usize, i64),
+        cache: &mut HashMap<(usize, i64), FnUpdate>,
+    ) -> FnUpdate {
+        let (index, threshold) = state;
+        let (min, max) = bounds[index];
+        if min >= threshold {
+            return Const(true);
+        }
+        if max < threshold {
+            return Const(false);
+        }
+        if let Some(result) = cache.get(&state) {
+            return result.clone();
+        }
+        // Otherwise, there is at least one remaining argument with a non-zero weight.
+        let (weight, arg) = &args[index];
+        let weight = i64::from(*weight);
+        let high = recursion(args, bounds, (index + 1, threshold - weight), cache);
+        let low = recursion(args, bounds, (index + 1, threshold), cache);
+        // For a positive weight, `low` implies `high` and we can write the result as
+        // `low | (arg & high)`. For a negative weight, `high` implies `low`.
+        let result = if weight >= 0 {
+            FnUpdate::mk_disjunction(&[low, FnUpdate::mk_conjunction(&[arg.clone(), high])])
+        } else {
+            let not_arg = arg.clone().negation();
+            FnUpdate::mk_disjunction(&[high, FnUpdate::mk_conjunction(&[not_arg, low])])
+        };
+        let result = result.simplify_constants();
+        cache.insert(state, result.clone());
+        result
+    }
+
+    // The smallest and largest sum that can be achieved by each suffix of `args`.
+    let mut bounds = vec![(0i64, 0i64); args.len() + 1];
+    for (i, (weight, _)) in args.iter().enumerate().rev() {
+        let (min, max) = bounds[i + 1];
+        let weight = i64::from(*weight);
+        bounds[i] = (min + weight.min(0), max + weight.max(0));
+    }
+    recursion(
+        args,
+        &bounds,
+        (0, i64::from(threshold)),
+        &mut HashMap::new(),
+    )
+}
+
 impl Display for FnUpdate {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.to_string_rec(None, true))
@@ -731,7 +1150,9 @@ impl Display for FnUpdate {
 #[cfg(test)]
 mod tests {
     use crate::symbolic_async_graph::SymbolicContext;
-    use crate::{BinaryOp, BooleanNetwork, FnUpdate, ParameterId, RegulatoryGraph, VariableId};
+    use crate::{
+        BinaryOp, BooleanNetwork, FnUpdate, NaryOp, ParameterId, RegulatoryGraph, VariableId,
+    };
     use biodivine_lib_bdd::bdd;
     use std::collections::HashMap;
     use std::convert::TryFrom;
@@ -1137,4 +1558,195 @@ mod tests {
             FnUpdate::mk_disjunction(&args)
         );
     }
+
+    #[test]
+    fn test_nary_constructors() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -> c
+            c -> a
+        ",
+        )
+        .unwrap();
+        let args = bn.variables().map(FnUpdate::mk_var).collect::<Vec<_>>();
+        assert_eq!(FnUpdate::mk_true(), FnUpdate::mk_nary_conjunction(&[]));
+        assert_eq!(FnUpdate::mk_false(), FnUpdate::mk_nary_disjunction(&[]));
+        assert_eq!(FnUpdate::mk_true(), FnUpdate::mk_disjunction(&[]));
+        assert!(FnUpdate::mk_conjunction(&args[..2]).as_binary().is_some());
+        assert!(FnUpdate::mk_disjunction(&args[..2]).as_binary().is_some());
+
+        let conjunction = FnUpdate::mk_conjunction(&args);
+        let (op, inner) = conjunction.as_nary().unwrap();
+        assert_eq!(NaryOp::And, op);
+        assert_eq!(args.as_slice(), inner);
+        assert_eq!(conjunction, FnUpdate::mk_nary_conjunction(&args));
+        assert_eq!("a & b & c", conjunction.to_string(&bn));
+        let disjunction = FnUpdate::mk_disjunction(&args);
+        assert_eq!(Some((NaryOp::Or, args.as_slice())), disjunction.as_nary());
+
+        // Flat chains are parsed as n-ary operators, nested chains stay binary.
+        let function = FnUpdate::try_from_str("a & (b | !c | a) & (c ^ b)", &bn).unwrap();
+        assert!(function.as_nary().is_some());
+        assert_eq!("a & (b | !c | a) & (c ^ b)", function.to_string(&bn));
+        let binary = FnUpdate::try_from_str("a & (b & c)", &bn).unwrap();
+        assert!(binary.as_binary().is_some());
+        assert_eq!("a & (b & c)", binary.to_string(&bn));
+        assert_eq!(conjunction, binary.flatten());
+
+        // Nested chains are flattened.
+        let nested = FnUpdate::try_from_str("a & (b & (c & !a))", &bn).unwrap();
+        let flat = FnUpdate::mk_nary_conjunction(&[
+            args[0].clone(),
+            args[1].clone(),
+            args[2].clone(),
+            args[0].clone().negation(),
+        ]);
+        assert_ne!(nested, flat);
+        assert_eq!(flat, nested.flatten());
+
+        let a = VariableId::from_index(0);
+        let b = VariableId::from_index(1);
+        let mut values = HashMap::new();
+        values.insert(a, true);
+        assert_eq!(None, function.evaluate(&values));
+        values.insert(b, true);
+        assert_eq!(None, function.evaluate(&values));
+        values.insert(a, false);
+        assert_eq!(Some(false), function.evaluate(&values));
+
+        assert_eq!(
+            FnUpdate::try_from_str("!(a & b & c)", &bn)
+                .unwrap()
+                .flatten()
+                .distribute_negation(),
+            FnUpdate::try_from_str("!a | !b | !c", &bn)
+                .unwrap()
+                .flatten(),
+        );
+        assert_eq!(
+            FnUpdate::try_from_str("a | false | b | c", &bn)
+                .unwrap()
+                .flatten()
+                .simplify_constants(),
+            FnUpdate::mk_nary_disjunction(&args),
+        );
+        assert_eq!(
+            FnUpdate::try_from_str("a & b & false", &bn)
+                .unwrap()
+                .flatten()
+                .simplify_constants(),
+            FnUpdate::mk_false(),
+        );
+
+        let ctx = SymbolicContext::new(&bn).unwrap();
+        assert_eq!(
+            ctx.mk_fn_update_true(&nested),
+            ctx.mk_fn_update_true(&nested.flatten())
+        );
+    }
+
+    #[test]
+    fn test_threshold_operators() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -> c
+            c -> a
+        ",
+        )
+        .unwrap();
+        let ctx = SymbolicContext::new(&bn).unwrap();
+        let args = bn.variables().map(FnUpdate::mk_var).collect::<Vec<_>>();
+
+        let at_least = FnUpdate::mk_at_least(2, &args);
+        assert_eq!("[a + b + c >= 2]", at_least.to_string(&bn));
+        assert_eq!(
+            at_least,
+            FnUpdate::try_from_str("[a + b + c >= 2]", &bn).unwrap()
+        );
+        let majority = FnUpdate::try_from_str("(a & b) | (a & c) | (b & c)", &bn).unwrap();
+        assert_eq!(
+            ctx.mk_fn_update_true(&majority),
+            ctx.mk_fn_update_true(&at_least)
+        );
+        assert_eq!(
+            ctx.mk_fn_update_true(&majority),
+            ctx.mk_fn_update_true(&at_least.expand_thresholds())
+        );
+
+        let weighted = FnUpdate::try_from_str("[2 * a - b + !(b & c) >= 2]", &bn).unwrap();
+        assert_eq!("[2 * a - b + !(b & c) >= 2]", weighted.to_string(&bn));
+        let expected = FnUpdate::try_from_str("a & (!b | !c)", &bn).unwrap();
+        assert_eq!(
+            ctx.mk_fn_update_true(&expected),
+            ctx.mk_fn_update_true(&weighted)
+        );
+        assert_eq!(
+            ctx.mk_fn_update_true(&expected),
+            ctx.mk_fn_update_true(&weighted.expand_thresholds())
+        );
+        assert_eq!(
+            ctx.mk_fn_update_true(&expected).not(),
+            ctx.mk_fn_update_true(&weighted.clone().negation().distribute_negation())
+        );
+
+        let exactly = FnUpdate::mk_exactly(1, &args);
+        let one_hot =
+            FnUpdate::try_from_str("(a & !b & !c) | (!a & b & !c) | (!a & !b & c)", &bn).unwrap();
+        assert_eq!(
+            ctx.mk_fn_update_true(&one_hot),
+            ctx.mk_fn_update_true(&exactly)
+        );
+
+        let a = VariableId::from_index(0);
+        let b = VariableId::from_index(1);
+        let mut values = HashMap::new();
+        values.insert(a, true);
+        assert_eq!(None, at_least.evaluate(&values));
+        assert_eq!(None, weighted.evaluate(&values));
+        values.insert(b, false);
+        assert_eq!(Some(true), weighted.evaluate(&values));
+        assert_eq!(None, at_least.evaluate(&values));
+        values.insert(a, false);
+        assert_eq!(Some(false), at_least.evaluate(&values));
+
+        assert_eq!(
+            FnUpdate::try_from_str("[a + true + c >= 2]", &bn)
+                .unwrap()
+                .simplify_constants(),
+            FnUpdate::try_from_str("[a + c >= 1]", &bn).unwrap(),
+        );
+        assert_eq!(
+            FnUpdate::try_from_str("[a + false + c >= 3]", &bn)
+                .unwrap()
+                .simplify_constants(),
+            FnUpdate::mk_false(),
+        );
+
+        assert!(FnUpdate::try_from_str("[a + b]", &bn).is_err());
+
+        // Extreme weights do not overflow.
+        let extreme = FnUpdate::mk_threshold(&[(i32::MIN, args[0].clone())], i32::MIN);
+        assert_eq!("[-2147483648 * a >= -2147483648]", extreme.to_string(&bn));
+        assert_eq!(
+            extreme,
+            FnUpdate::try_from_str(extreme.to_string(&bn).as_str(), &bn).unwrap()
+        );
+        assert_eq!(FnUpdate::mk_true(), extreme.expand_thresholds());
+        assert!(FnUpdate::try_from_str("[a + b >= c]", &bn).is_err());
+        assert!(FnUpdate::try_from_str("[a b >= 1]", &bn).is_err());
+
+        // In update functions, `0` is always the constant `false` (even if the network has
+        // a variable named `0`), hence `[0 >= k]` is an empty sum.
+        let bn = BooleanNetwork::try_from("0 -> a\n a -> 0\n $a: 0").unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        assert_eq!(&Some(FnUpdate::mk_false()), bn.get_update_function(a));
+        let empty = FnUpdate::try_from_str("[0 >= 1]", &bn).unwrap();
+        assert_eq!(FnUpdate::mk_threshold(&[], 1), empty);
+        assert_eq!("[0 >= 1]", empty.to_string(&bn));
+        let function = FnUpdate::try_from_str("[a + 0 >= 1]", &bn).unwrap();
+        let expected = [(1, FnUpdate::mk_var(a)), (1, FnUpdate::mk_false())];
+        assert_eq!(FnUpdate::mk_threshold(&expected, 1), function);
+    }
 }
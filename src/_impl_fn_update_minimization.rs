@@ -0,0 +1,609 @@
+use crate::symbolic_async_graph::_impl_symbolic_context::mk_threshold_bdd;
+use crate::BinaryOp::{And, Iff, Imp, Or, Xor};
+use crate::FnUpdate::*;
+use crate::{FnUpdate, NaryOp};
+use biodivine_lib_bdd::{Bdd, BddPartialValuation, BddVariable, BddVariableSet};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// **(internal)** Functions with at most this many atoms are minimised exactly. Larger
+/// functions use an Espresso-style heuristic.
+const EXACT_ATOM_LIMIT: usize = 10;
+
+/// **(internal)** A conjunction of literals over the atoms of a function, where the `i`-th
+/// item is the value of the `i`-th atom (or `None` when the atom is not in the cube).
+type Cube = Vec<Option<bool>>;
+
+/// **(internal)** A symbolic representation of an `FnUpdate` over its "atoms", i.e. the
+/// variables and uninterpreted function calls that appear in it.
+struct AtomFunction {
+    atoms: Vec<FnUpdate>,
+    variables: BddVariableSet,
+    bdd: Bdd,
+}
+
+/// Logic minimisation methods. The update function is treated as a function of its atoms,
+/// i.e. the network variables and the parameter calls (`f(x, y)`) that appear in it. The
+/// arguments of parameter calls are normalised using the same method.
+impl FnUpdate {
+    /// Compute all prime implicants of this function.
+    ///
+    /// Each implicant is a conjunction of literals (an atom or its negation). The result is
+    /// empty when the function is unsatisfiable, and contains only `true` when it is a
+    /// tautology.
+    ///
+    /// Note that the number of prime implicants can be exponential in the number of atoms.
+    pub fn prime_implicants(&self) -> Vec<FnUpdate> {
+        let function = AtomFunction::new(self, &|it| it.to_blake_canonical_form());
+        let primes = function.prime_implicants();
+        primes.iter().map(|it| function.cube_to_fn(it)).collect()
+    }
+
+    /// Compute the Blake canonical form of this function, i.e. the disjunction of all its
+    /// prime implicants.
+    ///
+    /// Two equivalent functions (over the same atoms) always have the same Blake canonical form.
+    pub fn to_blake_canonical_form(&self) -> FnUpdate {
+        let function = AtomFunction::new(self, &|it| it.to_blake_canonical_form());
+        let primes = function.prime_implicants();
+        function.dnf_to_fn(&primes)
+    }
+
+    /// Compute a minimal disjunctive normal form of this function.
+    ///
+    /// The result minimises the number of conjunctions, and then the number of literals. For
+    /// functions with up to 10 atoms, the minimisation is exact (Quine–McCluskey with
+    /// a branch-and-bound cover search). For larger functions, the result is an irredundant
+    /// disjunction of prime implicants (computed using Espresso-style heuristics), which is
+    /// not guaranteed to be minimal.
+    pub fn to_minimal_dnf(&self) -> FnUpdate {
+        let function = AtomFunction::new(self, &|it| it.to_minimal_dnf());
+        let cover = function.minimal_cover(&function.bdd);
+        function.dnf_to_fn(&cover)
+    }
+
+    /// Compute a minimal conjunctive normal form of this function.
+    ///
+    /// The result is obtained by minimising the disjunctive normal form of the negated
+    /// function, hence the same guarantees as in [FnUpdate::to_minimal_dnf] apply.
+    pub fn to_minimal_cnf(&self) -> FnUpdate {
+        let function = AtomFunction::new(self, &|it| it.to_minimal_cnf());
+        let cover = function.minimal_cover(&function.bdd.not());
+        let clauses = cover
+            .iter()
+            .map(|cube| {
+                let negated = cube.iter().map(|it| it.map(|value| !value)).collect();
+                let literals = function.cube_literals(&negated);
+                if literals.is_empty() {
+                    FnUpdate::mk_false()
+                } else {
+                    FnUpdate::mk_nary_disjunction(&literals)
+                }
+            })
+            .collect::<Vec<_>>();
+        FnUpdate::mk_nary_conjunction(&clauses)
+    }
+}
+
+impl AtomFunction {
+    /// Build the symbolic representation of `function`, normalising the arguments of
+    /// parameter calls using `normalize`.
+    fn new(function: &FnUpdate, normalize: &dyn Fn(&FnUpdate) -> FnUpdate) -> AtomFunction {
+        let function = normalize_arguments(function, normalize);
+        let mut atoms = Vec::new();
+        collect_atoms(&function, &mut atoms);
+        // Variables go first (in the order of their ids), followed by parameter calls (in the
+        // order of their ids and arguments), such that the order does not depend on
+        // the syntax of the function.
+        atoms.sort_by(compare_fn);
+        let variables = BddVariableSet::new_anonymous(u16::try_from(atoms.len()).unwrap());
+        let bdd = fn_to_bdd(&function, &atoms, &variables);
+        AtomFunction {
+            atoms,
+            variables,
+            bdd,
+        }
+    }
+
+    /// Compute all prime implicants of this function.
+    fn prime_implicants(&self) -> Vec<Cube> {
+        let mut primes = prime_implicants(&self.bdd, self.atoms.len());
+        sort_cubes(&mut primes);
+        primes
+    }
+
+    /// Compute a minimal (or small, for large functions) set of prime implicants which
+    /// covers the given function (expressed over the atoms of this function).
+    fn minimal_cover(&self, bdd: &Bdd) -> Vec<Cube> {
+        let mut cover = if self.atoms.len() <= EXACT_ATOM_LIMIT {
+            let primes = prime_implicants(bdd, self.atoms.len());
+            exact_cover(&primes, self.atoms.len())
+        } else {
+            self.heuristic_cover(bdd)
+        };
+        sort_cubes(&mut cover);
+        cover
+    }
+
+    /// Espresso-style cover: expand each cube of an initial DNF into a prime implicant and
+    /// then remove redundant implicants.
+    fn heuristic_cover(&self, bdd: &Bdd) -> Vec<Cube> {
+        let mut cover = Vec::new();
+        let mut known = HashSet::new();
+        for clause in bdd.to_optimized_dnf() {
+            let mut cube = (0..self.atoms.len())
+                .map(|i| clause.get_value(BddVariable::from_index(i)))
+                .collect::<Cube>();
+            // Expand: drop literals as long as the cube remains an implicant.
+            for i in 0..cube.len() {
+                if let Some(value) = cube[i] {
+                    cube[i] = None;
+                    if !self.cube_to_bdd(&cube).imp(bdd).is_true() {
+                        cube[i] = Some(value);
+                    }
+                }
+            }
+            if known.insert(cube.clone()) {
+                cover.push(cube);
+            }
+        }
+        // Irredundant: remove implicants covered by the rest (largest ones first).
+        cover.sort_by_key(|it| std::cmp::Reverse(literal_count(it)));
+        let mut i = 0;
+        while i < cover.len() {
+            let rest = cover
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(self.variables.mk_false(), |acc, (_, it)| {
+                    acc.or(&self.cube_to_bdd(it))
+                });
+            if self.cube_to_bdd(&cover[i]).imp(&rest).is_true() {
+                cover.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        cover
+    }
+
+    /// Convert a cube into a `Bdd` over the atom variables.
+    fn cube_to_bdd(&self, cube: &Cube) -> Bdd {
+        let values = cube
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| value.map(|value| (BddVariable::from_index(i), value)))
+            .collect::<Vec<_>>();
+        self.variables
+            .mk_conjunctive_clause(&BddPartialValuation::from_values(&values))
+    }
+
+    /// The literals of a cube as `FnUpdate` atoms (or their negations).
+    fn cube_literals(&self, cube: &Cube) -> Vec<FnUpdate> {
+        cube.iter()
+            .zip(&self.atoms)
+            .filter_map(|(value, atom)| match value {
+                Some(true) => Some(atom.clone()),
+                Some(false) => Some(atom.clone().negation()),
+                None => None,
+            })
+            .collect()
+    }
+
+    /// Convert a cube into a conjunction of literals.
+    fn cube_to_fn(&self, cube: &Cube) -> FnUpdate {
+        FnUpdate::mk_nary_conjunction(&self.cube_literals(cube))
+    }
+
+    /// Convert a list of cubes into a disjunction of conjunctions.
+    fn dnf_to_fn(&self, cubes: &[Cube]) -> FnUpdate {
+        if cubes.is_empty() {
+            return FnUpdate::mk_false();
+        }
+        let cubes = cubes
+            .iter()
+            .map(|it| self.cube_to_fn(it))
+            .collect::<Vec<_>>();
+        FnUpdate::mk_nary_disjunction(&cubes)
+    }
+}
+
+/// **(internal)** Apply `normalize` to the arguments of all parameter calls in `function`.
+fn normalize_arguments(function: &FnUpdate, normalize: &dyn Fn(&FnUpdate) -> FnUpdate) -> FnUpdate {
+    match function {
+        Const(_) | Var(_) => function.clone(),
+        Param(id, args) => Param(*id, args.iter().map(normalize).collect()),
+        Not(inner) => normalize_arguments(inner, normalize).negation(),
+        Binary(op, left, right) => FnUpdate::mk_binary(
+            *op,
+            normalize_arguments(left, normalize),
+            normalize_arguments(right, normalize),
+        ),
+        Threshold(args, threshold) => Threshold(
+            args.iter()
+                .map(|(w, it)| (*w, normalize_arguments(it, normalize)))
+                .collect(),
+            *threshold,
+        ),
+        Nary(op, args) => Nary(
+            *op,
+            args.iter()
+                .map(|it| normalize_arguments(it, normalize))
+                .collect(),
+        ),
+    }
+}
+
+/// **(internal)** Collect the distinct atoms (variables and parameter calls) of `function`
+/// in the order of their first occurrence.
+fn collect_atoms(function: &FnUpdate, atoms: &mut Vec<FnUpdate>) {
+    match function {
+        Const(_) => (),
+        Var(_) | Param(_, _) => {
+            if !atoms.contains(function) {
+                atoms.push(function.clone());
+            }
+        }
+        Not(inner) => collect_atoms(inner, atoms),
+        Binary(_, left, right) => {
+            collect_atoms(left, atoms);
+            collect_atoms(right, atoms);
+        }
+        Threshold(args, _) => {
+            for (_, arg) in args {
+                collect_atoms(arg, atoms);
+            }
+        }
+        Nary(_, args) => {
+            for arg in args {
+                collect_atoms(arg, atoms);
+            }
+        }
+    }
+}
+
+/// **(internal)** A total syntactic order on functions, used to sort atoms. Variables are
+/// ordered before parameter calls, which are ordered by their ids and then arguments.
+fn compare_fn(left: &FnUpdate, right: &FnUpdate) -> Ordering {
+    fn rank(function: &FnUpdate) -> u8 {
+        match function {
+            Const(_) => 0,
+            Var(_) => 1,
+            Param(_, _) => 2,
+            Not(_) => 3,
+            Binary(_, _, _) => 4,
+            Nary(_, _) => 5,
+            Threshold(_, _) => 6,
+        }
+    }
+    fn compare_all(left: &[FnUpdate], right: &[FnUpdate]) -> Ordering {
+        left.iter()
+            .zip(right)
+            .map(|(l, r)| compare_fn(l, r))
+            .find(|it| it.is_ne())
+            .unwrap_or_else(|| left.len().cmp(&right.len()))
+    }
+    match (left, right) {
+        (Const(l), Const(r)) => l.cmp(r),
+        (Var(l), Var(r)) => l.cmp(r),
+        (Param(l_id, l_args), Param(r_id, r_args)) => {
+            l_id.cmp(r_id).then_with(|| compare_all(l_args, r_args))
+        }
+        (Not(l), Not(r)) => compare_fn(l, r),
+        (Binary(l_op, l1, l2), Binary(r_op, r1, r2)) => (*l_op as u8)
+            .cmp(&(*r_op as u8))
+            .then_with(|| compare_fn(l1, r1))
+            .then_with(|| compare_fn(l2, r2)),
+        (Nary(l_op, l_args), Nary(r_op, r_args)) => (*l_op as u8)
+            .cmp(&(*r_op as u8))
+            .then_with(|| compare_all(l_args, r_args)),
+        (Threshold(l_args, l_k), Threshold(r_args, r_k)) => l_k.cmp(r_k).then_with(|| {
+            l_args
+                .iter()
+                .zip(r_args)
+                .map(|((l_w, l), (r_w, r))| l_w.cmp(r_w).then_with(|| compare_fn(l, r)))
+                .find(|it| it.is_ne())
+                .unwrap_or_else(|| l_args.len().cmp(&r_args.len()))
+        }),
+        _ => rank(left).cmp(&rank(right)),
+    }
+}
+
+/// **(internal)** Translate `function` into a `Bdd` where the `i`-th variable corresponds
+/// to the `i`-th atom.
+pub(crate) fn fn_to_bdd(
+    function: &FnUpdate,
+    atoms: &[FnUpdate],
+    variables: &BddVariableSet,
+) -> Bdd {
+    match function {
+        Const(value) => {
+            if *value {
+                variables.mk_true()
+            } else {
+                variables.mk_false()
+            }
+        }
+        Var(_) | Param(_, _) => {
+            let index = atoms.iter().position(|it| it == function).unwrap();
+            variables.mk_var(BddVariable::from_index(index))
+        }
+        Not(inner) => fn_to_bdd(inner, atoms, variables).not(),
+        Binary(op, left, right) => {
+            let left = fn_to_bdd(left, atoms, variables);
+            let right = fn_to_bdd(right, atoms, variables);
+            match op {
+                And => left.and(&right),
+                Or => left.or(&right),
+                Xor => left.xor(&right),
+                Iff => left.iff(&right),
+                Imp => left.imp(&right),
+            }
+        }
+        Threshold(args, threshold) => {
+            let args = args
+                .iter()
+                .map(|(weight, arg)| (*weight, fn_to_bdd(arg, atoms, variables)))
+                .collect();
+            mk_threshold_bdd(variables, args, *threshold)
+        }
+        Nary(op, args) => {
+            let args = args.iter().map(|it| fn_to_bdd(it, atoms, variables));
+            match op {
+                NaryOp::And => args.fold(variables.mk_true(), |acc, it| acc.and(&it)),
+                NaryOp::Or => args.fold(variables.mk_false(), |acc, it| acc.or(&it)),
+            }
+        }
+    }
+}
+
+/// **(internal)** Compute all prime implicants of `bdd` (over `n` variables) using the
+/// recursive cofactor characterisation: the primes of `f` are the primes of `f0 & f1`,
+/// plus the primes of `f0` (resp. `f1`) that are not primes of `f0 & f1`, extended with
+/// the negative (resp. positive) literal of the decision variable.
+fn prime_implicants(bdd: &Bdd, n: usize) -> Vec<Cube> {
+    if bdd.is_false() {
+        return Vec::new();
+    }
+    if bdd.is_true() {
+        return vec![vec![None; n]];
+    }
+    let var = bdd.support_set().into_iter().min().unwrap();
+    let f0 = bdd.var_restrict(var, false);
+    let f1 = bdd.var_restrict(var, true);
+    let both = prime_implicants(&f0.and(&f1), n);
+    let known = both.iter().cloned().collect::<HashSet<_>>();
+    let mut result = both;
+    for (value, cofactor) in [(false, f0), (true, f1)] {
+        for mut cube in prime_implicants(&cofactor, n) {
+            if !known.contains(&cube) {
+                cube[var.to_index()] = Some(value);
+                result.push(cube);
+            }
+        }
+    }
+    result
+}
+
+/// **(internal)** Find a minimum cover of all minterms of the function given by its
+/// `primes`, preferring fewer cubes and then fewer literals.
+fn exact_cover(primes: &[Cube], n: usize) -> Vec<Cube> {
+    let contains = |cube: &Cube, minterm: u32| {
+        cube.iter().enumerate().all(|(i, value)| match value {
+            Some(value) => ((minterm >> i) & 1 == 1) == *value,
+            None => true,
+        })
+    };
+    let minterms = (0..(1u32 << n))
+        .filter(|m| primes.iter().any(|p| contains(p, *m)))
+        .collect::<Vec<_>>();
+    let covers = minterms
+        .iter()
+        .map(|m| {
+            (0..primes.len())
+                .filter(|p| contains(&primes[*p], *m))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    struct Search<'a> {
+        primes: &'a [Cube],
+        minterms: &'a [u32],
+        covers: &'a [Vec<usize>],
+        best: Option<(Vec<usize>, (usize, usize))>,
+    }
+
+    impl Search<'_> {
+        fn run(&mut self, uncovered: &[usize], chosen: &mut Vec<usize>) {
+            if let Some((_, (best_count, _))) = &self.best {
+                let needed = if uncovered.is_empty() { 0 } else { 1 };
+                if chosen.len() + needed > *best_count {
+                    return;
+                }
+            }
+            if uncovered.is_empty() {
+                let literals = chosen.iter().map(|p| literal_count(&self.primes[*p])).sum();
+                let cost = (chosen.len(), literals);
+                if self.best.as_ref().map(|(_, it)| cost < *it).unwrap_or(true) {
+                    self.best = Some((chosen.clone(), cost));
+                }
+                return;
+            }
+            // Branch on the minterm with the fewest covering implicants.
+            let minterm = *uncovered
+                .iter()
+                .min_by_key(|m| self.covers[**m].len())
+                .unwrap();
+            for p in self.covers[minterm].clone() {
+                let rest = uncovered
+                    .iter()
+                    .filter(|m| !self.covers[**m].contains(&p))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                chosen.push(p);
+                self.run(&rest, chosen);
+                chosen.pop();
+            }
+        }
+    }
+
+    let mut search = Search {
+        primes,
+        minterms: &minterms,
+        covers: &covers,
+        best: None,
+    };
+    let uncovered = (0..search.minterms.len()).collect::<Vec<_>>();
+    search.run(&uncovered, &mut Vec::new());
+    let (chosen, _) = search.best.unwrap_or_default();
+    chosen.into_iter().map(|p| primes[p].clone()).collect()
+}
+
+/// **(internal)** The number of literals in a cube.
+fn literal_count(cube: &Cube) -> usize {
+    cube.iter().filter(|it| it.is_some()).count()
+}
+
+/// **(internal)** Sort cubes by the number of literals and then by the literals themselves
+/// (positive literals of earlier atoms go first).
+fn sort_cubes(cubes: &mut [Cube]) {
+    cubes.sort_by_key(|cube| {
+        let key = cube
+            .iter()
+            .map(|it| match it {
+                Some(true) => 0,
+                Some(false) => 1,
+                None => 2,
+            })
+            .collect::<Vec<_>>();
+        (literal_count(cube), key)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BooleanNetwork, FnUpdate};
+    use std::convert::TryFrom;
+
+    fn network() -> BooleanNetwork {
+        BooleanNetwork::try_from(
+            r"
+            a -> x
+            b -> x
+            c -> x
+            d -> x
+            $x: a
+            e -> x
+            f -> x
+            g -> x
+            h -> x
+            i -> x
+            j -> x
+            k -> x
+        ",
+        )
+        .unwrap()
+    }
+
+    fn check_equivalent(bn: &BooleanNetwork, left: &FnUpdate, right: &FnUpdate) {
+        let vars = left.collect_arguments();
+        for valuation in 0..(1u32 << vars.len()) {
+            let values = vars
+                .iter()
+                .enumerate()
+                .map(|(i, var)| (*var, (valuation >> i) & 1 == 1))
+                .collect();
+            assert_eq!(
+                left.evaluate(&values),
+                right.evaluate(&values),
+                "{} != {}",
+                left.to_string(bn),
+                right.to_string(bn)
+            );
+        }
+    }
+
+    #[test]
+    fn test_minimal_dnf_and_cnf() {
+        let bn = network();
+        let parse = |it: &str| FnUpdate::try_from_str(it, &bn).unwrap();
+
+        // Consensus: the `b & c` term is redundant.
+        let f = parse("(a & b) | (!a & c) | (b & c)");
+        assert_eq!(f.to_minimal_dnf().to_string(&bn), "(a & b) | (!a & c)");
+        assert_eq!(f.to_minimal_cnf().to_string(&bn), "(!a | b) & (a | c)");
+
+        let f = parse("(a & b & c) | (a & b & !c) | (a & !b & c) | (a & !b & !c)");
+        assert_eq!(f.to_minimal_dnf().to_string(&bn), "a");
+        assert_eq!(parse("a & !a").to_minimal_dnf().to_string(&bn), "false");
+        assert_eq!(parse("a | !a").to_minimal_dnf().to_string(&bn), "true");
+        assert_eq!(parse("a | !a").to_minimal_cnf().to_string(&bn), "true");
+        assert_eq!(parse("a & !a").to_minimal_cnf().to_string(&bn), "false");
+
+        let f = parse("(a ^ b) <=> (c => d)");
+        check_equivalent(&bn, &f, &f.to_minimal_dnf());
+        check_equivalent(&bn, &f, &f.to_minimal_cnf());
+
+        // A function with more atoms than the exact limit uses the heuristic.
+        let f = parse("(a & b) | (a & !b & c) | (d & e) | (f & g & h) | (i ^ j) | (k & a & b)");
+        let dnf = f.to_minimal_dnf();
+        check_equivalent(&bn, &f, &dnf);
+        assert_eq!(
+            dnf.to_string(&bn),
+            "(a & b) | (a & c) | (d & e) | (i & !j) | (!i & j) | (f & g & h)"
+        );
+    }
+
+    #[test]
+    fn test_prime_implicants() {
+        let bn = network();
+        let parse = |it: &str| FnUpdate::try_from_str(it, &bn).unwrap();
+
+        let f = parse("(a & b) | (!a & c)");
+        let primes = f
+            .prime_implicants()
+            .into_iter()
+            .map(|it| it.to_string(&bn))
+            .collect::<Vec<_>>();
+        assert_eq!(primes, vec!["a & b", "!a & c", "b & c"]);
+        assert_eq!(
+            f.to_blake_canonical_form().to_string(&bn),
+            "(a & b) | (!a & c) | (b & c)"
+        );
+        // Equivalent functions have the same Blake canonical form.
+        let g = parse("!(a => !b) | (!a & c) | (a & b & c)");
+        assert_eq!(f.to_blake_canonical_form(), g.to_blake_canonical_form());
+
+        assert!(parse("a & !a").prime_implicants().is_empty());
+        assert_eq!(
+            parse("a | !a").prime_implicants(),
+            vec![FnUpdate::mk_true()]
+        );
+    }
+
+    #[test]
+    fn test_minimization_with_parameters() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> x
+            b -> x
+            $x: f(a, b) | (g & !f(a, b)) | h(a & a)
+        ",
+        )
+        .unwrap();
+        let x = bn.as_graph().find_variable("x").unwrap();
+        let f = bn.get_update_function(x).as_ref().unwrap();
+        assert_eq!(f.to_minimal_dnf().to_string(&bn), "f(a, b) | g | h(a)");
+        assert_eq!(
+            f.to_blake_canonical_form().to_string(&bn),
+            "f(a, b) | g | h(a)"
+        );
+
+        // The canonical form does not depend on the order of parameter calls.
+        let g = FnUpdate::try_from_str("g | h(a) | f(a, b)", &bn).unwrap();
+        assert_eq!(f.to_blake_canonical_form(), g.to_blake_canonical_form());
+        let f = FnUpdate::try_from_str("f(b, a) | f(a, b)", &bn).unwrap();
+        let g = FnUpdate::try_from_str("f(a, b) | f(b, a)", &bn).unwrap();
+        assert_eq!(f.to_blake_canonical_form(), g.to_blake_canonical_form());
+    }
+}
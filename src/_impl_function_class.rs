@@ -0,0 +1,162 @@
+use crate::FunctionClass::*;
+use crate::{BooleanNetwork, FunctionClass, ParameterId, VariableId};
+use std::convert::TryFrom;
+use std::fmt::{Display, Error, Formatter};
+
+impl FunctionClass {
+    /// All supported function classes.
+    pub fn all() -> [FunctionClass; 4] {
+        [Unate, NestedCanalizing, Threshold, Majority]
+    }
+}
+
+impl Display for FunctionClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let name = match self {
+            Unate => "unate",
+            NestedCanalizing => "nested_canalizing",
+            Threshold => "threshold",
+            Majority => "majority",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for FunctionClass {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim();
+        FunctionClass::all()
+            .into_iter()
+            .find(|it| it.to_string() == value)
+            .ok_or_else(|| format!("Unknown function class `{}`.", value))
+    }
+}
+
+/// Methods for working with function class constraints of uninterpreted functions.
+impl BooleanNetwork {
+    /// Require that the explicit `parameter` belongs to the given function `class`.
+    pub fn add_parameter_class(&mut self, parameter: ParameterId, class: FunctionClass) {
+        self.parameter_classes
+            .entry(parameter)
+            .or_default()
+            .insert(class);
+    }
+
+    /// Require that the implicit update function of `variable` belongs to the given
+    /// function `class`.
+    ///
+    /// Fails if the `variable` has an explicit update function.
+    pub fn add_implicit_class(
+        &mut self,
+        variable: VariableId,
+        class: FunctionClass,
+    ) -> Result<(), String> {
+        if self.get_update_function(variable).is_some() {
+            return Err(format!(
+                "Variable `{}` has an explicit update function.",
+                self.get_variable_name(variable)
+            ));
+        }
+        self.implicit_classes
+            .entry(variable)
+            .or_default()
+            .insert(class);
+        Ok(())
+    }
+
+    /// The function classes required for the explicit `parameter` (sorted).
+    pub fn get_parameter_classes(&self, parameter: ParameterId) -> Vec<FunctionClass> {
+        self.parameter_classes
+            .get(&parameter)
+            .map(|it| it.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The function classes required for the implicit update function of `variable` (sorted).
+    ///
+    /// Note that the classes are ignored when the `variable` has an explicit update function.
+    pub fn get_implicit_classes(&self, variable: VariableId) -> Vec<FunctionClass> {
+        self.implicit_classes
+            .get(&variable)
+            .map(|it| it.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Remove all function class constraints from this network.
+    pub fn clear_function_classes(&mut self) {
+        self.parameter_classes.clear();
+        self.implicit_classes.clear();
+    }
+
+    /// **(internal)** Copy the function classes of all parameters and implicit functions
+    /// from the `source` network to this network. Parameters are matched by name, while
+    /// implicit functions are matched by `VariableId`.
+    pub(crate) fn copy_function_classes(&mut self, source: &BooleanNetwork) {
+        for (parameter, classes) in &source.parameter_classes {
+            let name = source.get_parameter(*parameter).get_name();
+            if let Some(parameter) = self.find_parameter(name) {
+                for class in classes {
+                    self.add_parameter_class(parameter, *class);
+                }
+            }
+        }
+        for (variable, classes) in &source.implicit_classes {
+            for class in classes {
+                // This fails if the variable has an explicit function now, which is fine.
+                let _ = self.add_implicit_class(*variable, *class);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FunctionClass::{Majority, NestedCanalizing, Threshold, Unate};
+    use crate::{BooleanNetwork, FunctionClass};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_function_classes() {
+        for class in FunctionClass::all() {
+            assert_eq!(
+                class,
+                FunctionClass::try_from(class.to_string().as_str()).unwrap()
+            );
+        }
+        assert!(FunctionClass::try_from("canalizing").is_err());
+
+        let model = r"
+            a -> b
+            b -| a
+            a -? c
+            $b: f(a)
+            @f: threshold, unate
+            @c: nested_canalizing
+            @c: majority
+        ";
+        let mut bn = BooleanNetwork::try_from(model).unwrap();
+        let f = bn.find_parameter("f").unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        assert_eq!(vec![Unate, Threshold], bn.get_parameter_classes(f));
+        assert_eq!(vec![NestedCanalizing, Majority], bn.get_implicit_classes(c));
+        assert!(bn.get_implicit_classes(a).is_empty());
+        assert!(bn.add_implicit_class(b, Unate).is_err());
+
+        // Classes are preserved by the `.aeon` format.
+        let copy = BooleanNetwork::try_from(bn.to_string().as_str()).unwrap();
+        assert_eq!(bn, copy);
+
+        bn.clear_function_classes();
+        assert!(bn.get_parameter_classes(f).is_empty());
+        assert_ne!(bn, copy);
+
+        // Unknown names, classes, and explicit functions are not allowed.
+        assert!(BooleanNetwork::try_from("a -> b\n@g: unate").is_err());
+        assert!(BooleanNetwork::try_from("a -> b\n@b: canalizing").is_err());
+        assert!(BooleanNetwork::try_from("a -> b\n$b: a\n@b: unate").is_err());
+    }
+}
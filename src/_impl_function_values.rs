@@ -0,0 +1,192 @@
+use crate::{BooleanNetwork, ParameterId, VariableId};
+use std::collections::BTreeMap;
+
+/// Methods for working with known values (i.e. partial truth tables) of uninterpreted
+/// functions.
+///
+/// The arguments of an explicit parameter are given in the order in which they appear
+/// in the parameter. The arguments of an implicit update function are the regulators of
+/// the variable, sorted by their `VariableId`.
+impl BooleanNetwork {
+    /// Require that the explicit `parameter` evaluates to `value` for the given `args`.
+    ///
+    /// Fails if the number of arguments does not match the arity of the parameter, or if
+    /// a different value is already known for the same arguments.
+    pub fn add_parameter_value(
+        &mut self,
+        parameter: ParameterId,
+        args: &[bool],
+        value: bool,
+    ) -> Result<(), String> {
+        let name = self.get_parameter(parameter).get_name().clone();
+        let arity = self.get_parameter(parameter).get_arity() as usize;
+        let table = self.parameter_values.entry(parameter).or_default();
+        insert_value(table, &name, arity, args, value)
+    }
+
+    /// Require that the implicit update function of `variable` evaluates to `value` for
+    /// the given `args`.
+    ///
+    /// Fails if the `variable` has an explicit update function, if the number of arguments
+    /// does not match the number of its regulators, or if a different value is already known
+    /// for the same arguments.
+    pub fn add_implicit_value(
+        &mut self,
+        variable: VariableId,
+        args: &[bool],
+        value: bool,
+    ) -> Result<(), String> {
+        let name = self.get_variable_name(variable).clone();
+        if self.get_update_function(variable).is_some() {
+            return Err(format!(
+                "Variable `{}` has an explicit update function.",
+                name
+            ));
+        }
+        let arity = self.regulators(variable).len();
+        let table = self.implicit_values.entry(variable).or_default();
+        insert_value(table, &name, arity, args, value)
+    }
+
+    /// The known values of the explicit `parameter` as `(args, value)` pairs
+    /// (sorted by `args`).
+    pub fn get_parameter_values(&self, parameter: ParameterId) -> Vec<(Vec<bool>, bool)> {
+        self.parameter_values
+            .get(&parameter)
+            .map(|it| it.iter().map(|(k, v)| (k.clone(), *v)).collect())
+            .unwrap_or_default()
+    }
+
+    /// The known values of the implicit update function of `variable` as `(args, value)`
+    /// pairs (sorted by `args`).
+    ///
+    /// Note that the values are ignored when the `variable` has an explicit update function.
+    pub fn get_implicit_values(&self, variable: VariableId) -> Vec<(Vec<bool>, bool)> {
+        self.implicit_values
+            .get(&variable)
+            .map(|it| it.iter().map(|(k, v)| (k.clone(), *v)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Remove all known values of uninterpreted functions from this network.
+    pub fn clear_function_values(&mut self) {
+        self.parameter_values.clear();
+        self.implicit_values.clear();
+    }
+
+    /// **(internal)** Copy the known values of all parameters and implicit functions
+    /// from the `source` network to this network. Parameters are matched by name, while
+    /// implicit functions are matched by `VariableId`.
+    pub(crate) fn copy_function_values(&mut self, source: &BooleanNetwork) {
+        for (parameter, values) in &source.parameter_values {
+            let name = source.get_parameter(*parameter).get_name();
+            if let Some(parameter) = self.find_parameter(name) {
+                for (args, value) in values {
+                    // This fails if the parameter has a different arity, which is fine.
+                    let _ = self.add_parameter_value(parameter, args, *value);
+                }
+            }
+        }
+        for (variable, values) in &source.implicit_values {
+            for (args, value) in values {
+                // This fails if the variable has an explicit function now, which is fine.
+                let _ = self.add_implicit_value(*variable, args, *value);
+            }
+        }
+    }
+}
+
+/// **(internal)** Format one row of a function table in the `.aeon` notation,
+/// e.g. `f(1, 0)`.
+pub(crate) fn function_row_to_string(name: &str, args: &[bool]) -> String {
+    let args = args
+        .iter()
+        .map(|it| if *it { "1" } else { "0" })
+        .collect::<Vec<_>>();
+    format!("{}({})", name, args.join(", "))
+}
+
+/// **(internal)** Insert a new row into a partial function `table`, checking that it is
+/// consistent with the function `arity` and the existing rows.
+fn insert_value(
+    table: &mut BTreeMap<Vec<bool>, bool>,
+    name: &str,
+    arity: usize,
+    args: &[bool],
+    value: bool,
+) -> Result<(), String> {
+    if args.len() != arity {
+        return Err(format!(
+            "Function `{}` has {} arguments, but {} were given.",
+            name,
+            arity,
+            args.len()
+        ));
+    }
+    match table.get(args) {
+        Some(old) if *old != value => Err(format!(
+            "Conflicting values given for `{}`.",
+            function_row_to_string(name, args)
+        )),
+        _ => {
+            table.insert(args.to_vec(), value);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_function_values() {
+        let model = r"
+            a -> b
+            b -| a
+            a -? c
+            b -? c
+            $b: f(a, a)
+            @f(1, 0) = 1
+            @f(0,0) = false
+            @c(true, 0) = 0
+        ";
+        let mut bn = BooleanNetwork::try_from(model).unwrap();
+        let f = bn.find_parameter("f").unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        assert_eq!(
+            vec![(vec![false, false], false), (vec![true, false], true)],
+            bn.get_parameter_values(f)
+        );
+        assert_eq!(vec![(vec![true, false], false)], bn.get_implicit_values(c));
+        assert!(bn.get_implicit_values(a).is_empty());
+
+        // Wrong arity, explicit functions and conflicting values are not allowed.
+        assert!(bn.add_parameter_value(f, &[true], true).is_err());
+        assert!(bn.add_implicit_value(b, &[true], true).is_err());
+        assert!(bn.add_implicit_value(c, &[true, false], true).is_err());
+        assert!(bn.add_implicit_value(c, &[true, false], false).is_ok());
+
+        // Values are preserved by the `.aeon` format.
+        let copy = BooleanNetwork::try_from(bn.to_string().as_str()).unwrap();
+        assert_eq!(bn, copy);
+
+        // Values are static constraints.
+        let relaxed = bn.remove_static_constraints();
+        assert!(relaxed.get_parameter_values(f).is_empty());
+        assert!(relaxed.get_implicit_values(c).is_empty());
+
+        bn.clear_function_values();
+        assert!(bn.get_parameter_values(f).is_empty());
+        assert_ne!(bn, copy);
+
+        // Unknown names and invalid constants are not allowed.
+        assert!(BooleanNetwork::try_from("a -> b\n@g(1) = 1").is_err());
+        assert!(BooleanNetwork::try_from("a -> b\n@b(2) = 1").is_err());
+        assert!(BooleanNetwork::try_from("a -> b\n@b(1) = yes").is_err());
+        assert!(BooleanNetwork::try_from("a -> b\n@b() = 1").is_err());
+    }
+}
@@ -0,0 +1,34 @@
+use crate::NaryOp::*;
+use crate::{BinaryOp, NaryOp};
+use std::fmt::{Display, Error, Formatter};
+
+impl NaryOp {
+    /// The binary operator with the same semantics as this n-ary operator.
+    pub fn to_binary(self) -> BinaryOp {
+        match self {
+            And => BinaryOp::And,
+            Or => BinaryOp::Or,
+        }
+    }
+
+    /// The n-ary operator with the same semantics as the given binary operator (if any).
+    pub fn try_from_binary(op: BinaryOp) -> Option<NaryOp> {
+        match op {
+            BinaryOp::And => Some(And),
+            BinaryOp::Or => Some(Or),
+            _ => None,
+        }
+    }
+
+    /// The value of this operator when applied to an empty list of arguments (`true` for
+    /// conjunction, `false` for disjunction).
+    pub fn neutral_value(self) -> bool {
+        self == And
+    }
+}
+
+impl Display for NaryOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}", self.to_binary())
+    }
+}
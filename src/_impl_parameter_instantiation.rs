@@ -0,0 +1,372 @@
+use crate::FnUpdate::*;
+use crate::{BinaryOp, BooleanNetwork, FnUpdate, ParameterId, ParameterInstantiation, VariableId};
+use std::collections::HashMap;
+
+impl ParameterInstantiation {
+    /// Create an empty instantiation (no function has a truth table).
+    pub fn new() -> ParameterInstantiation {
+        ParameterInstantiation::default()
+    }
+
+    /// Set the truth table of an explicit `parameter`. See [ParameterInstantiation] for
+    /// the ordering of table rows.
+    pub fn set_parameter_table(&mut self, parameter: ParameterId, table: Vec<bool>) {
+        self.explicit.insert(parameter, table);
+    }
+
+    /// Set the truth table of the implicit update function of `variable`. The arguments of
+    /// the function are the regulators of `variable` (sorted by their ids).
+    pub fn set_implicit_table(&mut self, variable: VariableId, table: Vec<bool>) {
+        self.implicit.insert(variable, table);
+    }
+
+    /// Get the truth table of an explicit `parameter` (if known).
+    pub fn get_parameter_table(&self, parameter: ParameterId) -> Option<&[bool]> {
+        self.explicit.get(&parameter).map(|it| it.as_slice())
+    }
+
+    /// Get the truth table of the implicit update function of `variable` (if known).
+    pub fn get_implicit_table(&self, variable: VariableId) -> Option<&[bool]> {
+        self.implicit.get(&variable).map(|it| it.as_slice())
+    }
+
+    /// Evaluate an explicit `parameter` for the given argument values. Returns `None` when
+    /// the parameter is not instantiated, or when the number of arguments does not match
+    /// the size of its truth table.
+    pub fn evaluate_parameter(&self, parameter: ParameterId, args: &[bool]) -> Option<bool> {
+        Self::evaluate_table(self.get_parameter_table(parameter)?, args)
+    }
+
+    /// Evaluate the implicit update function of `variable` for the given argument values.
+    /// Returns `None` when the function is not instantiated, or when the number of arguments
+    /// does not match the size of its truth table.
+    pub fn evaluate_implicit(&self, variable: VariableId, args: &[bool]) -> Option<bool> {
+        Self::evaluate_table(self.get_implicit_table(variable)?, args)
+    }
+
+    /// **(internal)** Read the row of `table` given by `args`, assuming the table has exactly
+    /// `2^args.len()` rows.
+    fn evaluate_table(table: &[bool], args: &[bool]) -> Option<bool> {
+        let expected = 1usize.checked_shl(u32::try_from(args.len()).ok()?)?;
+        if table.len() != expected {
+            return None;
+        }
+        Some(table[Self::row_index(args)])
+    }
+
+    /// The index of the truth table row which corresponds to the given argument values.
+    pub fn row_index(args: &[bool]) -> usize {
+        args.iter()
+            .enumerate()
+            .filter(|(_, value)| **value)
+            .map(|(i, _)| 1usize << i)
+            .sum()
+    }
+}
+
+impl FnUpdate {
+    /// Evaluate this function using the given network variable valuation, where the
+    /// parameters are evaluated using the given `instantiation`.
+    ///
+    /// Unlike [FnUpdate::evaluate], the result is always known. However, the method panics
+    /// when the valuation does not contain some of the necessary variables, or when some
+    /// parameter is not instantiated (or its truth table has a wrong size).
+    pub fn evaluate_instantiated(
+        &self,
+        values: &HashMap<VariableId, bool>,
+        instantiation: &ParameterInstantiation,
+    ) -> bool {
+        let eval = |it: &FnUpdate| it.evaluate_instantiated(values, instantiation);
+        match self {
+            Const(value) => *value,
+            Var(id) => *values
+                .get(id)
+                .unwrap_or_else(|| panic!("Missing value of variable {:?}.", id)),
+            Param(id, args) => {
+                let args = args.iter().map(eval).collect::<Vec<_>>();
+                instantiation
+                    .evaluate_parameter(*id, &args)
+                    .unwrap_or_else(|| panic!("Invalid instantiation of parameter {:?}.", id))
+            }
+            Not(inner) => !eval(inner),
+            Binary(op, left, right) => {
+                let (left, right) = (eval(left), eval(right));
+                match op {
+                    BinaryOp::And => left && right,
+                    BinaryOp::Or => left || right,
+                    BinaryOp::Xor => left != right,
+                    BinaryOp::Imp => !left || right,
+                    BinaryOp::Iff => left == right,
+                }
+            }
+            Nary(op, args) => {
+                let neutral = op.neutral_value();
+                if args.iter().any(|it| eval(it) != neutral) {
+                    !neutral
+                } else {
+                    neutral
+                }
+            }
+            Threshold(args, threshold) => {
+                let sum = args
+                    .iter()
+                    .filter(|(_, it)| eval(it))
+                    .map(|(w, _)| i64::from(*w))
+                    .sum::<i64>();
+                sum >= i64::from(*threshold)
+            }
+        }
+    }
+}
+
+impl BooleanNetwork {
+    /// Evaluate the update function of `variable` using the given network variable
+    /// valuation, where all uninterpreted functions (explicit parameters as well as
+    /// implicit update functions) are evaluated using the given `instantiation`.
+    ///
+    /// Panics when a necessary variable value or truth table is missing (or the truth table
+    /// has a wrong size).
+    pub fn evaluate_instantiated(
+        &self,
+        variable: VariableId,
+        values: &HashMap<VariableId, bool>,
+        instantiation: &ParameterInstantiation,
+    ) -> bool {
+        if let Some(function) = self.get_update_function(variable) {
+            function.evaluate_instantiated(values, instantiation)
+        } else {
+            let args = self
+                .regulators(variable)
+                .into_iter()
+                .map(|it| {
+                    *values
+                        .get(&it)
+                        .unwrap_or_else(|| panic!("Missing value of variable {:?}.", it))
+                })
+                .collect::<Vec<_>>();
+            instantiation
+                .evaluate_implicit(variable, &args)
+                .unwrap_or_else(|| {
+                    panic!("Invalid instantiation of variable {:?}.", variable);
+                })
+        }
+    }
+
+    /// Create a copy of this network where all uninterpreted functions (explicit parameters
+    /// and implicit update functions) are substituted using the given `instantiation`.
+    ///
+    /// The resulting network has the same regulatory graph, but no parameters. Every
+    /// substituted truth table is written as a minimal DNF over the arguments of the
+    /// function. The method fails when some truth table is missing or has a wrong size.
+    pub fn instantiate(
+        &self,
+        instantiation: &ParameterInstantiation,
+    ) -> Result<BooleanNetwork, String> {
+        let mut result = BooleanNetwork::new(self.as_graph().clone());
+        for var in self.variables() {
+            let function = if let Some(function) = self.get_update_function(var) {
+                self.instantiate_fn_update(function, instantiation)?
+            } else {
+                let name = self.get_variable_name(var);
+                let table = instantiation
+                    .get_implicit_table(var)
+                    .ok_or_else(|| format!("Missing instantiation of variable `{}`.", name))?;
+                let args = self
+                    .regulators(var)
+                    .into_iter()
+                    .map(FnUpdate::mk_var)
+                    .collect::<Vec<_>>();
+                table_to_fn_update(name, table, &args)?
+            };
+            result.set_update_function(var, Some(function))?;
+        }
+        Ok(result)
+    }
+
+    /// **(internal)** Substitute all parameters in the given `function`.
+    fn instantiate_fn_update(
+        &self,
+        function: &FnUpdate,
+        instantiation: &ParameterInstantiation,
+    ) -> Result<FnUpdate, String> {
+        let rec = |it: &FnUpdate| self.instantiate_fn_update(it, instantiation);
+        Ok(match function {
+            Const(_) | Var(_) => function.clone(),
+            Param(id, args) => {
+                let args = args.iter().map(rec).collect::<Result<Vec<_>, _>>()?;
+                let name = self.get_parameter(*id).get_name();
+                let table = instantiation
+                    .get_parameter_table(*id)
+                    .ok_or_else(|| format!("Missing instantiation of parameter `{}`.", name))?;
+                table_to_fn_update(name, table, &args)?
+            }
+            Not(inner) => rec(inner)?.negation(),
+            Binary(op, left, right) => FnUpdate::mk_binary(*op, rec(left)?, rec(right)?),
+            Nary(op, args) => Nary(*op, args.iter().map(rec).collect::<Result<_, _>>()?),
+            Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(w, it)| rec(it).map(|it| (*w, it)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Threshold(args, *threshold)
+            }
+        })
+    }
+}
+
+/// **(internal)** Write the truth `table` of the function `name` applied to `args` as
+/// a minimal DNF.
+fn table_to_fn_update(name: &str, table: &[bool], args: &[FnUpdate]) -> Result<FnUpdate, String> {
+    let expected = u32::try_from(args.len())
+        .ok()
+        .and_then(|it| 1usize.checked_shl(it))
+        .ok_or_else(|| {
+            format!(
+                "Truth table of `{}` with {} arguments is too large.",
+                name,
+                args.len()
+            )
+        })?;
+    if table.len() != expected {
+        return Err(format!(
+            "Truth table of `{}` has {} rows, but {} were expected.",
+            name,
+            table.len(),
+            expected
+        ));
+    }
+    // First, the table is minimized using placeholder variables (one for each argument),
+    // which are then replaced with the actual arguments.
+    let mut clauses = Vec::new();
+    for (row, value) in table.iter().enumerate() {
+        if !*value {
+            continue;
+        }
+        let literals = (0..args.len())
+            .map(|i| {
+                let literal = FnUpdate::mk_var(VariableId::from_index(i));
+                if row & (1 << i) == 0 {
+                    literal.negation()
+                } else {
+                    literal
+                }
+            })
+            .collect::<Vec<_>>();
+        clauses.push(FnUpdate::mk_nary_conjunction(&literals));
+    }
+    let template = FnUpdate::mk_nary_disjunction(&clauses).to_minimal_dnf();
+    Ok(replace_placeholders(&template, args))
+}
+
+/// **(internal)** Replace every variable `i` in the `template` with `args[i]`.
+fn replace_placeholders(template: &FnUpdate, args: &[FnUpdate]) -> FnUpdate {
+    let rec = |it: &FnUpdate| replace_placeholders(it, args);
+    match template {
+        Const(_) => template.clone(),
+        Var(id) => args[id.to_index()].clone(),
+        Param(id, p_args) => Param(*id, p_args.iter().map(rec).collect()),
+        Not(inner) => rec(inner).negation(),
+        Binary(op, left, right) => FnUpdate::mk_binary(*op, rec(left), rec(right)),
+        Nary(op, n_args) => Nary(*op, n_args.iter().map(rec).collect()),
+        Threshold(t_args, threshold) => Threshold(
+            t_args.iter().map(|(w, it)| (*w, rec(it))).collect(),
+            *threshold,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_impl_parameter_instantiation::table_to_fn_update;
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::{BooleanNetwork, FnUpdate, ParameterInstantiation};
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_instantiated_evaluation() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| a
+            a -> a
+            $a: f(a, !b) | a
+        ",
+        )
+        .unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let f = bn.find_parameter("f").unwrap();
+
+        // f(x, y) = x & y, b = a
+        let mut instantiation = ParameterInstantiation::new();
+        instantiation.set_parameter_table(f, vec![false, false, false, true]);
+        instantiation.set_implicit_table(b, vec![false, true]);
+        assert_eq!(3, ParameterInstantiation::row_index(&[true, true, false]));
+
+        let function = bn.get_update_function(a).as_ref().unwrap();
+        let mut values = HashMap::new();
+        values.insert(a, false);
+        values.insert(b, false);
+        assert_eq!(None, function.evaluate(&values));
+        assert!(!function.evaluate_instantiated(&values, &instantiation));
+        assert!(!bn.evaluate_instantiated(b, &values, &instantiation));
+        values.insert(a, true);
+        assert!(function.evaluate_instantiated(&values, &instantiation));
+        assert!(bn.evaluate_instantiated(b, &values, &instantiation));
+
+        let instantiated = bn.instantiate(&instantiation).unwrap();
+        assert_eq!(0, instantiated.num_parameters());
+        assert_eq!(
+            &FnUpdate::try_from_str("a & !b | a", &instantiated).unwrap(),
+            instantiated.get_update_function(a).as_ref().unwrap()
+        );
+        assert_eq!(
+            &FnUpdate::try_from_str("a", &instantiated).unwrap(),
+            instantiated.get_update_function(b).as_ref().unwrap()
+        );
+
+        // Tables of a wrong size, or a wrong number of arguments, cannot be evaluated.
+        assert_eq!(None, instantiation.evaluate_parameter(f, &[true]));
+        assert_eq!(None, instantiation.evaluate_implicit(b, &[true, true]));
+        instantiation.set_parameter_table(f, vec![true]);
+        assert_eq!(None, instantiation.evaluate_parameter(f, &[true, true]));
+        assert!(bn.instantiate(&instantiation).is_err());
+        assert!(bn.instantiate(&ParameterInstantiation::new()).is_err());
+
+        // Tables of functions with too many arguments cannot exist.
+        let args = vec![FnUpdate::mk_true(); 128];
+        assert!(table_to_fn_update("f", &[true], &args).is_err());
+    }
+
+    #[test]
+    fn test_instantiation_from_colors() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b -| a
+            c -? b
+            $b: f(a, c)
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let mut remaining = stg.mk_unit_colors();
+        while !remaining.is_empty() {
+            let color = remaining.pick_singleton();
+            let instantiation = stg.pick_instantiation(&color);
+            let witness = stg.pick_witness(&color);
+            let instantiated = bn.instantiate(&instantiation).unwrap();
+            let instantiated_stg = SymbolicAsyncGraph::new(&instantiated).unwrap();
+            let witness_stg = SymbolicAsyncGraph::new(&witness).unwrap();
+            for var in bn.variables() {
+                assert_eq!(
+                    instantiated_stg.get_symbolic_fn_update(var),
+                    witness_stg.get_symbolic_fn_update(var)
+                );
+            }
+            remaining = remaining.minus(&color);
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{Monotonicity, Regulation, RegulatoryGraph, VariableId, ID_REGEX_STR};
 use regex::Regex;
 
-/// **(internal)** Regex which matches the regulation arrow string with `monotonicity`
-/// and `observable` groups.
-const REGULATION_ARROW_REGEX_STR: &str = r"-(?P<monotonicity>[|>?])(?P<observable>\??)";
+/// **(internal)** Regex which matches the regulation arrow string with `uncertain`,
+/// `monotonicity` and `observable` groups.
+const REGULATION_ARROW_REGEX_STR: &str =
+    r"(?P<uncertain>\??)-(?P<monotonicity>[|>?])(?P<observable>\??)";
 
 lazy_static! {
     /// **(internal)** A regex which reads one line specifying a regulation.
@@ -25,6 +26,11 @@ impl Regulation {
         self.observable
     }
 
+    /// Check if the regulation is marked as uncertain, i.e. it may or may not exist.
+    pub fn is_uncertain(&self) -> bool {
+        self.uncertain
+    }
+
     /// Return monotonicity of the regulation (if specified).
     pub fn get_monotonicity(&self) -> Option<Monotonicity> {
         self.monotonicity
@@ -48,9 +54,25 @@ impl Regulation {
     ///
     /// The returned data correspond to the items as they appear in the string, i.e. `regulator`,
     /// `monotonicity`, `observability` and `target`. If the string is not valid, returns `None`.
+    /// Uncertain regulations are not valid here, see [Regulation::try_from_uncertain_string].
     pub fn try_from_string(
         regulation: &str,
     ) -> Option<(String, Option<Monotonicity>, bool, String)> {
+        match Self::try_from_uncertain_string(regulation) {
+            Some((r, false, m, o, t)) => Some((r, m, o, t)),
+            _ => None,
+        }
+    }
+
+    /// Try to read all available information about a possibly uncertain regulation from
+    /// a given string in the standard format.
+    ///
+    /// The returned data correspond to the items as they appear in the string, i.e. `regulator`,
+    /// `uncertainty`, `monotonicity`, `observability` and `target`. If the string is not valid,
+    /// returns `None`.
+    pub fn try_from_uncertain_string(
+        regulation: &str,
+    ) -> Option<(String, bool, Option<Monotonicity>, bool, String)> {
         REGULATION_REGEX
             .captures(regulation.trim())
             .map(|captures| {
@@ -61,8 +83,10 @@ impl Regulation {
                     _ => unreachable!("Nothing else matches this group."),
                 };
                 let observable = captures["observable"].is_empty();
+                let uncertain = !captures["uncertain"].is_empty();
                 (
                     captures["regulator"].to_string(),
+                    uncertain,
                     monotonicity,
                     observable,
                     captures["target"].to_string(),
@@ -78,9 +102,11 @@ impl Regulation {
             Some(Monotonicity::Inhibition) => "|",
         };
         let observability = if self.is_observable() { "" } else { "?" };
+        let uncertainty = if self.is_uncertain() { "?" } else { "" };
         format!(
-            "{} -{}{} {}",
+            "{} {}-{}{} {}",
             context.get_variable_name(self.regulator),
+            uncertainty,
             monotonicity,
             observability,
             context.get_variable_name(self.target)
@@ -118,4 +144,28 @@ mod tests {
 
         assert_eq!(None, Regulation::try_from_string("a --> b"));
     }
+    #[test]
+    fn uncertain_regulation_conversion() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> b
+            b ?-| a
+            a ?-?? a
+        ",
+        )
+        .unwrap();
+
+        for regulation in bn.graph.regulations() {
+            let string = regulation.to_string(bn.as_graph());
+            let (r, u, m, o, t) = Regulation::try_from_uncertain_string(&string).unwrap();
+            assert_eq!(&r, bn.get_variable_name(regulation.get_regulator()));
+            assert_eq!(&t, bn.get_variable_name(regulation.get_target()));
+            assert_eq!(u, regulation.is_uncertain());
+            assert_eq!(m, regulation.get_monotonicity());
+            assert_eq!(o, regulation.is_observable());
+            assert_eq!(u, Regulation::try_from_string(&string).is_none());
+        }
+
+        assert_eq!(None, Regulation::try_from_uncertain_string("a ??-> b"));
+    }
 }
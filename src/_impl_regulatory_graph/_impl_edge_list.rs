@@ -0,0 +1,357 @@
+#[cfg(feature = "json")]
+use crate::sbml::Layout;
+use crate::{Monotonicity, RegulatoryGraph};
+#[cfg(feature = "json")]
+use serde_json::json;
+use std::collections::HashSet;
+
+/// **(internal)** A single edge of an imported edge list.
+struct Edge {
+    source: String,
+    target: String,
+    monotonicity: Option<Monotonicity>,
+    observable: bool,
+}
+
+impl RegulatoryGraph {
+    /// Try to read a `RegulatoryGraph` from a SIF (simple interaction format) file.
+    ///
+    /// Each line has the form `source interaction target_1 ... target_k` (tab or space
+    /// separated), or only contains the name of a single node. The interaction is
+    /// `activation` (also `activates`, `positive`, `+` or `->`), `inhibition` (also
+    /// `inhibits`, `negative`, `-` or `-|`), or `unknown` (also `?` or `-?`), and is
+    /// case insensitive. A line can end with an extra `observable=true` or `observable=false`
+    /// column which sets the observability of all its regulations (as written by
+    /// [RegulatoryGraph::to_sif]). Without this column, regulations are observable. Empty
+    /// lines and lines starting with `#` are ignored.
+    ///
+    /// Variables are sorted alphabetically.
+    pub fn try_from_sif(sif: &str) -> Result<RegulatoryGraph, String> {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        for line in sif.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut segments = if line.contains('\t') {
+                line.split('\t').map(|it| it.trim()).collect::<Vec<_>>()
+            } else {
+                line.split_whitespace().collect::<Vec<_>>()
+            };
+            let observable = match segments
+                .last()
+                .and_then(|it| it.strip_prefix("observable="))
+            {
+                Some(value) => {
+                    segments.pop();
+                    parse_observability(value)
+                        .ok_or_else(|| format!("Invalid observability in line `{}`.", line))?
+                }
+                None => true,
+            };
+            if segments.len() == 1 {
+                nodes.push(segments[0].to_string());
+            } else if segments.len() == 2 {
+                return Err(format!("Missing interaction target in line `{}`.", line));
+            } else {
+                let monotonicity = parse_interaction(segments[1])?;
+                for target in &segments[2..] {
+                    edges.push(Edge {
+                        source: segments[0].to_string(),
+                        target: target.to_string(),
+                        monotonicity,
+                        observable,
+                    });
+                }
+            }
+        }
+        build_graph(nodes, edges)
+    }
+
+    /// Try to read a `RegulatoryGraph` from a CSV edge list.
+    ///
+    /// The file must start with a `source,target,interaction` header, optionally followed
+    /// by an `observable` column. The interaction types are the same as in
+    /// [RegulatoryGraph::try_from_sif], and observability is `true`/`false` (or `1`/`0`).
+    /// When the observability column is missing, all regulations are observable. Empty
+    /// lines and lines starting with `#` are ignored.
+    ///
+    /// Variables are sorted alphabetically.
+    pub fn try_from_edge_list_csv(csv: &str) -> Result<RegulatoryGraph, String> {
+        let mut lines = csv
+            .lines()
+            .map(|it| it.trim())
+            .filter(|it| !it.is_empty() && !it.starts_with('#'));
+        let header = lines
+            .next()
+            .ok_or_else(|| "Missing edge list header.".to_string())?
+            .split(',')
+            .map(|it| it.trim().to_lowercase())
+            .collect::<Vec<_>>();
+        let has_observability = if header == ["source", "target", "interaction"] {
+            false
+        } else if header == ["source", "target", "interaction", "observable"] {
+            true
+        } else {
+            return Err(format!(
+                "Expected `source,target,interaction[,observable]` header, but found `{}`.",
+                header.join(",")
+            ));
+        };
+
+        let mut edges = Vec::new();
+        for line in lines {
+            let segments = line.split(',').map(|it| it.trim()).collect::<Vec<_>>();
+            if segments.len() != header.len() {
+                return Err(format!("Unexpected line: `{}`", line));
+            }
+            let observable = if has_observability {
+                parse_observability(segments[3])
+                    .ok_or_else(|| format!("Invalid observability in line `{}`.", line))?
+            } else {
+                true
+            };
+            edges.push(Edge {
+                source: segments[0].to_string(),
+                target: segments[1].to_string(),
+                monotonicity: parse_interaction(segments[2])?,
+                observable,
+            });
+        }
+        build_graph(Vec::new(), edges)
+    }
+
+    /// Export this graph in the SIF (simple interaction format), one tab-separated
+    /// `source interaction target observable=<bool>` line per regulation, using `activation`,
+    /// `inhibition` and `unknown` interaction types. Variables without any regulation are
+    /// written as single-node lines.
+    ///
+    /// The observability column is an extension of SIF understood by
+    /// [RegulatoryGraph::try_from_sif].
+    pub fn to_sif(&self) -> String {
+        let mut result = String::new();
+        for var in self.variables() {
+            if self.regulators(var).is_empty() && self.targets(var).is_empty() {
+                result.push_str(format!("{}\n", self.get_variable_name(var)).as_str());
+            }
+        }
+        for regulation in self.regulations() {
+            result.push_str(
+                format!(
+                    "{}\t{}\t{}\tobservable={}\n",
+                    self.get_variable_name(regulation.get_regulator()),
+                    interaction_name(regulation.get_monotonicity()),
+                    self.get_variable_name(regulation.get_target()),
+                    regulation.is_observable(),
+                )
+                .as_str(),
+            );
+        }
+        result
+    }
+
+    /// Export this graph as a CSV edge list with a `source,target,interaction,observable`
+    /// header (see [RegulatoryGraph::try_from_edge_list_csv]).
+    ///
+    /// Note that variables without any regulations are not part of the edge list.
+    pub fn to_edge_list_csv(&self) -> String {
+        let mut result = "source,target,interaction,observable\n".to_string();
+        for regulation in self.regulations() {
+            result.push_str(
+                format!(
+                    "{},{},{},{}\n",
+                    self.get_variable_name(regulation.get_regulator()),
+                    self.get_variable_name(regulation.get_target()),
+                    interaction_name(regulation.get_monotonicity()),
+                    regulation.is_observable(),
+                )
+                .as_str(),
+            );
+        }
+        result
+    }
+
+    /// Export this graph as a Cytoscape.js JSON document (the `elements` format).
+    ///
+    /// Nodes use the variable names as identifiers. Edges have `interaction` (`activation`,
+    /// `inhibition` or `unknown`) and `observable` data attributes. If a `layout` is given,
+    /// node positions are included for all variables present in the layout.
+    ///
+    /// Requires the `json` feature (enabled by default).
+    #[cfg(feature = "json")]
+    pub fn to_cytoscape_json(&self, layout: Option<&Layout>) -> String {
+        let nodes = self
+            .variables()
+            .map(|var| {
+                let name = self.get_variable_name(var);
+                let mut node = json!({ "data": { "id": name, "name": name } });
+                if let Some((x, y)) = layout.and_then(|it| it.get(name)) {
+                    node["position"] = json!({ "x": x, "y": y });
+                }
+                node
+            })
+            .collect::<Vec<_>>();
+        let edges = self
+            .regulations()
+            .map(|regulation| {
+                let source = self.get_variable_name(regulation.get_regulator());
+                let target = self.get_variable_name(regulation.get_target());
+                json!({
+                    "data": {
+                        "id": format!("{}__{}", source, target),
+                        "source": source,
+                        "target": target,
+                        "interaction": interaction_name(regulation.get_monotonicity()),
+                        "observable": regulation.is_observable(),
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "elements": { "nodes": nodes, "edges": edges } }).to_string()
+    }
+}
+
+/// **(internal)** Name of the interaction type corresponding to the given `monotonicity`.
+fn interaction_name(monotonicity: Option<Monotonicity>) -> &'static str {
+    match monotonicity {
+        Some(Monotonicity::Activation) => "activation",
+        Some(Monotonicity::Inhibition) => "inhibition",
+        None => "unknown",
+    }
+}
+
+/// **(internal)** Parse an interaction type of an edge list.
+fn parse_interaction(interaction: &str) -> Result<Option<Monotonicity>, String> {
+    match interaction.to_lowercase().as_str() {
+        "activation" | "activates" | "positive" | "+" | "->" => Ok(Some(Monotonicity::Activation)),
+        "inhibition" | "inhibits" | "negative" | "-" | "-|" => Ok(Some(Monotonicity::Inhibition)),
+        "unknown" | "?" | "-?" => Ok(None),
+        _ => Err(format!("Unknown interaction type `{}`.", interaction)),
+    }
+}
+
+/// **(internal)** Parse the observability of an edge list regulation.
+fn parse_observability(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// **(internal)** Build a `RegulatoryGraph` from the imported nodes and edges.
+fn build_graph(nodes: Vec<String>, edges: Vec<Edge>) -> Result<RegulatoryGraph, String> {
+    let mut variables = nodes.into_iter().collect::<HashSet<_>>();
+    for edge in &edges {
+        variables.insert(edge.source.clone());
+        variables.insert(edge.target.clone());
+    }
+    for name in &variables {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Invalid variable name `{}`.", name));
+        }
+    }
+    let mut variables = variables.into_iter().collect::<Vec<_>>();
+    variables.sort();
+    let mut graph = RegulatoryGraph::new(variables);
+    for edge in edges {
+        graph.add_regulation(
+            edge.source.as_str(),
+            edge.target.as_str(),
+            edge.observable,
+            edge.monotonicity,
+        )?;
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "json")]
+    use crate::sbml::Layout;
+    use crate::RegulatoryGraph;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_sif_import_export() {
+        let sif = "# comment\nA\tactivation\tB\tC\nC inhibits A\nB ? B\nD\n";
+        let rg = RegulatoryGraph::try_from_sif(sif).unwrap();
+        let expected = RegulatoryGraph::try_from("A -> B\nA -> C\nC -| A\nB -? B").unwrap();
+        assert_eq!(rg.num_vars(), 4);
+        assert_eq!(rg.regulations().count(), 4);
+        for regulation in expected.regulations() {
+            let regulator = expected.get_variable_name(regulation.get_regulator());
+            let target = expected.get_variable_name(regulation.get_target());
+            let imported = rg.find_regulation(
+                rg.find_variable(regulator).unwrap(),
+                rg.find_variable(target).unwrap(),
+            );
+            assert_eq!(
+                imported.unwrap().get_monotonicity(),
+                regulation.get_monotonicity()
+            );
+        }
+
+        let exported = rg.to_sif();
+        assert!(exported.starts_with("D\n"));
+        assert!(exported.contains("A\tactivation\tB\tobservable=true\n"));
+        assert!(exported.contains("B\tunknown\tB\tobservable=true\n"));
+        assert_eq!(RegulatoryGraph::try_from_sif(&exported).unwrap(), rg);
+
+        // Observability is preserved.
+        let rg = RegulatoryGraph::try_from("a -> b\nb -|? a\nc -?? a").unwrap();
+        let exported = rg.to_sif();
+        assert!(exported.contains("b\tinhibition\ta\tobservable=false\n"));
+        assert_eq!(RegulatoryGraph::try_from_sif(&exported).unwrap(), rg);
+        let rg = RegulatoryGraph::try_from_sif("x + y z observable=false").unwrap();
+        assert!(rg.regulations().all(|it| !it.is_observable()));
+        assert!(RegulatoryGraph::try_from_sif("x + y observable=maybe").is_err());
+
+        assert!(RegulatoryGraph::try_from_sif("A activation").is_err());
+        assert!(RegulatoryGraph::try_from_sif("A stimulates B").is_err());
+        assert!(RegulatoryGraph::try_from_sif("A->B activation C").is_err());
+        assert!(RegulatoryGraph::try_from_sif("A + B\nA - B").is_err());
+    }
+
+    #[test]
+    fn test_edge_list_import_export() {
+        let rg = RegulatoryGraph::try_from("a -> b\nb -|? a\nc -?? a").unwrap();
+        let csv = rg.to_edge_list_csv();
+        assert_eq!(
+            csv,
+            "source,target,interaction,observable\na,b,activation,true\nb,a,inhibition,false\nc,a,unknown,false\n"
+        );
+        assert_eq!(RegulatoryGraph::try_from_edge_list_csv(&csv).unwrap(), rg);
+
+        let csv = "Source,Target,Interaction\nx,y,+\n";
+        let rg = RegulatoryGraph::try_from_edge_list_csv(csv).unwrap();
+        let x = rg.find_variable("x").unwrap();
+        let y = rg.find_variable("y").unwrap();
+        assert!(rg.find_regulation(x, y).unwrap().is_observable());
+
+        assert!(RegulatoryGraph::try_from_edge_list_csv("a,b\nx,y").is_err());
+        assert!(RegulatoryGraph::try_from_edge_list_csv(
+            "source,target,interaction,observable\nx,y,+,maybe"
+        )
+        .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_cytoscape_export() {
+        let rg = RegulatoryGraph::try_from("a -> b\nb -|? a").unwrap();
+        let mut layout = Layout::new();
+        layout.insert("a".to_string(), (1.0, 2.5));
+        let json = rg.to_cytoscape_json(Some(&layout));
+        assert!(json.starts_with("{\"elements\":{\"nodes\":["));
+        assert!(json.contains(
+            "{\"data\":{\"id\":\"a\",\"name\":\"a\"},\"position\":{\"x\":1.0,\"y\":2.5}}"
+        ));
+        assert!(json.contains("{\"data\":{\"id\":\"b\",\"name\":\"b\"}}"));
+        assert!(json.contains(
+            "\"source\":\"b\",\"target\":\"a\",\"interaction\":\"inhibition\",\"observable\":false"
+        ));
+        assert!(!rg.to_cytoscape_json(None).contains("position"));
+    }
+}
@@ -0,0 +1,421 @@
+use crate::sbml::Layout;
+use crate::{Monotonicity, Regulation, RegulatoryGraph, VariableId};
+use std::collections::{HashMap, HashSet};
+
+/// **(internal)** Colors used to distinguish strongly connected components.
+const SCC_PALETTE: [&str; 8] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+];
+
+/// A configurable export of a [RegulatoryGraph] into the GraphViz `.dot` or `GraphML` format.
+///
+/// Create the export using [RegulatoryGraph::export], configure it using the `with_*`
+/// methods, and then produce the result using [RegulatoryGraphExport::to_dot] or
+/// [RegulatoryGraphExport::to_graphml]:
+///
+/// ```rust
+/// # use biodivine_lib_param_bn::RegulatoryGraph;
+/// # use std::convert::TryFrom;
+/// let rg = RegulatoryGraph::try_from("a -> b \n b -| a \n b ->? c").unwrap();
+/// let fvs = rg.feedback_vertex_set();
+/// let dot = rg.export()
+///     .with_scc_colors()
+///     .with_highlighted_variables(&fvs)
+///     .to_dot();
+/// assert!(dot.starts_with("digraph G {"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RegulatoryGraphExport<'a> {
+    graph: &'a RegulatoryGraph,
+    layout: Option<&'a Layout>,
+    scc_colors: bool,
+    highlighted_variables: HashSet<VariableId>,
+    highlighted_regulations: HashSet<(VariableId, VariableId)>,
+    non_observable_style: String,
+}
+
+impl RegulatoryGraph {
+    /// Create a configurable [RegulatoryGraphExport] of this graph.
+    pub fn export(&self) -> RegulatoryGraphExport<'_> {
+        RegulatoryGraphExport {
+            graph: self,
+            layout: None,
+            scc_colors: false,
+            highlighted_variables: HashSet::new(),
+            highlighted_regulations: HashSet::new(),
+            non_observable_style: "dashed".to_string(),
+        }
+    }
+}
+
+impl<'a> RegulatoryGraphExport<'a> {
+    /// Use node positions from the given `layout`. Variables that are not in the layout
+    /// have no position.
+    ///
+    /// In `.dot`, the positions are written as fixed (`pos="x,y!"`) with the `y` axis
+    /// flipped (the `Layout` uses the SBML convention where `y` grows downwards).
+    pub fn with_layout(mut self, layout: &'a Layout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    /// Color the nodes of each strongly connected component that contains a cycle
+    /// (see [RegulatoryGraph::strongly_connected_components]) using a distinct color.
+    /// This includes single-variable components with a self-loop.
+    pub fn with_scc_colors(mut self) -> Self {
+        self.scc_colors = true;
+        self
+    }
+
+    /// Highlight a cycle of the graph, given as a sequence of variables (e.g. a result
+    /// of [RegulatoryGraph::shortest_cycle]). All variables of the cycle and the regulations
+    /// between consecutive variables (including the last and the first one) are highlighted.
+    pub fn with_highlighted_cycle(mut self, cycle: &[VariableId]) -> Self {
+        for (i, var) in cycle.iter().enumerate() {
+            let next = cycle[(i + 1) % cycle.len()];
+            self.highlighted_variables.insert(*var);
+            self.highlighted_regulations.insert((*var, next));
+        }
+        self
+    }
+
+    /// Highlight the given set of variables (e.g. a result of
+    /// [RegulatoryGraph::feedback_vertex_set]).
+    pub fn with_highlighted_variables(mut self, variables: &HashSet<VariableId>) -> Self {
+        self.highlighted_variables.extend(variables);
+        self
+    }
+
+    /// Highlight the given regulations (e.g. a result of [RegulatoryGraph::feedback_arc_set]).
+    pub fn with_highlighted_regulations(mut self, regulations: &[Regulation]) -> Self {
+        self.highlighted_regulations.extend(
+            regulations
+                .iter()
+                .map(|it| (it.get_regulator(), it.get_target())),
+        );
+        self
+    }
+
+    /// Set the GraphViz line style of non-observable regulations (`dashed` by default, use
+    /// e.g. `dotted`, or `solid` to disable the distinction).
+    pub fn with_non_observable_style(mut self, style: &str) -> Self {
+        self.non_observable_style = style.to_string();
+        self
+    }
+
+    /// Produce the `.dot` representation of the graph.
+    ///
+    /// Positive and negative regulations use green and red color (and normal and tee arrows),
+    /// regulations with unknown monotonicity are grey. Highlighted nodes and edges are drawn
+    /// with a thicker line.
+    pub fn to_dot(&self) -> String {
+        let scc_colors = self.compute_scc_colors();
+        let mut result = "digraph G {\n".to_string();
+        for var in self.graph.variables() {
+            let mut attributes = vec![
+                "shape=box".to_string(),
+                format!(
+                    "label=\"{}\"",
+                    escape_dot(self.graph.get_variable_name(var))
+                ),
+                "style=filled".to_string(),
+            ];
+            if let Some(color) = scc_colors.get(&var) {
+                attributes.push(format!("fillcolor=\"{}\"", color));
+            }
+            if self.highlighted_variables.contains(&var) {
+                attributes.push("penwidth=3".to_string());
+            }
+            if let Some((x, y)) = self.position(var) {
+                attributes.push(format!("pos=\"{},{}!\"", x, -y));
+            }
+            result.push_str(format!("v{} [{}];\n", var.to_index(), attributes.join(", ")).as_str());
+        }
+        for regulation in self.graph.regulations() {
+            let style = if regulation.is_observable() {
+                "filled"
+            } else {
+                self.non_observable_style.as_str()
+            };
+            let (color, arrow) = match regulation.get_monotonicity() {
+                Some(Monotonicity::Activation) => ("#4abd73", "normal"),
+                Some(Monotonicity::Inhibition) => ("#d05d5d", "tee"),
+                None => ("#797979", "empty"),
+            };
+            let mut attributes = vec![
+                format!("style=\"{}\"", escape_dot(style)),
+                format!("color=\"{}\"", color),
+                format!("arrowhead=\"{}\"", arrow),
+            ];
+            if self.is_highlighted(regulation) {
+                attributes.push("penwidth=3".to_string());
+            }
+            result.push_str(
+                format!(
+                    "v{} -> v{} [{}];\n",
+                    regulation.get_regulator().to_index(),
+                    regulation.get_target().to_index(),
+                    attributes.join(", ")
+                )
+                .as_str(),
+            );
+        }
+        result.push_str("}\n");
+        result
+    }
+
+    /// Produce the `GraphML` representation of the graph.
+    ///
+    /// Nodes have `name`, `color` (SCC color, if enabled), `scc` (index of the SCC with
+    /// a cycle, including self-loops, or `-1`), `highlighted` and optionally `x`/`y` attributes. Edges have
+    /// `monotonicity` (`activation`, `inhibition` or `unknown`), `observable`, `style`
+    /// and `highlighted` attributes.
+    pub fn to_graphml(&self) -> String {
+        let scc_colors = self.compute_scc_colors();
+        let scc_index = self.compute_scc_index();
+        let mut result = String::new();
+        result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        result.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        let keys = [
+            ("name", "node", "name", "string"),
+            ("color", "node", "color", "string"),
+            ("scc", "node", "scc", "int"),
+            ("highlighted", "node", "highlighted", "boolean"),
+            ("x", "node", "x", "double"),
+            ("y", "node", "y", "double"),
+            ("monotonicity", "edge", "monotonicity", "string"),
+            ("observable", "edge", "observable", "boolean"),
+            ("style", "edge", "style", "string"),
+            ("edge_highlighted", "edge", "highlighted", "boolean"),
+        ];
+        for (id, target, name, kind) in keys {
+            result.push_str(
+                format!(
+                    "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+                    id, target, name, kind
+                )
+                .as_str(),
+            );
+        }
+        result.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+        for var in self.graph.variables() {
+            result.push_str(format!("    <node id=\"v{}\">\n", var.to_index()).as_str());
+            write_data(&mut result, "name", self.graph.get_variable_name(var));
+            if let Some(color) = scc_colors.get(&var) {
+                write_data(&mut result, "color", color);
+            }
+            let scc = scc_index.get(&var).map(|it| *it as i64).unwrap_or(-1);
+            write_data(&mut result, "scc", &scc.to_string());
+            let highlighted = self.highlighted_variables.contains(&var);
+            write_data(&mut result, "highlighted", &highlighted.to_string());
+            if let Some((x, y)) = self.position(var) {
+                write_data(&mut result, "x", &x.to_string());
+                write_data(&mut result, "y", &y.to_string());
+            }
+            result.push_str("    </node>\n");
+        }
+        for regulation in self.graph.regulations() {
+            result.push_str(
+                format!(
+                    "    <edge source=\"v{}\" target=\"v{}\">\n",
+                    regulation.get_regulator().to_index(),
+                    regulation.get_target().to_index()
+                )
+                .as_str(),
+            );
+            let monotonicity = match regulation.get_monotonicity() {
+                Some(Monotonicity::Activation) => "activation",
+                Some(Monotonicity::Inhibition) => "inhibition",
+                None => "unknown",
+            };
+            let style = if regulation.is_observable() {
+                "solid"
+            } else {
+                self.non_observable_style.as_str()
+            };
+            write_data(&mut result, "monotonicity", monotonicity);
+            write_data(
+                &mut result,
+                "observable",
+                &regulation.is_observable().to_string(),
+            );
+            write_data(&mut result, "style", style);
+            let highlighted = self.is_highlighted(regulation);
+            write_data(&mut result, "edge_highlighted", &highlighted.to_string());
+            result.push_str("    </edge>\n");
+        }
+        result.push_str("  </graph>\n");
+        result.push_str("</graphml>\n");
+        result
+    }
+
+    /// **(internal)** Position of a variable in the layout (if any).
+    fn position(&self, var: VariableId) -> Option<(f64, f64)> {
+        self.layout
+            .and_then(|it| it.get(self.graph.get_variable_name(var)))
+            .cloned()
+    }
+
+    /// **(internal)** Check if the given regulation should be highlighted.
+    fn is_highlighted(&self, regulation: &Regulation) -> bool {
+        self.highlighted_regulations
+            .contains(&(regulation.get_regulator(), regulation.get_target()))
+    }
+
+    /// **(internal)** Map the variables of SCCs with a cycle (including self-loops) to their index.
+    fn compute_scc_index(&self) -> HashMap<VariableId, usize> {
+        let mut result = HashMap::new();
+        for (i, component) in self
+            .graph
+            .strongly_connected_components()
+            .iter()
+            .enumerate()
+        {
+            for var in component {
+                result.insert(*var, i);
+            }
+        }
+        result
+    }
+
+    /// **(internal)** Map the variables of SCCs with a cycle to their color (if enabled).
+    fn compute_scc_colors(&self) -> HashMap<VariableId, &'static str> {
+        if !self.scc_colors {
+            return HashMap::new();
+        }
+        self.compute_scc_index()
+            .into_iter()
+            .map(|(var, i)| (var, SCC_PALETTE[i % SCC_PALETTE.len()]))
+            .collect()
+    }
+}
+
+/// **(internal)** Write a single GraphML `data` element. The `value` is escaped, since it
+/// can contain arbitrary user-provided text (e.g. the non-observable edge style).
+fn write_data(output: &mut String, key: &str, value: &str) {
+    output.push_str(format!("      <data key=\"{}\">{}</data>\n", key, escape_xml(value)).as_str());
+}
+
+/// **(internal)** Escape the special XML characters in a text value.
+pub(crate) fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// **(internal)** Escape a `.dot` string value, such that it can be written in double quotes.
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sbml::Layout;
+    use crate::RegulatoryGraph;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_configurable_dot_export() {
+        let rg = RegulatoryGraph::try_from("a -> b\nb -|? a\nb -> c\nc -? c").unwrap();
+        let a = rg.find_variable("a").unwrap();
+        let b = rg.find_variable("b").unwrap();
+
+        // Default settings reproduce the basic styling.
+        let dot = rg.export().to_dot();
+        assert!(dot.contains("v0 [shape=box, label=\"a\", style=filled];\n"));
+        assert!(
+            dot.contains("v1 -> v0 [style=\"dashed\", color=\"#d05d5d\", arrowhead=\"tee\"];\n")
+        );
+
+        let mut layout = Layout::new();
+        layout.insert("a".to_string(), (10.0, 20.0));
+        let cycle = rg.shortest_cycle(a).unwrap();
+        let dot = rg
+            .export()
+            .with_layout(&layout)
+            .with_scc_colors()
+            .with_highlighted_cycle(&cycle)
+            .with_non_observable_style("dotted")
+            .to_dot();
+        let expected = "v0 [shape=box, label=\"a\", style=filled, fillcolor=\"#ffffb3\", ";
+        assert!(dot.contains(format!("{}penwidth=3, pos=\"10,-20!\"];\n", expected).as_str()));
+        // `c` is a trivial SCC with a self-loop, which is still colored.
+        assert!(dot.contains("v2 [shape=box, label=\"c\", style=filled, fillcolor="));
+        assert!(dot.contains(
+            "v1 -> v0 [style=\"dotted\", color=\"#d05d5d\", arrowhead=\"tee\", penwidth=3];\n"
+        ));
+        assert!(
+            dot.contains("v1 -> v2 [style=\"filled\", color=\"#4abd73\", arrowhead=\"normal\"];\n")
+        );
+
+        let fas = rg.feedback_arc_set();
+        let dot = rg
+            .export()
+            .with_highlighted_variables(&[b].into_iter().collect())
+            .with_highlighted_regulations(&fas)
+            .to_dot();
+        assert!(dot.contains("v1 [shape=box, label=\"b\", style=filled, penwidth=3];\n"));
+        assert_eq!(dot.matches("penwidth=3").count(), 1 + fas.len());
+
+        // User-provided styles are escaped.
+        let dot = rg.export().with_non_observable_style("a\"b\\").to_dot();
+        assert!(dot.contains("v1 -> v0 [style=\"a\\\"b\\\\\", "));
+    }
+
+    #[test]
+    fn test_graphml_export() {
+        let rg = RegulatoryGraph::try_from("a -> b\nb -|? a\nb -> c").unwrap();
+        let mut layout = Layout::new();
+        layout.insert("c".to_string(), (1.5, 2.0));
+        let graphml = rg
+            .export()
+            .with_layout(&layout)
+            .with_scc_colors()
+            .to_graphml();
+        let document = roxmltree::Document::parse(&graphml).unwrap();
+        let nodes = document
+            .descendants()
+            .filter(|it| it.has_tag_name("node"))
+            .collect::<Vec<_>>();
+        let edges = document
+            .descendants()
+            .filter(|it| it.has_tag_name("edge"))
+            .collect::<Vec<_>>();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(edges.len(), 3);
+        let data = |node: &roxmltree::Node, key: &str| {
+            node.children()
+                .find(|it| it.attribute("key") == Some(key))
+                .and_then(|it| it.text())
+                .map(|it| it.to_string())
+        };
+        assert_eq!(data(&nodes[0], "scc"), Some("0".to_string()));
+        assert_eq!(data(&nodes[2], "scc"), Some("-1".to_string()));
+        assert_eq!(data(&nodes[2], "color"), None);
+        assert_eq!(data(&nodes[2], "x"), Some("1.5".to_string()));
+        assert_eq!(data(&edges[1], "observable"), Some("false".to_string()));
+        assert_eq!(data(&edges[1], "style"), Some("dashed".to_string()));
+        assert_eq!(
+            data(&edges[1], "monotonicity"),
+            Some("inhibition".to_string())
+        );
+
+        // User-provided values are escaped.
+        let graphml = rg
+            .export()
+            .with_non_observable_style("<dashed & \"dotted\">")
+            .to_graphml();
+        let document = roxmltree::Document::parse(&graphml).unwrap();
+        let edge = document
+            .descendants()
+            .filter(|it| it.has_tag_name("edge"))
+            .nth(1)
+            .unwrap();
+        assert_eq!(
+            data(&edge, "style"),
+            Some("<dashed & \"dotted\">".to_string())
+        );
+    }
+}
@@ -45,6 +45,7 @@ impl RegulatoryGraph {
             target,
             observable,
             monotonicity,
+            uncertain: false,
         });
         Ok(())
     }
@@ -72,6 +73,31 @@ impl RegulatoryGraph {
         }
     }
 
+    /// Mark an existing [Regulation] as `uncertain`, i.e. the regulation may or may not exist
+    /// (or remove this mark if `uncertain` is false).
+    ///
+    /// Returns `Err` if the regulation does not exist.
+    pub fn set_regulation_uncertain(
+        &mut self,
+        regulator: VariableId,
+        target: VariableId,
+        uncertain: bool,
+    ) -> Result<(), String> {
+        let regulation = self
+            .regulations
+            .iter_mut()
+            .find(|r| r.regulator == regulator && r.target == target);
+        if let Some(regulation) = regulation {
+            regulation.uncertain = uncertain;
+            Ok(())
+        } else {
+            Err(format!(
+                "Regulation ({:?}, {:?}) does not exist.",
+                regulator, target
+            ))
+        }
+    }
+
     /// Add a new regulation using the [Regulation] object.
     pub fn add_raw_regulation(&mut self, regulation: Regulation) -> Result<(), String> {
         self.assert_no_regulation(regulation.regulator, regulation.target)?;
@@ -5,6 +5,14 @@ pub mod _impl_misc;
 /// GraphViz `.dot` format.
 pub mod _impl_dot_export;
 
+/// **(internal)** Implements a configurable `.dot` and `GraphML` export of regulatory graphs
+/// (layout, SCC colors and cycle/FVS highlighting).
+pub mod _impl_graph_export;
+
+/// **(internal)** Implements SIF and CSV edge list import/export, as well as Cytoscape.js
+/// JSON export of regulatory graphs.
+pub mod _impl_edge_list;
+
 /// **(internal)** Implements a basic "syntactic" equivalence relation on regulatory graphs.
 pub mod _impl_equality;
 
@@ -0,0 +1,290 @@
+use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
+use crate::_impl_regulatory_graph::signed_directed_graph::{SdGraph, Sign};
+use crate::VariableId;
+use std::collections::{HashMap, HashSet};
+
+impl SdGraph {
+    /// Enumerate all elementary cycles (i.e. cycles where no vertex repeats) of this `SdGraph`.
+    ///
+    /// See [SdGraph::restricted_elementary_cycles] for details.
+    pub fn elementary_cycles(&self, upper_bound: usize, limit: usize) -> Vec<Vec<VariableId>> {
+        self.restricted_elementary_cycles(&self.mk_all_vertices(), upper_bound, limit)
+    }
+
+    /// Enumerate the elementary cycles (i.e. cycles where no vertex repeats) within the given
+    /// `restriction` of this `SdGraph`.
+    ///
+    /// Each cycle is returned as a vector of vertices which starts with the smallest vertex
+    /// of the cycle, followed by the remaining vertices in the order in which they appear on
+    /// the cycle. Note that the edge signs are ignored by this method: two vertices connected
+    /// by a non-monotonic edge are considered only once. Use [SdGraph::cycle_sign] to
+    /// compute the parity of each cycle.
+    ///
+    /// The number of elementary cycles can be exponential. Hence, only cycles with at most
+    /// `upper_bound` edges are considered, and the enumeration stops once `limit` cycles have
+    /// been found.
+    ///
+    /// The implementation follows Johnson's algorithm ("Finding all the elementary circuits of
+    /// a directed graph", 1975), but without recursion to avoid stack overflows on large graphs.
+    pub fn restricted_elementary_cycles(
+        &self,
+        restriction: &HashSet<VariableId>,
+        upper_bound: usize,
+        limit: usize,
+    ) -> Vec<Vec<VariableId>> {
+        let mut results = Vec::new();
+        if upper_bound == 0 || limit == 0 {
+            return results;
+        }
+
+        let mut remaining = restriction.clone();
+        let mut pivots = Vec::from_iter(restriction.iter().cloned());
+        pivots.sort();
+
+        for pivot in pivots {
+            // Only consider the strongly connected component of `pivot` in the graph induced
+            // by the vertices that are not smaller than `pivot` (i.e. not yet processed).
+            let fwd = self.restricted_forward_reachable(&remaining, HashSet::from([pivot]));
+            let bwd = self.restricted_backward_reachable(&remaining, HashSet::from([pivot]));
+            let component: HashSet<VariableId> = fwd.intersection(&bwd).cloned().collect();
+            remaining.remove(&pivot);
+
+            let successors: HashMap<VariableId, Vec<VariableId>> = component
+                .iter()
+                .map(|x| {
+                    let mut step = self.successors[x.to_index()]
+                        .iter()
+                        .map(|(it, _)| *it)
+                        .filter(|it| component.contains(it))
+                        .collect::<Vec<_>>();
+                    step.sort();
+                    step.dedup();
+                    (*x, step)
+                })
+                .collect();
+
+            let mut blocked: HashSet<VariableId> = HashSet::from([pivot]);
+            let mut blocked_by: HashMap<VariableId, HashSet<VariableId>> = HashMap::new();
+
+            // Each frame is a vertex on the current path, index of its next successor,
+            // and a flag indicating that the vertex can reach `pivot` (i.e. can be unblocked).
+            let mut path = vec![pivot];
+            let mut frames: Vec<(VariableId, usize, bool)> = vec![(pivot, 0, false)];
+            while let Some((vertex, next, found)) = frames.last_mut() {
+                let vertex_successors = &successors[vertex];
+                if *next < vertex_successors.len() {
+                    let successor = vertex_successors[*next];
+                    *next += 1;
+                    if successor == pivot {
+                        *found = true;
+                        results.push(path.clone());
+                        if results.len() >= limit {
+                            return results;
+                        }
+                    } else if !blocked.contains(&successor) {
+                        if path.len() < upper_bound {
+                            blocked.insert(successor);
+                            path.push(successor);
+                            frames.push((successor, 0, false));
+                        } else {
+                            // The path cannot be extended, but this does not mean the successor
+                            // cannot reach pivot. To keep the blocking sound, we must treat
+                            // the vertex as if a cycle was found.
+                            *found = true;
+                        }
+                    }
+                } else {
+                    let (vertex, found) = (*vertex, *found);
+                    frames.pop();
+                    path.pop();
+                    if found {
+                        unblock(vertex, &mut blocked, &mut blocked_by);
+                    } else {
+                        for successor in vertex_successors {
+                            blocked_by.entry(*successor).or_default().insert(vertex);
+                        }
+                    }
+                    if let Some((_, _, parent_found)) = frames.last_mut() {
+                        *parent_found = *parent_found || found;
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Compute the sign (parity) of the given `cycle`, i.e. a product of signs of the edges
+    /// on the cycle. The cycle is given as a sequence of vertices, with the last vertex
+    /// connected to the first one.
+    ///
+    /// Returns `None` if one of the edges of the cycle is non-monotonic (such cycle can be
+    /// both positive and negative). Panics if the `cycle` is not a cycle of this graph.
+    pub fn cycle_sign(&self, cycle: &[VariableId]) -> Option<Sign> {
+        let mut result = Positive;
+        for (i, source) in cycle.iter().enumerate() {
+            let target = cycle[(i + 1) % cycle.len()];
+            let mut edge_signs = self.successors[source.to_index()]
+                .iter()
+                .filter(|(it, _)| *it == target)
+                .map(|(_, sign)| *sign);
+            let sign = edge_signs
+                .next()
+                .unwrap_or_else(|| panic!("No edge from {:?} to {:?}.", source, target));
+            if edge_signs.next().is_some() {
+                // Both signs are present: the edge is non-monotonic.
+                return None;
+            }
+            result = result + sign;
+        }
+        Some(result)
+    }
+
+    /// Count the positive and negative elementary cycles in which each vertex of the given
+    /// `restriction` appears. The result maps each vertex to a `(positive, negative)` tuple.
+    ///
+    /// Cycles with non-monotonic edges are not counted. The `upper_bound` and `limit`
+    /// arguments are the same as in [SdGraph::restricted_elementary_cycles].
+    pub fn restricted_feedback_loop_counts(
+        &self,
+        restriction: &HashSet<VariableId>,
+        upper_bound: usize,
+        limit: usize,
+    ) -> HashMap<VariableId, (usize, usize)> {
+        let mut result: HashMap<VariableId, (usize, usize)> =
+            restriction.iter().map(|it| (*it, (0, 0))).collect();
+        for cycle in self.restricted_elementary_cycles(restriction, upper_bound, limit) {
+            let sign = self.cycle_sign(&cycle);
+            for var in cycle {
+                let counts = result.get_mut(&var).unwrap();
+                match sign {
+                    Some(Positive) => counts.0 += 1,
+                    Some(Negative) => counts.1 += 1,
+                    None => (),
+                }
+            }
+        }
+        result
+    }
+}
+
+/// **(internal)** Unblock the `vertex` and (transitively) all vertices that were blocked because
+/// of it, as in Johnson's algorithm.
+fn unblock(
+    vertex: VariableId,
+    blocked: &mut HashSet<VariableId>,
+    blocked_by: &mut HashMap<VariableId, HashSet<VariableId>>,
+) {
+    let mut stack = vec![vertex];
+    while let Some(x) = stack.pop() {
+        if blocked.remove(&x) {
+            if let Some(dependent) = blocked_by.remove(&x) {
+                stack.extend(dependent);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_impl_regulatory_graph::_impl_misc::tests::build_test_regulatory_graph;
+    use crate::_impl_regulatory_graph::signed_directed_graph::SdGraph;
+    use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
+    use crate::RegulatoryGraph;
+    use std::collections::HashSet;
+
+    #[test]
+    pub fn test_elementary_cycles() {
+        // See method for high-level graph description.
+        let rg = build_test_regulatory_graph();
+
+        let b_1 = rg.find_variable("b_1").unwrap();
+        let b_2 = rg.find_variable("b_2").unwrap();
+        let d_1 = rg.find_variable("d_1").unwrap();
+        let d_2 = rg.find_variable("d_2").unwrap();
+        let d_3 = rg.find_variable("d_3").unwrap();
+        let e = rg.find_variable("e").unwrap();
+
+        let graph = SdGraph::from(&rg);
+
+        let cycles: HashSet<Vec<_>> = graph
+            .elementary_cycles(usize::MAX, usize::MAX)
+            .into_iter()
+            .collect();
+        let expected =
+            HashSet::from([vec![b_1, b_2], vec![d_1, d_3, d_2], vec![d_1, d_2], vec![e]]);
+        assert_eq!(cycles, expected);
+
+        // Upper bound on cycle length.
+        let cycles: HashSet<Vec<_>> = graph.elementary_cycles(2, usize::MAX).into_iter().collect();
+        assert_eq!(cycles.len(), 3);
+        assert!(!cycles.contains(&vec![d_1, d_3, d_2]));
+
+        // Limit on the number of cycles.
+        assert_eq!(graph.elementary_cycles(usize::MAX, 2).len(), 2);
+
+        // Restriction.
+        let restriction = HashSet::from([d_1, d_2, d_3]);
+        let cycles = graph.restricted_elementary_cycles(&restriction, usize::MAX, usize::MAX);
+        assert_eq!(cycles.len(), 2);
+    }
+
+    #[test]
+    pub fn test_complete_graph_cycles() {
+        // A complete graph with self-loops on four vertices has 4 + 6 + 8 + 6 = 24
+        // elementary cycles.
+        let names = vec!["a", "b", "c", "d"];
+        let mut rg = RegulatoryGraph::new(names.iter().map(|it| it.to_string()).collect());
+        for source in &names {
+            for target in &names {
+                rg.add_regulation(source, target, false, None).unwrap();
+            }
+        }
+        let graph = SdGraph::from(&rg);
+        let cycles = graph.elementary_cycles(usize::MAX, usize::MAX);
+        assert_eq!(cycles.len(), 24);
+        let unique: HashSet<Vec<_>> = cycles.into_iter().collect();
+        assert_eq!(unique.len(), 24);
+        // Cycles with at most two edges.
+        assert_eq!(graph.elementary_cycles(2, usize::MAX).len(), 10);
+        // All cycles are non-monotonic.
+        let counts =
+            graph.restricted_feedback_loop_counts(&graph.mk_all_vertices(), usize::MAX, usize::MAX);
+        assert!(counts.values().all(|it| *it == (0, 0)));
+    }
+
+    #[test]
+    pub fn test_cycle_sign() {
+        let rg = RegulatoryGraph::try_from(
+            r#"
+            x_1 -> x_2
+            x_2 -| x_3
+            x_3 -> x_4
+            x_4 -| x_1
+            x_1 -> x_5
+            x_5 -> x_4
+            x_3 -? x_3
+        "#,
+        )
+        .unwrap();
+
+        let x_1 = rg.find_variable("x_1").unwrap();
+        let x_2 = rg.find_variable("x_2").unwrap();
+        let x_3 = rg.find_variable("x_3").unwrap();
+        let x_4 = rg.find_variable("x_4").unwrap();
+        let x_5 = rg.find_variable("x_5").unwrap();
+
+        let graph = SdGraph::from(&rg);
+        assert_eq!(Some(Positive), graph.cycle_sign(&[x_1, x_2, x_3, x_4]));
+        assert_eq!(Some(Negative), graph.cycle_sign(&[x_1, x_5, x_4]));
+        assert_eq!(None, graph.cycle_sign(&[x_3]));
+
+        let counts =
+            graph.restricted_feedback_loop_counts(&graph.mk_all_vertices(), usize::MAX, usize::MAX);
+        assert_eq!(counts[&x_1], (1, 1));
+        assert_eq!(counts[&x_2], (1, 0));
+        assert_eq!(counts[&x_3], (1, 0));
+        assert_eq!(counts[&x_4], (1, 1));
+        assert_eq!(counts[&x_5], (0, 1));
+    }
+}
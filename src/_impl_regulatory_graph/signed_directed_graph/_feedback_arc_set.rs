@@ -0,0 +1,347 @@
+use crate::_impl_regulatory_graph::signed_directed_graph::{SdGraph, Sign};
+use crate::VariableId;
+use std::collections::{HashMap, HashSet};
+
+/// A directed edge of an `SdGraph`, regardless of its sign.
+type Edge = (VariableId, VariableId);
+
+impl SdGraph {
+    /// Compute a feedback arc set of the subgraph induced by the vertices in the given
+    /// `restriction` set.
+    ///
+    /// A feedback arc set is a set of edges such that when these edges are removed,
+    /// the resulting graph is acyclic. Each edge is identified by its source and target
+    /// vertex (i.e. removing an edge removes both of its signs).
+    ///
+    /// The algorithm greedily removes edges from the shortest cycles, prioritising edges that
+    /// appear in the highest number of such cycles. The result is then pruned to be subset
+    /// minimal, but it is not guaranteed to be the minimum feedback arc set. For small graphs,
+    /// see [SdGraph::restricted_minimum_feedback_arc_set].
+    pub fn restricted_feedback_arc_set(&self, restriction: &HashSet<VariableId>) -> HashSet<Edge> {
+        self.greedy_feedback_arc_set(restriction, None)
+    }
+
+    /// Compute a feedback arc set of the desired parity, considered within the subgraph
+    /// induced by the vertices in `restriction`.
+    ///
+    /// A parity feedback arc set is a set of edges such that when removed, the graph has
+    /// no cycles of the specified parity. See also [SdGraph::restricted_feedback_arc_set] for
+    /// notes about minimality.
+    pub fn restricted_parity_feedback_arc_set(
+        &self,
+        restriction: &HashSet<VariableId>,
+        parity: Sign,
+    ) -> HashSet<Edge> {
+        self.greedy_feedback_arc_set(restriction, Some(parity))
+    }
+
+    /// Compute a minimum feedback arc set of the subgraph induced by the vertices
+    /// in `restriction`.
+    ///
+    /// The algorithm first enumerates all elementary cycles and then searches for the smallest
+    /// set of edges that intersects all of them. As such, it is only usable for small graphs.
+    /// If the graph has more than `cycle_limit` elementary cycles, the method returns `None`.
+    pub fn restricted_minimum_feedback_arc_set(
+        &self,
+        restriction: &HashSet<VariableId>,
+        cycle_limit: usize,
+    ) -> Option<HashSet<Edge>> {
+        self.exact_feedback_arc_set(restriction, None, cycle_limit)
+    }
+
+    /// Compute a minimum feedback arc set of the desired parity, considered within
+    /// the subgraph induced by the vertices in `restriction`.
+    ///
+    /// See [SdGraph::restricted_minimum_feedback_arc_set] for notes about complexity.
+    pub fn restricted_minimum_parity_feedback_arc_set(
+        &self,
+        restriction: &HashSet<VariableId>,
+        parity: Sign,
+        cycle_limit: usize,
+    ) -> Option<HashSet<Edge>> {
+        self.exact_feedback_arc_set(restriction, Some(parity), cycle_limit)
+    }
+
+    /// **(internal)** Create a copy of this graph without the given edges (of both signs).
+    fn without_edges(&self, removed: &HashSet<Edge>) -> SdGraph {
+        let mut edges = Vec::new();
+        for (source, successors) in self.successors.iter().enumerate() {
+            let source = VariableId::from_index(source);
+            for (target, sign) in successors {
+                if !removed.contains(&(source, *target)) {
+                    edges.push((source, *target, *sign));
+                }
+            }
+        }
+        SdGraph::from_edges(self.successors.len(), &edges)
+    }
+
+    /// **(internal)** Find the shortest cycle through `pivot` (with the given parity, if any).
+    fn shortest_optional_parity_cycle(
+        &self,
+        restriction: &HashSet<VariableId>,
+        pivot: VariableId,
+        parity: Option<Sign>,
+    ) -> Option<Vec<VariableId>> {
+        if let Some(parity) = parity {
+            self.shortest_parity_cycle(restriction, pivot, parity, usize::MAX)
+        } else {
+            self.shortest_cycle(restriction, pivot, usize::MAX)
+        }
+    }
+
+    /// **(internal)** Shared implementation of the greedy (parity) feedback arc set heuristic.
+    fn greedy_feedback_arc_set(
+        &self,
+        restriction: &HashSet<VariableId>,
+        parity: Option<Sign>,
+    ) -> HashSet<Edge> {
+        // Ensure determinism.
+        let mut pivots = Vec::from_iter(restriction.iter().cloned());
+        pivots.sort();
+
+        let mut removed: HashSet<Edge> = HashSet::new();
+        loop {
+            let graph = self.without_edges(&removed);
+            let cycles: Vec<Vec<VariableId>> = pivots
+                .iter()
+                .filter_map(|pivot| {
+                    graph.shortest_optional_parity_cycle(restriction, *pivot, parity)
+                })
+                .collect();
+            let shortest = if let Some(cycle) = cycles.iter().min_by_key(|it| it.len()) {
+                cycle
+            } else {
+                break;
+            };
+
+            let mut frequency: HashMap<Edge, usize> = HashMap::new();
+            for cycle in &cycles {
+                for edge in cycle_edges(cycle) {
+                    *frequency.entry(edge).or_default() += 1;
+                }
+            }
+
+            let mut best = cycle_edges(shortest);
+            best.sort();
+            let best = best
+                .into_iter()
+                .rev()
+                .max_by_key(|it| frequency[it])
+                .unwrap();
+            removed.insert(best);
+        }
+
+        // Try to return every edge back to the graph. If the graph is still free of
+        // the relevant cycles, the edge is not needed.
+        let mut candidates = Vec::from_iter(removed.iter().cloned());
+        candidates.sort();
+        for edge in candidates {
+            removed.remove(&edge);
+            let graph = self.without_edges(&removed);
+            let has_cycle = pivots.iter().any(|pivot| {
+                graph
+                    .shortest_optional_parity_cycle(restriction, *pivot, parity)
+                    .is_some()
+            });
+            if has_cycle {
+                removed.insert(edge);
+            }
+        }
+
+        removed
+    }
+
+    /// **(internal)** Shared implementation of the exact (parity) feedback arc set algorithm.
+    fn exact_feedback_arc_set(
+        &self,
+        restriction: &HashSet<VariableId>,
+        parity: Option<Sign>,
+        cycle_limit: usize,
+    ) -> Option<HashSet<Edge>> {
+        let cycles = self.restricted_elementary_cycles(
+            restriction,
+            usize::MAX,
+            cycle_limit.saturating_add(1),
+        );
+        if cycles.len() > cycle_limit {
+            return None;
+        }
+
+        // A cycle with a non-monotonic edge has both parities.
+        let cycles: Vec<Vec<Edge>> = cycles
+            .into_iter()
+            .filter(|cycle| match (parity, self.cycle_sign(cycle)) {
+                (Some(parity), Some(sign)) => parity == sign,
+                _ => true,
+            })
+            .map(|cycle| cycle_edges(&cycle))
+            .collect();
+
+        let mut selected = Vec::new();
+        for size in 0..=cycles.len() {
+            if hitting_set(&cycles, size, &mut selected) {
+                return Some(selected.into_iter().collect());
+            }
+        }
+
+        unreachable!("Selecting one edge from each cycle is always a valid solution.")
+    }
+}
+
+/// **(internal)** List of edges of the given cycle (including the edge from the last vertex
+/// back to the first vertex).
+fn cycle_edges(cycle: &[VariableId]) -> Vec<Edge> {
+    (0..cycle.len())
+        .map(|i| (cycle[i], cycle[(i + 1) % cycle.len()]))
+        .collect()
+}
+
+/// **(internal)** Try to extend `selected` with at most `budget` edges such that each cycle
+/// contains at least one selected edge. If this is not possible, `selected` is left unchanged
+/// and the result is `false`.
+fn hitting_set(cycles: &[Vec<Edge>], budget: usize, selected: &mut Vec<Edge>) -> bool {
+    let unhit = cycles
+        .iter()
+        .filter(|cycle| !cycle.iter().any(|edge| selected.contains(edge)))
+        .min_by_key(|cycle| cycle.len());
+    let unhit = if let Some(cycle) = unhit {
+        cycle
+    } else {
+        return true;
+    };
+    if budget == 0 {
+        return false;
+    }
+    for edge in unhit {
+        selected.push(*edge);
+        if hitting_set(cycles, budget - 1, selected) {
+            return true;
+        }
+        selected.pop();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_impl_regulatory_graph::signed_directed_graph::SdGraph;
+    use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
+    use crate::RegulatoryGraph;
+    use std::collections::HashSet;
+
+    #[test]
+    pub fn test_feedback_arc_set() {
+        // Same graph as in the FVS test: `b_1 -> b_2` is a negative cycle, the `d`-component
+        // has both one positive and one negative cycle, and `e` has a positive self-loop.
+        let rg = RegulatoryGraph::try_from(
+            r#"
+            a -> c
+            b_1 -> b_2
+            b_2 -| b_1
+            b_2 -> c
+            c -> d_2
+            c -> e
+            d_1 -> d_3
+            d_3 -| d_2
+            d_2 -> d_1
+            d_1 -> d_2
+            e -> e
+        "#,
+        )
+        .unwrap();
+
+        let b_1 = rg.find_variable("b_1").unwrap();
+        let b_2 = rg.find_variable("b_2").unwrap();
+        let d_1 = rg.find_variable("d_1").unwrap();
+        let d_2 = rg.find_variable("d_2").unwrap();
+        let e = rg.find_variable("e").unwrap();
+
+        let graph = SdGraph::from(&rg);
+        let vertices = graph.mk_all_vertices();
+
+        let fas = graph.restricted_feedback_arc_set(&vertices);
+        assert_eq!(fas.len(), 3);
+        assert!(fas.contains(&(e, e)));
+        assert!(fas.contains(&(b_1, b_2)) || fas.contains(&(b_2, b_1)));
+        // The only edge shared by both cycles in the `d`-component.
+        assert!(fas.contains(&(d_2, d_1)));
+
+        let p_fas = graph.restricted_parity_feedback_arc_set(&vertices, Positive);
+        assert_eq!(p_fas.len(), 2);
+        assert!(p_fas.contains(&(e, e)));
+
+        let n_fas = graph.restricted_parity_feedback_arc_set(&vertices, Negative);
+        assert_eq!(n_fas.len(), 2);
+        assert!(!n_fas.contains(&(e, e)));
+        assert!(n_fas.contains(&(b_1, b_2)) || n_fas.contains(&(b_2, b_1)));
+
+        let min_fas = graph
+            .restricted_minimum_feedback_arc_set(&vertices, 100)
+            .unwrap();
+        assert_eq!(min_fas.len(), 3);
+        let min_n_fas = graph
+            .restricted_minimum_parity_feedback_arc_set(&vertices, Negative, 100)
+            .unwrap();
+        assert_eq!(min_n_fas.len(), 2);
+
+        // The graph has four cycles.
+        assert!(graph
+            .restricted_minimum_feedback_arc_set(&vertices, 3)
+            .is_none());
+        let restriction = HashSet::from([d_1, d_2]);
+        assert_eq!(
+            graph.restricted_minimum_feedback_arc_set(&restriction, 3),
+            Some(HashSet::from([(d_1, d_2)]))
+        );
+    }
+
+    #[test]
+    pub fn test_feedback_arc_set_is_minimal() {
+        // Two vertices that are connected in both directions, plus a long cycle through
+        // both of them. Removing `a -> b` breaks both cycles, since it is also on the long
+        // cycle. Finally, `a` has a negative self-loop.
+        let rg = RegulatoryGraph::try_from(
+            r#"
+            a -> b
+            b -> a
+            b -> c
+            c -> d
+            d -> a
+            a -| a
+        "#,
+        )
+        .unwrap();
+        let a = rg.find_variable("a").unwrap();
+        let b = rg.find_variable("b").unwrap();
+
+        let graph = SdGraph::from(&rg);
+        let vertices = graph.mk_all_vertices();
+        let fas = graph.restricted_feedback_arc_set(&vertices);
+        let min_fas = graph
+            .restricted_minimum_feedback_arc_set(&vertices, 100)
+            .unwrap();
+        assert_eq!(fas, min_fas);
+        assert_eq!(fas, HashSet::from([(a, a), (a, b)]));
+
+        // Only one negative cycle.
+        let n_fas = graph.restricted_parity_feedback_arc_set(&vertices, Negative);
+        assert_eq!(n_fas, HashSet::from([(a, a)]));
+        let p_fas = graph
+            .restricted_minimum_parity_feedback_arc_set(&vertices, Positive, 100)
+            .unwrap();
+        assert_eq!(p_fas, HashSet::from([(a, b)]));
+
+        // The same result, but as regulations.
+        let regulations = rg.feedback_arc_set();
+        assert_eq!(regulations.len(), 2);
+        assert_eq!(&regulations[0], rg.find_regulation(a, a).unwrap());
+        assert_eq!(&regulations[1], rg.find_regulation(a, b).unwrap());
+        assert_eq!(
+            rg.minimum_parity_feedback_arc_set(Negative, 100)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
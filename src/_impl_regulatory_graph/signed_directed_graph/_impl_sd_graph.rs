@@ -5,10 +5,35 @@ use std::collections::HashSet;
 use Sign::Negative;
 
 impl SdGraph {
+    /// Create a new `SdGraph` with `vertex_count` vertices and the given list of signed `edges`.
+    ///
+    /// A non-monotonic edge is represented as two edges (one positive and one negative) between
+    /// the same pair of vertices. Duplicate edges are ignored.
+    pub fn from_edges(vertex_count: usize, edges: &[(VariableId, VariableId, Sign)]) -> SdGraph {
+        let mut successors = vec![Vec::new(); vertex_count];
+        let mut predecessors = vec![Vec::new(); vertex_count];
+        for (source, target, sign) in edges {
+            let successor_list: &mut Vec<(VariableId, Sign)> = &mut successors[source.to_index()];
+            if !successor_list.contains(&(*target, *sign)) {
+                successor_list.push((*target, *sign));
+                predecessors[target.to_index()].push((*source, *sign));
+            }
+        }
+        SdGraph {
+            successors,
+            predecessors,
+        }
+    }
+
     pub fn mk_all_vertices(&self) -> HashSet<VariableId> {
         let var_count = self.successors.len();
         (0..var_count).map(VariableId::from_index).collect()
     }
+
+    /// Check if this graph contains an edge from `source` to `target` with the given `sign`.
+    pub fn has_edge(&self, source: VariableId, target: VariableId, sign: Sign) -> bool {
+        self.successors[source.to_index()].contains(&(target, sign))
+    }
 }
 
 impl From<&RegulatoryGraph> for SdGraph {
@@ -56,6 +81,8 @@ impl From<&RegulatoryGraph> for SdGraph {
 mod tests {
     use crate::_impl_regulatory_graph::_impl_misc::tests::build_test_regulatory_graph;
     use crate::_impl_regulatory_graph::signed_directed_graph::SdGraph;
+    use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
+    use crate::VariableId;
 
     #[test]
     pub fn basic_sd_graph_test() {
@@ -77,4 +104,23 @@ mod tests {
 
         assert_eq!(sd_graph.mk_all_vertices(), rg.variables().collect());
     }
+
+    #[test]
+    pub fn sd_graph_from_edges_test() {
+        let a = VariableId::from_index(0);
+        let b = VariableId::from_index(1);
+        let edges = vec![
+            (a, b, Positive),
+            (a, b, Negative),
+            (b, a, Positive),
+            (b, a, Positive),
+        ];
+        let graph = SdGraph::from_edges(2, &edges);
+        assert!(graph.has_edge(a, b, Positive));
+        assert!(graph.has_edge(a, b, Negative));
+        assert!(graph.has_edge(b, a, Positive));
+        assert!(!graph.has_edge(b, a, Negative));
+        assert_eq!(graph.successors[b.to_index()].len(), 1);
+        assert_eq!(graph.predecessors[a.to_index()].len(), 1);
+    }
 }
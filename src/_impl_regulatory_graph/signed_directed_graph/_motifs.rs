@@ -0,0 +1,423 @@
+use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
+use crate::_impl_regulatory_graph::signed_directed_graph::{Motif, MotifOccurrence, SdGraph, Sign};
+use crate::VariableId;
+use std::collections::HashSet;
+
+impl Motif {
+    /// Create a new motif with the given `name`, number of vertices and a list of
+    /// (optionally signed) edges.
+    ///
+    /// Panics if an edge references a vertex outside of `0..vertex_count`.
+    pub fn new(name: &str, vertex_count: usize, edges: &[(usize, usize, Option<Sign>)]) -> Motif {
+        for (source, target, _) in edges {
+            assert!(
+                *source < vertex_count && *target < vertex_count,
+                "Invalid motif edge {} -> {}.",
+                source,
+                target
+            );
+        }
+        Motif {
+            name: name.to_string(),
+            vertex_count,
+            edges: edges.to_vec(),
+            parallel_paths: None,
+            feedback_loop: None,
+        }
+    }
+
+    /// Declare two parallel paths of this motif, given as lists of edge indices. The coherence
+    /// of an occurrence is then determined by comparing the signs of the two paths.
+    ///
+    /// Panics if an edge index is out of bounds.
+    pub fn with_parallel_paths(mut self, first: &[usize], second: &[usize]) -> Motif {
+        self.check_edge_indices(first);
+        self.check_edge_indices(second);
+        self.parallel_paths = Some((first.to_vec(), second.to_vec()));
+        self
+    }
+
+    /// Declare a feedback loop of this motif, given as a list of edge indices. The feedback
+    /// sign of an occurrence is then the sign of this loop.
+    ///
+    /// Panics if an edge index is out of bounds.
+    pub fn with_feedback_loop(mut self, edges: &[usize]) -> Motif {
+        self.check_edge_indices(edges);
+        self.feedback_loop = Some(edges.to_vec());
+        self
+    }
+
+    /// A feed-forward loop: `0 -> 1`, `1 -> 2` and `0 -> 2`.
+    ///
+    /// The loop is coherent when the direct edge `0 -> 2` has the same sign as the indirect
+    /// path `0 -> 1 -> 2`.
+    pub fn feed_forward_loop() -> Motif {
+        Motif::new(
+            "feed_forward_loop",
+            3,
+            &[(0, 1, None), (1, 2, None), (0, 2, None)],
+        )
+        .with_parallel_paths(&[0, 1], &[2])
+    }
+
+    /// A bi-fan: two regulators `0` and `1` which both regulate two targets `2` and `3`.
+    ///
+    /// The bi-fan is coherent when the regulators have the same relative effect on both
+    /// targets, i.e. the path `2 <- 0 -> 3` has the same sign as the path `2 <- 1 -> 3`.
+    pub fn bifan() -> Motif {
+        Motif::new(
+            "bifan",
+            4,
+            &[(0, 2, None), (0, 3, None), (1, 2, None), (1, 3, None)],
+        )
+        .with_parallel_paths(&[0, 1], &[2, 3])
+    }
+
+    /// A pair of variables which inhibit each other (a positive feedback loop).
+    pub fn mutual_inhibition() -> Motif {
+        Motif::new(
+            "mutual_inhibition",
+            2,
+            &[(0, 1, Some(Negative)), (1, 0, Some(Negative))],
+        )
+        .with_feedback_loop(&[0, 1])
+    }
+
+    /// A pair of variables which activate each other (a positive feedback loop).
+    pub fn mutual_activation() -> Motif {
+        Motif::new(
+            "mutual_activation",
+            2,
+            &[(0, 1, Some(Positive)), (1, 0, Some(Positive))],
+        )
+        .with_feedback_loop(&[0, 1])
+    }
+
+    /// The name of this motif.
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The number of vertices of this motif.
+    pub fn num_vertices(&self) -> usize {
+        self.vertex_count
+    }
+
+    /// The (optionally signed) edges of this motif.
+    pub fn edges(&self) -> &[(usize, usize, Option<Sign>)] {
+        &self.edges
+    }
+
+    /// Check whether the given `occurrence` of this motif is coherent, i.e. whether
+    /// both parallel paths of the motif have the same sign.
+    ///
+    /// The result is `None` if the motif has no parallel paths, or if some of the edges
+    /// on these paths are non-monotonic.
+    pub fn is_coherent(&self, occurrence: &MotifOccurrence) -> Option<bool> {
+        let (first, second) = self.parallel_paths.as_ref()?;
+        Some(path_sign(occurrence, first)? == path_sign(occurrence, second)?)
+    }
+
+    /// Compute the sign of the feedback loop of this motif in the given `occurrence`.
+    ///
+    /// The result is `None` if the motif has no feedback loop, or if some of the edges
+    /// in the loop are non-monotonic.
+    pub fn feedback_sign(&self, occurrence: &MotifOccurrence) -> Option<Sign> {
+        path_sign(occurrence, self.feedback_loop.as_ref()?)
+    }
+
+    /// **(internal)** Panics if one of the `edges` is not a valid edge index of this motif.
+    fn check_edge_indices(&self, edges: &[usize]) {
+        for edge in edges {
+            assert!(
+                *edge < self.edges.len(),
+                "Invalid motif edge index {}.",
+                edge
+            );
+        }
+    }
+}
+
+/// **(internal)** Compute the sign of a path given by the motif edge indices in `edges`,
+/// or `None` if some of the edges in the `occurrence` are non-monotonic.
+fn path_sign(occurrence: &MotifOccurrence, edges: &[usize]) -> Option<Sign> {
+    let mut result = Positive;
+    for edge in edges {
+        result = result + occurrence.signs[*edge]?;
+    }
+    Some(result)
+}
+
+impl SdGraph {
+    /// Find all occurrences of the given `motif` in this `SdGraph`.
+    ///
+    /// See [SdGraph::restricted_find_motifs] for details.
+    pub fn find_motifs(&self, motif: &Motif) -> Vec<MotifOccurrence> {
+        self.restricted_find_motifs(&self.mk_all_vertices(), motif)
+    }
+
+    /// Find all occurrences of the given `motif` within the given `restriction` of this `SdGraph`.
+    ///
+    /// An occurrence is an injective mapping of motif vertices to graph vertices such that
+    /// every motif edge maps to a graph edge of a compatible sign. The occurrence does not have
+    /// to be an induced subgraph (i.e. the graph can contain other edges between the vertices).
+    /// Occurrences which only differ by a symmetry of the motif (i.e. they match the same sets
+    /// of graph vertices and graph edges) are reported only once.
+    ///
+    /// Note that a non-monotonic edge only matches motif edges without a prescribed sign.
+    pub fn restricted_find_motifs(
+        &self,
+        restriction: &HashSet<VariableId>,
+        motif: &Motif,
+    ) -> Vec<MotifOccurrence> {
+        let mut all_vertices = Vec::from_iter(restriction.iter().cloned());
+        all_vertices.sort();
+
+        let mut results = Vec::new();
+        if motif.vertex_count == 0 {
+            return results;
+        }
+
+        let mut seen = HashSet::new();
+        let mut assignment: Vec<VariableId> = Vec::with_capacity(motif.vertex_count);
+        // For each motif vertex, the list of candidate graph vertices and the index
+        // of the next candidate that should be tested.
+        let mut frames: Vec<(Vec<VariableId>, usize)> = vec![(all_vertices.clone(), 0)];
+        while let Some((candidates, next)) = frames.last_mut() {
+            if *next >= candidates.len() {
+                frames.pop();
+                assignment.pop();
+                continue;
+            }
+            let vertex = candidates[*next];
+            *next += 1;
+            if assignment.contains(&vertex) {
+                continue;
+            }
+            assignment.push(vertex);
+            if !self.is_partial_motif_match(motif, &assignment) {
+                assignment.pop();
+                continue;
+            }
+            if assignment.len() < motif.vertex_count {
+                let candidates = self.motif_candidates(motif, &assignment, restriction);
+                frames.push((candidates.unwrap_or_else(|| all_vertices.clone()), 0));
+                continue;
+            }
+
+            // The assignment is complete.
+            let mut matched_edges: Vec<(VariableId, VariableId)> = motif
+                .edges
+                .iter()
+                .map(|(s, t, _)| (assignment[*s], assignment[*t]))
+                .collect();
+            matched_edges.sort();
+            let mut matched_vertices = assignment.clone();
+            matched_vertices.sort();
+            if seen.insert((matched_vertices, matched_edges)) {
+                let signs = motif
+                    .edges
+                    .iter()
+                    .map(|(s, t, _)| self.monotonic_edge_sign(assignment[*s], assignment[*t]))
+                    .map(|it| it.unwrap())
+                    .collect();
+                results.push(MotifOccurrence {
+                    vertices: assignment.clone(),
+                    signs,
+                });
+            }
+            assignment.pop();
+        }
+
+        results
+    }
+
+    /// **(internal)** If the next unassigned motif vertex is connected to an already assigned
+    /// vertex, return the (sorted) neighbours of the assigned vertex as candidates. Otherwise,
+    /// return `None` (all vertices are candidates).
+    fn motif_candidates(
+        &self,
+        motif: &Motif,
+        assignment: &[VariableId],
+        restriction: &HashSet<VariableId>,
+    ) -> Option<Vec<VariableId>> {
+        let vertex = assignment.len();
+        let mut neighbours = motif
+            .edges
+            .iter()
+            .find_map(|(s, t, _)| {
+                if *s == vertex && *t < vertex {
+                    Some(&self.predecessors[assignment[*t].to_index()])
+                } else if *t == vertex && *s < vertex {
+                    Some(&self.successors[assignment[*s].to_index()])
+                } else {
+                    None
+                }
+            })?
+            .iter()
+            .map(|(it, _)| *it)
+            .filter(|it| restriction.contains(it))
+            .collect::<Vec<_>>();
+        neighbours.sort();
+        neighbours.dedup();
+        Some(neighbours)
+    }
+
+    /// **(internal)** Check that all motif edges between the already assigned motif vertices
+    /// (i.e. the first `assignment.len()` vertices) are present in the graph.
+    fn is_partial_motif_match(&self, motif: &Motif, assignment: &[VariableId]) -> bool {
+        motif.edges.iter().all(|(s, t, sign)| {
+            if *s >= assignment.len() || *t >= assignment.len() {
+                return true;
+            }
+            match self.monotonic_edge_sign(assignment[*s], assignment[*t]) {
+                None => false,
+                Some(edge_sign) => sign.is_none() || *sign == edge_sign,
+            }
+        })
+    }
+
+    /// **(internal)** Return `None` if there is no edge from `source` to `target`. Otherwise,
+    /// return the sign of the edge, or `Some(None)` if the edge is non-monotonic.
+    fn monotonic_edge_sign(&self, source: VariableId, target: VariableId) -> Option<Option<Sign>> {
+        let has_positive = self.has_edge(source, target, Positive);
+        let has_negative = self.has_edge(source, target, Negative);
+        match (has_positive, has_negative) {
+            (true, true) => Some(None),
+            (true, false) => Some(Some(Positive)),
+            (false, true) => Some(Some(Negative)),
+            (false, false) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
+    use crate::_impl_regulatory_graph::signed_directed_graph::{Motif, MotifCensus, SdGraph};
+    use crate::RegulatoryGraph;
+
+    #[test]
+    pub fn test_motif_detection() {
+        let rg = RegulatoryGraph::try_from(
+            r#"
+            a -> b
+            b -> c
+            a -> c
+            a -| d
+            d -> c
+            b -| e
+            e -| b
+            e -? a
+            f -> g
+            f -> h
+            i -| g
+            i -> h
+        "#,
+        )
+        .unwrap();
+
+        let a = rg.find_variable("a").unwrap();
+        let b = rg.find_variable("b").unwrap();
+        let c = rg.find_variable("c").unwrap();
+        let d = rg.find_variable("d").unwrap();
+        let e = rg.find_variable("e").unwrap();
+
+        let graph = SdGraph::from(&rg);
+
+        // There are two feed-forward loops: a -> b -> c (coherent) and a -| d -> c (incoherent),
+        // and one with a non-monotonic edge: e -? a -> b.
+        let ffl_motif = Motif::feed_forward_loop();
+        let ffl = graph.find_motifs(&ffl_motif);
+        assert_eq!(ffl.len(), 3);
+        let coherent = ffl.iter().find(|it| it.vertices == vec![a, b, c]).unwrap();
+        assert_eq!(ffl_motif.is_coherent(coherent), Some(true));
+        let incoherent = ffl.iter().find(|it| it.vertices == vec![a, d, c]).unwrap();
+        assert_eq!(ffl_motif.is_coherent(incoherent), Some(false));
+        assert_eq!(
+            incoherent.signs,
+            vec![Some(Negative), Some(Positive), Some(Positive)]
+        );
+        let unknown = ffl.iter().find(|it| it.vertices[0] == e).unwrap();
+        assert_eq!(ffl_motif.is_coherent(unknown), None);
+        assert_eq!(ffl_motif.feedback_sign(coherent), None);
+
+        // Mutual inhibition is detected only once, despite the symmetry. It has no coherence,
+        // but it is a positive feedback loop.
+        let inhibition_motif = Motif::mutual_inhibition();
+        let inhibition = graph.find_motifs(&inhibition_motif);
+        assert_eq!(inhibition.len(), 1);
+        assert!(inhibition[0].vertices.contains(&b));
+        assert!(inhibition[0].vertices.contains(&e));
+        assert_eq!(inhibition_motif.is_coherent(&inhibition[0]), None);
+        assert_eq!(
+            inhibition_motif.feedback_sign(&inhibition[0]),
+            Some(Positive)
+        );
+        assert!(graph.find_motifs(&Motif::mutual_activation()).is_empty());
+
+        // One bi-fan, which is incoherent (i inhibits g, but activates h).
+        let census = rg.motif_census(&[
+            Motif::bifan(),
+            Motif::feed_forward_loop(),
+            Motif::mutual_inhibition(),
+        ]);
+        let bifan = MotifCensus {
+            total: 1,
+            incoherent: 1,
+            ..Default::default()
+        };
+        let ffl = MotifCensus {
+            total: 3,
+            coherent: 1,
+            incoherent: 1,
+            ..Default::default()
+        };
+        let inhibition = MotifCensus {
+            total: 1,
+            positive_feedback: 1,
+            ..Default::default()
+        };
+        assert_eq!(census, vec![bifan, ffl, inhibition]);
+    }
+
+    #[test]
+    pub fn test_custom_motif() {
+        let rg = RegulatoryGraph::try_from(
+            r#"
+            a -| a
+            a -> b
+            b -? b
+        "#,
+        )
+        .unwrap();
+        let a = rg.find_variable("a").unwrap();
+
+        // Negative auto-regulation. Note that `b` has a non-monotonic self-loop.
+        let motif = Motif::new("negative_autoregulation", 1, &[(0, 0, Some(Negative))]);
+        assert_eq!(motif.get_name(), "negative_autoregulation");
+        let occurrences = rg.find_motifs(&motif);
+        assert_eq!(occurrences.len(), 1);
+        assert_eq!(occurrences[0].vertices, vec![a]);
+        assert_eq!(
+            rg.find_motifs(&Motif::new("loop", 1, &[(0, 0, None)]))
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    pub fn test_motif_with_isolated_vertex() {
+        let rg = RegulatoryGraph::try_from(
+            r#"
+            a -> b
+            c -> d
+        "#,
+        )
+        .unwrap();
+
+        // Each edge can be combined with two different isolated vertices.
+        let motif = Motif::new("edge_and_vertex", 3, &[(0, 1, Some(Positive))]);
+        assert_eq!(rg.find_motifs(&motif).len(), 4);
+    }
+}
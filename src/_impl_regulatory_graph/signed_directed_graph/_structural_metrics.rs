@@ -0,0 +1,485 @@
+use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
+use crate::_impl_regulatory_graph::signed_directed_graph::{SdGraph, Sign};
+use crate::VariableId;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+impl SdGraph {
+    /// The number of distinct predecessors of the given `vertex`.
+    pub fn in_degree(&self, vertex: VariableId) -> usize {
+        distinct_neighbours(&self.predecessors[vertex.to_index()]).len()
+    }
+
+    /// The number of distinct successors of the given `vertex`.
+    pub fn out_degree(&self, vertex: VariableId) -> usize {
+        distinct_neighbours(&self.successors[vertex.to_index()]).len()
+    }
+
+    /// Compute the in-degree distribution of this graph. The value at index `k` is the number
+    /// of vertices with in-degree `k`.
+    pub fn in_degree_distribution(&self) -> Vec<usize> {
+        degree_distribution(&self.predecessors)
+    }
+
+    /// Compute the out-degree distribution of this graph. The value at index `k` is the number
+    /// of vertices with out-degree `k`.
+    pub fn out_degree_distribution(&self) -> Vec<usize> {
+        degree_distribution(&self.successors)
+    }
+
+    /// Compute the (non-normalised) betweenness centrality of every vertex, i.e. the sum of
+    /// fractions of shortest paths between all other pairs of vertices that pass through
+    /// the vertex. Edge signs are ignored.
+    ///
+    /// The implementation uses the algorithm of Brandes ("A faster algorithm for betweenness
+    /// centrality", 2001).
+    pub fn betweenness_centrality(&self) -> HashMap<VariableId, f64> {
+        let vertex_count = self.successors.len();
+        let successors: Vec<Vec<VariableId>> = self
+            .successors
+            .iter()
+            .map(|it| distinct_neighbours(it))
+            .collect();
+
+        let mut centrality = vec![0.0; vertex_count];
+        for source in 0..vertex_count {
+            let mut stack = Vec::new();
+            let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+            let mut path_count = vec![0.0; vertex_count];
+            let mut distance: Vec<Option<usize>> = vec![None; vertex_count];
+            path_count[source] = 1.0;
+            distance[source] = Some(0);
+
+            let mut queue = VecDeque::from([source]);
+            while let Some(x) = queue.pop_front() {
+                stack.push(x);
+                let x_distance = distance[x].unwrap();
+                for y in &successors[x] {
+                    let y = y.to_index();
+                    if distance[y].is_none() {
+                        distance[y] = Some(x_distance + 1);
+                        queue.push_back(y);
+                    }
+                    if distance[y] == Some(x_distance + 1) {
+                        path_count[y] += path_count[x];
+                        predecessors[y].push(x);
+                    }
+                }
+            }
+
+            let mut dependency = vec![0.0; vertex_count];
+            while let Some(y) = stack.pop() {
+                for x in &predecessors[y] {
+                    dependency[*x] += (path_count[*x] / path_count[y]) * (1.0 + dependency[y]);
+                }
+                if y != source {
+                    centrality[y] += dependency[y];
+                }
+            }
+        }
+
+        centrality
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| (VariableId::from_index(i), value))
+            .collect()
+    }
+
+    /// Compute the closeness centrality of every vertex based on the distances *to* all other
+    /// vertices that are reachable from it. Edge signs are ignored.
+    ///
+    /// Since the graph does not have to be strongly connected, we use the Wasserman-Faust
+    /// variant of closeness: `(r / s) * (r / (n - 1))`, where `r` is the number of reachable
+    /// vertices, `s` is the sum of their distances, and `n` is the number of vertices.
+    /// Vertices that cannot reach any other vertex have zero closeness.
+    pub fn closeness_centrality(&self) -> HashMap<VariableId, f64> {
+        let vertex_count = self.successors.len();
+        let mut result = HashMap::new();
+        for source in 0..vertex_count {
+            let source = VariableId::from_index(source);
+            let distances = self.shortest_distances(source);
+            let reachable = (distances.len() - 1) as f64;
+            let distance_sum: usize = distances.values().sum();
+            let closeness = if distance_sum == 0 {
+                0.0
+            } else {
+                (reachable / distance_sum as f64) * (reachable / (vertex_count - 1) as f64)
+            };
+            result.insert(source, closeness);
+        }
+        result
+    }
+
+    /// Compute the length (number of edges) of the shortest path from `source` to every
+    /// vertex that is reachable from it (including `source` itself, with length zero).
+    pub fn shortest_distances(&self, source: VariableId) -> HashMap<VariableId, usize> {
+        let mut result = HashMap::from([(source, 0)]);
+        let mut queue = VecDeque::from([source]);
+        while let Some(x) = queue.pop_front() {
+            let x_distance = result[&x];
+            for (y, _) in &self.successors[x.to_index()] {
+                if !result.contains_key(y) {
+                    result.insert(*y, x_distance + 1);
+                    queue.push_back(*y);
+                }
+            }
+        }
+        result
+    }
+
+    /// Count the positive and negative simple paths (i.e. paths where no vertex repeats)
+    /// from `source` to `target`. The result is a `(positive, negative)` tuple. If `source`
+    /// and `target` are the same vertex, the paths are the elementary cycles through `source`.
+    ///
+    /// A non-monotonic edge is treated as two edges, one positive and one negative. Hence,
+    /// a path with non-monotonic edges is counted both as positive and as negative.
+    ///
+    /// Only paths with at most `upper_bound` edges are counted, and the counting stops once
+    /// the total number of paths reaches `limit`.
+    pub fn signed_path_counts(
+        &self,
+        source: VariableId,
+        target: VariableId,
+        upper_bound: usize,
+        limit: usize,
+    ) -> (usize, usize) {
+        let mut counts = (0, 0);
+        if limit == 0 {
+            return counts;
+        }
+        self.visit_signed_simple_paths(source, target, upper_bound, &mut |_, sign| {
+            match sign {
+                Positive => counts.0 += 1,
+                Negative => counts.1 += 1,
+            }
+            counts.0 + counts.1 < limit
+        });
+        counts
+    }
+
+    /// Find the shortest simple path from `source` to `target` with the given `sign`.
+    /// The result starts with `source` and ends with `target`. Returns `None` if there
+    /// is no such path.
+    ///
+    /// As in the case of [SdGraph::shortest_parity_cycle], only simple paths are considered,
+    /// because otherwise the parity of a path could be changed by repeating a cycle.
+    ///
+    /// The shortest signed *walk* is found using a BFS in `O(|V| + |E|)`. If this walk is also
+    /// a simple path, it is returned immediately (this is the typical case). Otherwise, the
+    /// method falls back to an exhaustive search of simple paths with increasing length
+    /// (finding the shortest simple path with a given parity is NP-hard in general). In the
+    /// worst case, this enumerates all simple paths from `source` (up to the length of the
+    /// result, or all of them if no such path exists), which can take exponential time in
+    /// large, densely connected graphs.
+    pub fn shortest_signed_path(
+        &self,
+        source: VariableId,
+        target: VariableId,
+        sign: Sign,
+    ) -> Option<Vec<VariableId>> {
+        /*
+           First, we use BFS on the graph of (vertex, sign) pairs to find the shortest signed
+           walk. If the walk is also a simple path, we are done. Otherwise, the length of the
+           walk is a lower bound for the length of the simple path, and we continue with an
+           exhaustive (iterative deepening) search from this bound.
+        */
+        type Config = (VariableId, Sign);
+        let mut predecessor: HashMap<Config, Config> = HashMap::new();
+        let mut queue: VecDeque<(Config, usize)> = VecDeque::from([((source, Positive), 0)]);
+        let mut found: Option<(Config, usize)> = None;
+        'bfs: while let Some(((x, x_sign), length)) = queue.pop_front() {
+            for (y, edge_sign) in &self.successors[x.to_index()] {
+                let y_config = (*y, x_sign + *edge_sign);
+                if y_config == (target, sign) {
+                    predecessor.insert(y_config, (x, x_sign));
+                    found = Some((y_config, length + 1));
+                    break 'bfs;
+                }
+                if predecessor.contains_key(&y_config) || y_config == (source, Positive) {
+                    continue;
+                }
+                predecessor.insert(y_config, (x, x_sign));
+                queue.push_back((y_config, length + 1));
+            }
+        }
+
+        let (mut config, lower_bound) = found?;
+        let mut walk = vec![config.0];
+        while config != (source, Positive) || walk.len() == 1 {
+            config = predecessor[&config];
+            walk.push(config.0);
+        }
+        walk.reverse();
+
+        // The walk is simple if no vertex repeats, except for the case when source is
+        // also the target.
+        let head: HashSet<VariableId> = walk[..walk.len() - 1].iter().cloned().collect();
+        let tail: HashSet<VariableId> = walk[1..].iter().cloned().collect();
+        if head.len() == walk.len() - 1 && tail.len() == walk.len() - 1 {
+            return Some(walk);
+        }
+
+        for upper_bound in lower_bound..=self.successors.len() {
+            let mut result = None;
+            self.visit_signed_simple_paths(source, target, upper_bound, &mut |path, path_sign| {
+                if path_sign == sign && path.len() == upper_bound + 1 {
+                    result = Some(path.to_vec());
+                    false
+                } else {
+                    true
+                }
+            });
+            if result.is_some() {
+                return result;
+            }
+        }
+
+        None
+    }
+
+    /// Compute the signs of all simple paths from `source` to `target` with at most
+    /// `upper_bound` edges. The result is empty if there is no such path, and it contains
+    /// both signs if both positive and negative paths exist (e.g. due to a non-monotonic edge).
+    ///
+    /// The search stops as soon as both signs are found. However, if all paths share the same
+    /// sign, all simple paths up to `upper_bound` have to be enumerated, which can take
+    /// exponential time in large networks. Hence, the `upper_bound` should be kept reasonably
+    /// small (use `usize::MAX` to consider all simple paths).
+    pub fn path_signs(
+        &self,
+        source: VariableId,
+        target: VariableId,
+        upper_bound: usize,
+    ) -> HashSet<Sign> {
+        let mut result = HashSet::new();
+        self.visit_signed_simple_paths(source, target, upper_bound, &mut |_, sign| {
+            result.insert(sign);
+            result.len() < 2
+        });
+        result
+    }
+
+    /// Compute the "net effect" of `source` on `target`, i.e. the sign shared by all simple paths
+    /// from `source` to `target` with at most `upper_bound` edges. Returns `None` if there is
+    /// no such path, or if paths of both signs exist.
+    ///
+    /// See [SdGraph::path_signs] for the cost of this operation.
+    pub fn net_effect(
+        &self,
+        source: VariableId,
+        target: VariableId,
+        upper_bound: usize,
+    ) -> Option<Sign> {
+        let signs = self.path_signs(source, target, upper_bound);
+        if signs.len() == 1 {
+            signs.into_iter().next()
+        } else {
+            None
+        }
+    }
+
+    /// **(internal)** Call `callback` for every signed simple path from `source` to `target`
+    /// with at most `upper_bound` edges. The callback can stop the search by returning `false`.
+    ///
+    /// The path given to the callback starts with `source` and ends with `target`.
+    fn visit_signed_simple_paths<F>(
+        &self,
+        source: VariableId,
+        target: VariableId,
+        upper_bound: usize,
+        callback: &mut F,
+    ) where
+        F: FnMut(&[VariableId], Sign) -> bool,
+    {
+        if upper_bound == 0 {
+            return;
+        }
+
+        let mut on_path = vec![false; self.successors.len()];
+        on_path[source.to_index()] = true;
+        let mut path = vec![source];
+        // For every vertex of the path, the sign of the path so far and the index
+        // of the next successor to explore.
+        let mut frames: Vec<(Sign, usize)> = vec![(Positive, 0)];
+        while let Some((path_sign, next)) = frames.last_mut() {
+            let x = *path.last().unwrap();
+            let x_successors = &self.successors[x.to_index()];
+            if *next >= x_successors.len() {
+                frames.pop();
+                path.pop();
+                on_path[x.to_index()] = false;
+                continue;
+            }
+            let (y, edge_sign) = x_successors[*next];
+            *next += 1;
+            let y_sign = *path_sign + edge_sign;
+            if y == target {
+                path.push(y);
+                let should_continue = callback(&path, y_sign);
+                path.pop();
+                if !should_continue {
+                    return;
+                }
+            } else if !on_path[y.to_index()] && path.len() < upper_bound {
+                on_path[y.to_index()] = true;
+                path.push(y);
+                frames.push((y_sign, 0));
+            }
+        }
+    }
+}
+
+/// **(internal)** Deduplicated list of neighbour vertices (ignoring signs).
+fn distinct_neighbours(edges: &[(VariableId, Sign)]) -> Vec<VariableId> {
+    let mut result: Vec<VariableId> = edges.iter().map(|(it, _)| *it).collect();
+    result.sort();
+    result.dedup();
+    result
+}
+
+/// **(internal)** Compute the degree distribution based on the given edge relation.
+fn degree_distribution(edges: &[Vec<(VariableId, Sign)>]) -> Vec<usize> {
+    let mut result = Vec::new();
+    for vertex_edges in edges {
+        let degree = distinct_neighbours(vertex_edges).len();
+        if result.len() <= degree {
+            result.resize(degree + 1, 0);
+        }
+        result[degree] += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::_impl_regulatory_graph::_impl_misc::tests::build_test_regulatory_graph;
+    use crate::_impl_regulatory_graph::signed_directed_graph::SdGraph;
+    use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
+    use crate::RegulatoryGraph;
+    use std::collections::HashSet;
+
+    #[test]
+    pub fn test_degrees_and_centrality() {
+        // See method for high-level graph description.
+        let rg = build_test_regulatory_graph();
+        let graph = SdGraph::from(&rg);
+
+        let a = rg.find_variable("a").unwrap();
+        let c = rg.find_variable("c").unwrap();
+        let d_2 = rg.find_variable("d_2").unwrap();
+        let e = rg.find_variable("e").unwrap();
+
+        assert_eq!(graph.in_degree(c), 2);
+        assert_eq!(graph.out_degree(c), 2);
+        assert_eq!(graph.in_degree(d_2), 3);
+        // In-degrees: a=0, b_1=1, b_2=1, c=2, d_1=1, d_2=3, d_3=1, e=2.
+        assert_eq!(graph.in_degree_distribution(), vec![1, 4, 2, 1]);
+        // Out-degrees: a=1, b_1=1, b_2=2, c=2, d_1=2, d_2=1, d_3=1, e=1.
+        assert_eq!(graph.out_degree_distribution(), vec![0, 5, 3]);
+
+        let betweenness = graph.betweenness_centrality();
+        // Nothing passes through `a` and `e`.
+        assert_eq!(betweenness[&a], 0.0);
+        assert_eq!(betweenness[&e], 0.0);
+        // `c` is on all paths from {a, b_1, b_2} to {d_1, d_2, d_3, e}.
+        assert_eq!(betweenness[&c], 12.0);
+
+        let closeness = graph.closeness_centrality();
+        assert_eq!(closeness[&e], 0.0);
+        // `c` reaches 4 vertices with total distance 1 + 1 + 2 + 3 = 7.
+        assert_eq!(closeness[&c], (4.0 / 7.0) * (4.0 / 7.0));
+    }
+
+    #[test]
+    pub fn test_signed_paths() {
+        let rg = RegulatoryGraph::try_from(
+            r#"
+            a -> b
+            b -| c
+            a -> c
+            c -> d
+            a -| e
+            e -| d
+            d -> f
+            f -? g
+        "#,
+        )
+        .unwrap();
+
+        let a = rg.find_variable("a").unwrap();
+        let b = rg.find_variable("b").unwrap();
+        let c = rg.find_variable("c").unwrap();
+        let d = rg.find_variable("d").unwrap();
+        let e = rg.find_variable("e").unwrap();
+        let f = rg.find_variable("f").unwrap();
+        let g = rg.find_variable("g").unwrap();
+
+        let graph = SdGraph::from(&rg);
+
+        // a -> c -> d (+), a -> b -| c -> d (-), a -| e -| d (+).
+        assert_eq!(
+            graph.signed_path_counts(a, d, usize::MAX, usize::MAX),
+            (2, 1)
+        );
+        assert_eq!(graph.signed_path_counts(a, d, 2, usize::MAX), (2, 0));
+        let (positive, negative) = graph.signed_path_counts(a, d, usize::MAX, 2);
+        assert_eq!(positive + negative, 2);
+
+        assert_eq!(graph.shortest_signed_path(a, d, Positive).unwrap().len(), 3);
+        assert_eq!(
+            graph.shortest_signed_path(a, d, Negative),
+            Some(vec![a, b, c, d])
+        );
+        assert_eq!(graph.shortest_signed_path(d, a, Positive), None);
+
+        assert_eq!(graph.net_effect(a, c, usize::MAX), None);
+        assert_eq!(graph.net_effect(e, f, usize::MAX), Some(Negative));
+        assert_eq!(graph.net_effect(b, f, usize::MAX), Some(Negative));
+        assert_eq!(graph.net_effect(f, a, usize::MAX), None);
+        assert_eq!(graph.path_signs(f, a, usize::MAX), HashSet::new());
+        assert_eq!(
+            graph.path_signs(d, g, usize::MAX),
+            HashSet::from([Positive, Negative])
+        );
+        // With a short enough bound, only the positive paths from `a` to `d` are considered.
+        assert_eq!(graph.path_signs(a, d, 2), HashSet::from([Positive]));
+    }
+
+    #[test]
+    pub fn test_shortest_signed_path_is_simple() {
+        // The shortest negative walk from x_1 to x_3 repeats the negative cycle on x_2,
+        // but the shortest negative simple path goes through x_4 and x_5.
+        let rg = RegulatoryGraph::try_from(
+            r#"
+            x_1 -> x_2
+            x_2 -| x_2
+            x_2 -> x_3
+            x_1 -> x_4
+            x_4 -> x_5
+            x_5 -| x_3
+        "#,
+        )
+        .unwrap();
+
+        let x_1 = rg.find_variable("x_1").unwrap();
+        let x_2 = rg.find_variable("x_2").unwrap();
+        let x_3 = rg.find_variable("x_3").unwrap();
+        let x_4 = rg.find_variable("x_4").unwrap();
+        let x_5 = rg.find_variable("x_5").unwrap();
+
+        let graph = SdGraph::from(&rg);
+        assert_eq!(
+            graph.shortest_signed_path(x_1, x_3, Positive),
+            Some(vec![x_1, x_2, x_3])
+        );
+        assert_eq!(
+            graph.shortest_signed_path(x_1, x_3, Negative),
+            Some(vec![x_1, x_4, x_5, x_3])
+        );
+        // Cycles are also paths.
+        assert_eq!(
+            graph.shortest_signed_path(x_2, x_2, Negative),
+            Some(vec![x_2, x_2])
+        );
+        assert_eq!(graph.shortest_signed_path(x_2, x_2, Positive), None);
+    }
+}
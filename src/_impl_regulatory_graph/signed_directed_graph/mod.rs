@@ -1,6 +1,6 @@
 use crate::_impl_regulatory_graph::signed_directed_graph::Sign::{Negative, Positive};
-use crate::{RegulatoryGraph, VariableId};
-use std::collections::HashSet;
+use crate::{Regulation, RegulatoryGraph, VariableId};
+use std::collections::{HashMap, HashSet};
 use std::ops::Add;
 
 /// **(internal)** Basic utility methods for manipulating the `SdGraph`.
@@ -21,12 +21,25 @@ mod _weakly_connected_components;
 /// variants for each algorithms.
 mod _cycle_detection;
 
+/// **(internal)** Algorithm for enumerating all elementary cycles and computing their parity.
+mod _elementary_cycles;
+
 /// **(internal)** Algorithm for computing an approximation of the minimum feedback vertex set.
 mod _feedback_vertex_set;
 
+/// **(internal)** Algorithms for computing a (heuristic or exact) minimum feedback arc set.
+mod _feedback_arc_set;
+
 /// **(internal)** Algorithm for computing an approximation of the maximum independent cycles set.
 mod _independent_cycles;
 
+/// **(internal)** Detection of small signed network motifs (feed-forward loops, bi-fans, ...).
+mod _motifs;
+
+/// **(internal)** Structural metrics of the signed directed graph (degrees, centrality,
+/// signed paths).
+mod _structural_metrics;
+
 /// A sign enum that describes the monotonicity of edges.
 ///
 /// TODO: If we rewrite the API at some point, this should merge with `Monotonicity`.
@@ -46,6 +59,55 @@ pub struct SdGraph {
     predecessors: Vec<Vec<(VariableId, Sign)>>,
 }
 
+/// A small signed pattern graph (network motif) that can be searched for in an `SdGraph`.
+///
+/// The motif has `vertex_count` vertices (identified by indices `0..vertex_count`) and
+/// a list of directed `edges`. Each edge can optionally require a specific `Sign`. If the sign
+/// is not given, any edge (including a non-monotonic one) matches the pattern edge.
+///
+/// New patterns can be created using `Motif::new`. A few common motifs are also
+/// available as predefined constructors (e.g. `Motif::feed_forward_loop`).
+///
+/// Additionally, a motif can declare two `parallel_paths` (lists of edge indices) which
+/// determine the coherence of its occurrences (see [Motif::is_coherent]), and a
+/// `feedback_loop` (list of edge indices) which determines the feedback sign of its
+/// occurrences (see [Motif::feedback_sign]).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Motif {
+    name: String,
+    vertex_count: usize,
+    edges: Vec<(usize, usize, Option<Sign>)>,
+    parallel_paths: Option<(Vec<usize>, Vec<usize>)>,
+    feedback_loop: Option<Vec<usize>>,
+}
+
+/// One occurrence of a `Motif` in an `SdGraph`.
+///
+/// The `vertices` are the graph vertices assigned to the motif vertices (i.e. `vertices[i]` is
+/// the image of motif vertex `i`). The `signs` are the signs of the matched graph edges,
+/// in the same order as the edges of the `Motif`. The sign is `None` if the graph edge is
+/// non-monotonic.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MotifOccurrence {
+    pub vertices: Vec<VariableId>,
+    pub signs: Vec<Option<Sign>>,
+}
+
+/// The number of occurrences of a single `Motif` (see [RegulatoryGraph::motif_census]).
+///
+/// The `coherent` and `incoherent` counts are only relevant for motifs with parallel
+/// paths, and the `positive_feedback` and `negative_feedback` counts are only relevant for
+/// motifs with a feedback loop. Occurrences with non-monotonic edges on the relevant paths
+/// are only included in the `total`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct MotifCensus {
+    pub total: usize,
+    pub coherent: usize,
+    pub incoherent: usize,
+    pub positive_feedback: usize,
+    pub negative_feedback: usize,
+}
+
 impl Add for Sign {
     type Output = Sign;
 
@@ -125,6 +187,68 @@ impl RegulatoryGraph {
         graph.restricted_parity_feedback_vertex_set(&graph.mk_all_vertices(), parity)
     }
 
+    /// Compute the set of regulations that, if removed, cause this `RegulatoryGraph` to become
+    /// acyclic.
+    ///
+    /// The method tries to obtain a minimal such set, but the minimality is not guaranteed.
+    /// The regulations are sorted by regulator and target.
+    pub fn feedback_arc_set(&self) -> Vec<Regulation> {
+        let graph = SdGraph::from(self);
+        let edges = graph.restricted_feedback_arc_set(&graph.mk_all_vertices());
+        self.edges_to_regulations(edges)
+    }
+
+    /// Compute the set of regulations that, if removed, cause this `RegulatoryGraph` to lose
+    /// all cycles of the specified parity.
+    ///
+    /// The method tries to obtain a minimal such set, but the minimality is not guaranteed.
+    /// The regulations are sorted by regulator and target.
+    pub fn parity_feedback_arc_set(&self, parity: Sign) -> Vec<Regulation> {
+        let graph = SdGraph::from(self);
+        let edges = graph.restricted_parity_feedback_arc_set(&graph.mk_all_vertices(), parity);
+        self.edges_to_regulations(edges)
+    }
+
+    /// Compute the smallest set of regulations that, if removed, cause this `RegulatoryGraph`
+    /// to become acyclic.
+    ///
+    /// The method enumerates all elementary cycles of the graph, hence it is only usable for
+    /// small graphs. If there are more than `cycle_limit` cycles, the result is `None`.
+    pub fn minimum_feedback_arc_set(&self, cycle_limit: usize) -> Option<Vec<Regulation>> {
+        let graph = SdGraph::from(self);
+        let edges =
+            graph.restricted_minimum_feedback_arc_set(&graph.mk_all_vertices(), cycle_limit)?;
+        Some(self.edges_to_regulations(edges))
+    }
+
+    /// Compute the smallest set of regulations that, if removed, cause this `RegulatoryGraph`
+    /// to lose all cycles of the specified parity.
+    ///
+    /// See [RegulatoryGraph::minimum_feedback_arc_set] for notes about complexity.
+    pub fn minimum_parity_feedback_arc_set(
+        &self,
+        parity: Sign,
+        cycle_limit: usize,
+    ) -> Option<Vec<Regulation>> {
+        let graph = SdGraph::from(self);
+        let edges = graph.restricted_minimum_parity_feedback_arc_set(
+            &graph.mk_all_vertices(),
+            parity,
+            cycle_limit,
+        )?;
+        Some(self.edges_to_regulations(edges))
+    }
+
+    /// **(internal)** Convert a set of `SdGraph` edges into a sorted list of regulations.
+    fn edges_to_regulations(&self, edges: HashSet<(VariableId, VariableId)>) -> Vec<Regulation> {
+        let mut edges = Vec::from_iter(edges);
+        edges.sort();
+        edges
+            .into_iter()
+            .map(|(regulator, target)| self.find_regulation(regulator, target).unwrap().clone())
+            .collect()
+    }
+
     /// Compute a collection of independent cycles of this `RegulatoryGraph`. That is, disjoint
     /// cycles that intersect every cycle in the graph.
     ///
@@ -143,4 +267,142 @@ impl RegulatoryGraph {
         let graph = SdGraph::from(self);
         graph.restricted_independent_parity_cycles(&graph.mk_all_vertices(), parity)
     }
+
+    /// Enumerate the elementary cycles (feedback loops) of this `RegulatoryGraph`. Each cycle
+    /// starts with its smallest variable, followed by the remaining variables in the order
+    /// in which they appear on the cycle.
+    ///
+    /// The number of elementary cycles can be exponential, so only cycles with at most
+    /// `upper_bound` regulations are considered, and at most `limit` cycles are returned.
+    pub fn elementary_cycles(&self, upper_bound: usize, limit: usize) -> Vec<Vec<VariableId>> {
+        SdGraph::from(self).elementary_cycles(upper_bound, limit)
+    }
+
+    /// Compute the sign of the given `cycle` based on the `Monotonicity` of its regulations.
+    ///
+    /// Returns `None` if the cycle contains a regulation without monotonicity. Panics if
+    /// the `cycle` is not a cycle of this graph.
+    pub fn cycle_sign(&self, cycle: &[VariableId]) -> Option<Sign> {
+        SdGraph::from(self).cycle_sign(cycle)
+    }
+
+    /// Count the positive and negative elementary cycles (feedback loops) that each variable
+    /// is a member of. The result maps each variable to a `(positive, negative)` tuple.
+    ///
+    /// Cycles containing a regulation without monotonicity are not counted. The `upper_bound`
+    /// and `limit` arguments have the same meaning as in
+    /// [RegulatoryGraph::elementary_cycles].
+    pub fn feedback_loop_counts(
+        &self,
+        upper_bound: usize,
+        limit: usize,
+    ) -> HashMap<VariableId, (usize, usize)> {
+        let graph = SdGraph::from(self);
+        graph.restricted_feedback_loop_counts(&graph.mk_all_vertices(), upper_bound, limit)
+    }
+
+    /// Find all occurrences of the given `motif` in this `RegulatoryGraph`.
+    ///
+    /// See [SdGraph::find_motifs] for details.
+    pub fn find_motifs(&self, motif: &Motif) -> Vec<MotifOccurrence> {
+        SdGraph::from(self).find_motifs(motif)
+    }
+
+    /// Count the occurrences of each of the given `motifs` in this `RegulatoryGraph`.
+    ///
+    /// For each motif, the result also counts the coherent and incoherent occurrences
+    /// (see [Motif::is_coherent]), and the occurrences with positive and negative feedback
+    /// (see [Motif::feedback_sign]).
+    pub fn motif_census(&self, motifs: &[Motif]) -> Vec<MotifCensus> {
+        let graph = SdGraph::from(self);
+        motifs
+            .iter()
+            .map(|motif| {
+                let mut census = MotifCensus::default();
+                for occurrence in graph.find_motifs(motif) {
+                    census.total += 1;
+                    match motif.is_coherent(&occurrence) {
+                        Some(true) => census.coherent += 1,
+                        Some(false) => census.incoherent += 1,
+                        None => (),
+                    }
+                    match motif.feedback_sign(&occurrence) {
+                        Some(Positive) => census.positive_feedback += 1,
+                        Some(Negative) => census.negative_feedback += 1,
+                        None => (),
+                    }
+                }
+                census
+            })
+            .collect()
+    }
+
+    /// Compute the in-degree distribution of this `RegulatoryGraph`. The value at index `k`
+    /// is the number of variables with exactly `k` regulators.
+    pub fn in_degree_distribution(&self) -> Vec<usize> {
+        SdGraph::from(self).in_degree_distribution()
+    }
+
+    /// Compute the out-degree distribution of this `RegulatoryGraph`. The value at index `k`
+    /// is the number of variables with exactly `k` targets.
+    pub fn out_degree_distribution(&self) -> Vec<usize> {
+        SdGraph::from(self).out_degree_distribution()
+    }
+
+    /// Compute the betweenness centrality of every variable.
+    ///
+    /// See [SdGraph::betweenness_centrality] for details.
+    pub fn betweenness_centrality(&self) -> HashMap<VariableId, f64> {
+        SdGraph::from(self).betweenness_centrality()
+    }
+
+    /// Compute the closeness centrality of every variable.
+    ///
+    /// See [SdGraph::closeness_centrality] for details.
+    pub fn closeness_centrality(&self) -> HashMap<VariableId, f64> {
+        SdGraph::from(self).closeness_centrality()
+    }
+
+    /// Count the positive and negative simple paths from `regulator` to `target` as
+    /// a `(positive, negative)` tuple.
+    ///
+    /// See [SdGraph::signed_path_counts] for details.
+    pub fn signed_path_counts(
+        &self,
+        regulator: VariableId,
+        target: VariableId,
+        upper_bound: usize,
+        limit: usize,
+    ) -> (usize, usize) {
+        SdGraph::from(self).signed_path_counts(regulator, target, upper_bound, limit)
+    }
+
+    /// Compute the shortest simple path from `regulator` to `target` with the given `sign`,
+    /// or `None` if there is no such path.
+    ///
+    /// See [SdGraph::shortest_signed_path] for the cost of this operation (it can take
+    /// exponential time in the worst case).
+    pub fn shortest_signed_path(
+        &self,
+        regulator: VariableId,
+        target: VariableId,
+        sign: Sign,
+    ) -> Option<Vec<VariableId>> {
+        SdGraph::from(self).shortest_signed_path(regulator, target, sign)
+    }
+
+    /// Compute the "net effect" of `regulator` on `target`, i.e. the sign shared by all simple
+    /// paths between the two variables with at most `upper_bound` regulations. Returns `None`
+    /// if there is no such path, or if there are paths of both signs (including paths with
+    /// non-monotonic regulations).
+    ///
+    /// See [SdGraph::path_signs] for the cost of this operation.
+    pub fn net_effect(
+        &self,
+        regulator: VariableId,
+        target: VariableId,
+        upper_bound: usize,
+    ) -> Option<Sign> {
+        SdGraph::from(self).net_effect(regulator, target, upper_bound)
+    }
 }
@@ -62,6 +62,18 @@ fn flatten_fn_update(network: &mut BooleanNetwork, update: &FnUpdate) -> FnUpdat
             Box::new(flatten_fn_update(network, left)),
             Box::new(flatten_fn_update(network, right)),
         ),
+        FnUpdate::Threshold(args, threshold) => FnUpdate::Threshold(
+            args.iter()
+                .map(|(w, it)| (*w, flatten_fn_update(network, it)))
+                .collect(),
+            *threshold,
+        ),
+        FnUpdate::Nary(op, args) => FnUpdate::Nary(
+            *op,
+            args.iter()
+                .map(|it| flatten_fn_update(network, it))
+                .collect(),
+        ),
     }
 }
 
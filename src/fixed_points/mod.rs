@@ -786,7 +786,8 @@ mod tests {
         for (state, function) in projection.iter() {
             assert_eq!(1, function.len());
             // All functions should be non-trivial. That's all I've got.
-            assert!(function[0].1.as_binary().is_some());
+            let function = &function[0].1;
+            assert!(function.as_binary().is_some() || function.as_nary().is_some());
             assert_eq!((VariableId(2), false), state[1]);
             if state[0].1 {
                 has_true = true;
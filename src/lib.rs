@@ -14,12 +14,13 @@ extern crate lazy_static;
 extern crate core;
 
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::iter::Map;
 use std::ops::Range;
 
 pub mod biodivine_std;
 pub mod fixed_points;
+pub mod multi_valued;
 pub mod sbml;
 #[cfg(feature = "solver-z3")]
 pub mod solver_context;
@@ -35,20 +36,49 @@ mod _impl_annotations;
 mod _impl_binary_op;
 /// **(internal)** Utility methods for `BooleanNetwork`.
 mod _impl_boolean_network;
+/// **(internal)** Implements Bio Model Analyzer (BMA) JSON parser and writer for `BooleanNetwork`.
+#[cfg(feature = "json")]
+mod _impl_boolean_network_bma;
+/// **(internal)** Implements BoolNet (R package) parser and writer for `BooleanNetwork`.
+mod _impl_boolean_network_boolnet;
 /// **(internal)** `BooleanNetwork` to `.aeon` string.
 mod _impl_boolean_network_display;
 /// **(internal)** Implements experimental `.bnet` parser for `BooleanNetwork`.
 mod _impl_boolean_network_from_bnet;
+/// **(internal)** Implements GINsim `.ginml` parser for `BooleanNetwork`.
+mod _impl_boolean_network_from_ginml;
 /// **(internal)** Implements an experimental `.bnet` writer for `BooleanNetwork`.
 mod _impl_boolean_network_to_bnet;
+/// **(internal)** Implements DIMACS CNF writer for the `SolverEncoding` of a `BooleanNetwork`.
+mod _impl_boolean_network_to_dimacs;
+/// **(internal)** Implements MaBoSS model writer for `BooleanNetwork` and `MaBossSettings`.
+mod _impl_boolean_network_to_maboss;
+/// **(internal)** Implements NuSMV/nuXmv model writer for `BooleanNetwork`.
+mod _impl_boolean_network_to_nusmv;
+/// **(internal)** Implements SMT-LIB2 writer for the `SolverEncoding` of a `BooleanNetwork`.
+mod _impl_boolean_network_to_smt;
+/// **(internal)** Import and export of update functions as CSV truth tables.
+mod _impl_boolean_network_truth_table;
+/// **(internal)** Construction of threshold networks from signed weighted graphs.
+mod _impl_boolean_network_weighted_graph;
 /// **(internal)** All methods implemented by the `ExtendedBoolean` object.
 mod _impl_extended_boolean;
 /// **(internal)** Utility methods for `FnUpdate`.
 mod _impl_fn_update;
+/// **(internal)** Logic minimisation (prime implicants, minimal DNF/CNF) of `FnUpdate`.
+mod _impl_fn_update_minimization;
+/// **(internal)** Utility methods for `FunctionClass` and function class constraints.
+mod _impl_function_class;
+/// **(internal)** Known values (partial truth tables) of uninterpreted functions.
+mod _impl_function_values;
+/// **(internal)** Utility methods for `NaryOp`.
+mod _impl_nary_op;
 /// **(internal)** Utility methods for `Parameter`.
 mod _impl_parameter;
 /// **(internal)** Utility methods for `ParameterId`.
 mod _impl_parameter_id;
+/// **(internal)** Utility methods for `ParameterInstantiation`.
+mod _impl_parameter_instantiation;
 /// **(internal)** Utility methods for `Regulation`.
 mod _impl_regulation;
 /// **(internal)** All methods for analysing and manipulating `RegulatoryGraph`.
@@ -61,6 +91,10 @@ mod _impl_variable;
 mod _impl_variable_id;
 
 // Re-export data structures used for advanced graph algorithms on `RegulatoryGraph`.
+pub use _impl_regulatory_graph::_impl_graph_export::RegulatoryGraphExport;
+pub use _impl_regulatory_graph::signed_directed_graph::Motif;
+pub use _impl_regulatory_graph::signed_directed_graph::MotifCensus;
+pub use _impl_regulatory_graph::signed_directed_graph::MotifOccurrence;
 pub use _impl_regulatory_graph::signed_directed_graph::SdGraph;
 pub use _impl_regulatory_graph::signed_directed_graph::Sign;
 
@@ -129,6 +163,30 @@ pub enum Monotonicity {
     Inhibition,
 }
 
+/// A class of Boolean functions that can be required for an uninterpreted function of
+/// a `BooleanNetwork` (an explicit parameter or an implicit update function).
+///
+/// In the `.aeon` format, the classes are assigned using lines such as
+/// `@f: unate, nested_canalizing`, where `f` is either a parameter, or a variable with
+/// an implicit update function.
+///
+/// Constant functions belong to every class except `Majority` (a majority function
+/// with at least one argument is never constant).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum FunctionClass {
+    /// Every argument is either positive or negative monotonic (`unate`).
+    Unate,
+    /// The function is nested canalizing in all its essential arguments, i.e. its
+    /// canalization depth is the number of essential arguments (`nested_canalizing`).
+    NestedCanalizing,
+    /// A linear threshold function `[w_1 * x_1 + ... + w_k * x_k >= t]` with integer
+    /// weights (`threshold`). Only supported for functions with at most five arguments.
+    Threshold,
+    /// A majority of signed arguments `[l_1 + ... + l_k >= t]`, where every `l_i` is
+    /// either `x_i` or `!x_i`, and `t` is `ceil(k / 2)` or `floor(k / 2) + 1` (`majority`).
+    Majority,
+}
+
 /// A Boolean variable of a `RegulatoryGraph` (or a `BooleanNetwork`) with a given `name`.
 ///
 /// `Variable` can be only created by and borrowed from a `RegulatoryGraph`.
@@ -160,12 +218,20 @@ pub struct Parameter {
 ///  of the `target` update function in *some* context. If set to false, this is not enforced
 ///  (i.e. the `regulator` *can* have an influence on the `target`, but it is not required).
 ///
+/// Furthermore, a regulation can be *uncertain*, meaning that it is not known whether the
+/// regulation exists at all. Symbolically, every uncertain regulation is represented by a
+/// dedicated parameter: if the regulation exists, its monotonicity and observability are
+/// enforced as usual, otherwise the `regulator` must have no influence on the `target`.
+/// As such, the colors of a `SymbolicAsyncGraph` then also cover the alternative topologies
+/// of the network.
+///
 /// Regulations can be represented as strings in the
 /// form `"regulator_name 'relationship' target_name"`. The 'relationship' starts with `-`, which
 /// is followed by `>` for activation (positive monotonicity), `|` for inhibition (negative
 /// monotonicity) or `?` for unspecified monotonicity. Finally, an additional `?` at the end
 /// of 'relationship' signifies a non-observable regulation. Together, this gives the
-/// following options:  `->, ->?, -|, -|?, -?, -??`.
+/// following options:  `->, ->?, -|, -|?, -?, -??`. Uncertain regulations are prefixed with
+/// an extra `?`, e.g. `a ?-> b`.
 ///
 /// Regulations cannot be created directly, they are only borrowed from a `RegulatoryGraph`
 /// or a `BooleanNetwork`.
@@ -175,6 +241,7 @@ pub struct Regulation {
     pub target: VariableId,
     pub observable: bool,
     pub monotonicity: Option<Monotonicity>,
+    pub(crate) uncertain: bool,
 }
 
 /// A directed graph representing relationships between a collection of Boolean variables
@@ -222,6 +289,13 @@ pub enum BinaryOp {
     Imp,
 }
 
+/// Possible n-ary Boolean operators that can appear in `FnUpdate`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NaryOp {
+    And,
+    Or,
+}
+
 /// A Boolean update function formula which references
 /// `Variables` and `Parameters` of a `BooleanNetwork`.
 ///
@@ -242,6 +316,16 @@ pub enum FnUpdate {
     Not(Box<FnUpdate>),
     /// Binary boolean operation.
     Binary(BinaryOp, Box<FnUpdate>, Box<FnUpdate>),
+    /// N-ary boolean operation, i.e. a flat conjunction or disjunction of the arguments.
+    ///
+    /// An empty conjunction is `true` and an empty disjunction is `false`.
+    Nary(NaryOp, Vec<FnUpdate>),
+    /// Threshold (weighted counting) operation: true when the sum of weights of the
+    /// arguments that are true is at least the given threshold.
+    ///
+    /// For example, "at least `k` of `x_1, ..., x_n`" has all weights equal to one and
+    /// threshold `k`.
+    Threshold(Vec<(i32, FnUpdate)>, i32),
 }
 
 /// A Boolean network, possibly parametrised with uninterpreted Boolean functions.
@@ -271,6 +355,10 @@ pub struct BooleanNetwork {
     parameters: Vec<Parameter>,
     update_functions: Vec<Option<FnUpdate>>,
     parameter_to_index: HashMap<String, ParameterId>,
+    parameter_classes: HashMap<ParameterId, BTreeSet<FunctionClass>>,
+    implicit_classes: HashMap<VariableId, BTreeSet<FunctionClass>>,
+    parameter_values: HashMap<ParameterId, BTreeMap<Vec<bool>, bool>>,
+    implicit_values: HashMap<VariableId, BTreeMap<Vec<bool>, bool>>,
 }
 
 /// An iterator over all `VariableIds` of a `RegulatoryGraph` (or a `BooleanNetwork`).
@@ -345,3 +433,48 @@ pub struct ModelAnnotation {
     value: Option<String>,
     inner: HashMap<String, ModelAnnotation>,
 }
+
+/// Settings of a MaBoSS simulation, used when exporting a `BooleanNetwork` into
+/// the MaBoSS `.bnd` and `.cfg` files (see `BooleanNetwork::to_maboss`).
+///
+/// Every variable has an activation (`rate_up`) and deactivation (`rate_down`) rate, and
+/// an initial probability of being active. Values which are not set explicitly fall back
+/// to the default rates and an initial probability of `0.5`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaBossSettings {
+    default_rates: (f64, f64),
+    rates: HashMap<VariableId, (f64, f64)>,
+    initial_states: HashMap<VariableId, f64>,
+    time_tick: f64,
+    max_time: f64,
+    sample_count: u64,
+    discrete_time: bool,
+}
+
+/// Identifies the problem that is encoded when a `BooleanNetwork` is exported into a solver
+/// input format (see `BooleanNetwork::to_smt_lib` and `BooleanNetwork::to_dimacs`).
+///
+/// Every encoding also includes the static constraints (observability and monotonicity)
+/// of the network regulations, such that only valid parametrisations are admissible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SolverEncoding {
+    /// Only the static constraints on the network parametrisations.
+    ParameterConstraints,
+    /// Fixed-point states of the network, i.e. `x <=> f_x(x)` for every variable.
+    FixedPoints,
+    /// Trap spaces of the network, encoded using two variables per network variable: one
+    /// is true when the space admits value `1`, the other when it admits value `0`.
+    TrapSpaces,
+}
+
+/// An explicit instantiation of the uninterpreted functions of a `BooleanNetwork`, i.e. a
+/// truth table for every explicit parameter and for every implicit update function.
+///
+/// A truth table of a function with `k` arguments has `2^k` rows. The index of a row encodes
+/// the values of the arguments, where the `i`-th argument is the `i`-th least significant
+/// bit of the index.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParameterInstantiation {
+    explicit: HashMap<ParameterId, Vec<bool>>,
+    implicit: HashMap<VariableId, Vec<bool>>,
+}
@@ -0,0 +1,295 @@
+use crate::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+use crate::biodivine_std::traits::Set;
+use crate::multi_valued::{Booleanization, MultiValuedVariable};
+use crate::symbolic_async_graph::{GraphColoredVertices, SymbolicAsyncGraph};
+use crate::{BooleanNetwork, FnUpdate, RegulatoryGraph, VariableId};
+
+impl MultiValuedVariable {
+    /// The name of the original multi-valued variable.
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The maximal level of this variable (i.e. the levels are `0..=max_level`).
+    pub fn get_max_level(&self) -> u32 {
+        self.max_level
+    }
+
+    /// The Boolean variables encoding this multi-valued variable. The `i`-th variable
+    /// is true if and only if the level is at least `i + 1`.
+    pub fn encoding(&self) -> &[VariableId] {
+        &self.encoding
+    }
+
+    /// Compute the level of this variable in the given Boolean `state`. Returns `None` if the
+    /// state is not admissible for this variable.
+    pub fn level(&self, state: &ArrayBitVector) -> Option<u32> {
+        let level = self
+            .encoding
+            .iter()
+            .take_while(|it| state.get(it.to_index()))
+            .count();
+        if self.encoding[level..]
+            .iter()
+            .any(|it| state.get(it.to_index()))
+        {
+            None
+        } else {
+            Some(level as u32)
+        }
+    }
+
+    /// **(internal)** Create an update function expression which is true if and only if
+    /// the level of this variable is at least `level`.
+    pub(crate) fn mk_level_geq(&self, level: u32) -> FnUpdate {
+        if level == 0 {
+            FnUpdate::mk_true()
+        } else if level > self.max_level {
+            FnUpdate::mk_false()
+        } else {
+            FnUpdate::mk_var(self.encoding[(level - 1) as usize])
+        }
+    }
+
+    /// **(internal)** Build the Van Ham update functions for the Boolean variables of this
+    /// multi-valued variable, given a function that produces an expression which is true
+    /// if and only if the *target level* of the variable is at least `k` (for `k >= 1`).
+    ///
+    /// The Boolean variable `x_bk` can only change when `x` is `k - 1` or `k`, in which case
+    /// it is updated to `target >= k`. This way, every update changes the level by one,
+    /// and the set of admissible states is preserved.
+    pub(crate) fn mk_update_functions<F>(&self, target_geq: F) -> Vec<FnUpdate>
+    where
+        F: Fn(u32) -> FnUpdate,
+    {
+        (1..=self.max_level)
+            .map(|k| {
+                let mut function = target_geq(k);
+                if k > 1 {
+                    function = self.mk_level_geq(k - 1).and(function);
+                }
+                if k < self.max_level {
+                    function = self.mk_level_geq(k + 1).or(function);
+                }
+                function
+            })
+            .collect()
+    }
+
+    /// **(internal)** Build an expression which is true if and only if the target level
+    /// is at least `level`, assuming the target level is the maximal level of a satisfied
+    /// `terms` condition, or `default` if no condition is satisfied.
+    pub(crate) fn mk_target_geq(terms: &[(u32, FnUpdate)], default: u32, level: u32) -> FnUpdate {
+        let mut clauses = terms
+            .iter()
+            .filter(|(result, _)| *result >= level)
+            .map(|(_, condition)| condition.clone())
+            .collect::<Vec<_>>();
+        if default >= level {
+            let no_term = terms
+                .iter()
+                .map(|(_, condition)| condition.clone().negation())
+                .collect::<Vec<_>>();
+            clauses.push(FnUpdate::mk_nary_conjunction(&no_term));
+        }
+        if clauses.is_empty() {
+            FnUpdate::mk_false()
+        } else {
+            FnUpdate::mk_nary_disjunction(&clauses)
+        }
+    }
+}
+
+impl Booleanization {
+    /// Create a new `Booleanization` of variables with the given names and maximal levels.
+    ///
+    /// The Boolean variables are assigned IDs in the order of the given variables, such that
+    /// they match a `RegulatoryGraph` created using [Booleanization::boolean_variable_names].
+    pub fn new(variables: &[(String, u32)]) -> Booleanization {
+        let mut result = Vec::with_capacity(variables.len());
+        let mut next_id = 0;
+        for (name, max_level) in variables {
+            let encoding = (next_id..(next_id + *max_level as usize))
+                .map(VariableId::from_index)
+                .collect();
+            next_id += *max_level as usize;
+            result.push(MultiValuedVariable {
+                name: name.clone(),
+                max_level: *max_level,
+                encoding,
+            });
+        }
+        Booleanization { variables: result }
+    }
+
+    /// The names of the Boolean variables, in the order of their `VariableId`s.
+    ///
+    /// Variables with maximal level `1` keep their name, other variables use
+    /// `name_b1, ..., name_bm`.
+    pub fn boolean_variable_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for var in &self.variables {
+            if var.max_level == 1 {
+                names.push(var.name.clone());
+            } else {
+                for k in 1..=var.max_level {
+                    names.push(format!("{}_b{}", var.name, k));
+                }
+            }
+        }
+        names
+    }
+
+    /// The multi-valued variables of this mapping.
+    pub fn variables(&self) -> &[MultiValuedVariable] {
+        &self.variables
+    }
+
+    /// Find a multi-valued variable by its name.
+    pub fn find_variable(&self, name: &str) -> Option<&MultiValuedVariable> {
+        self.variables.iter().find(|it| it.name == name)
+    }
+
+    /// Find the multi-valued variable which is encoded using the given Boolean `variable`.
+    pub fn find_encoded_variable(&self, variable: VariableId) -> Option<&MultiValuedVariable> {
+        self.variables
+            .iter()
+            .find(|it| it.encoding.contains(&variable))
+    }
+
+    /// Decode the levels of all multi-valued variables from a Boolean `state`. Returns `None`
+    /// if the state is not admissible.
+    pub fn decode_state(&self, state: &ArrayBitVector) -> Option<Vec<u32>> {
+        self.variables.iter().map(|it| it.level(state)).collect()
+    }
+
+    /// Encode the `levels` of all multi-valued variables as a Boolean state.
+    ///
+    /// Panics if the number of levels does not match, or if some level is too high.
+    pub fn encode_state(&self, levels: &[u32]) -> ArrayBitVector {
+        assert_eq!(levels.len(), self.variables.len());
+        let num_vars = self.variables.iter().map(|it| it.encoding.len()).sum();
+        let mut state = ArrayBitVector::empty(num_vars);
+        for (var, level) in self.variables.iter().zip(levels) {
+            assert!(*level <= var.max_level, "Invalid level of {}.", var.name);
+            for id in &var.encoding[..(*level as usize)] {
+                state.set(id.to_index(), true);
+            }
+        }
+        state
+    }
+
+    /// Compute the set of admissible states (for all colors) of the given Booleanized `graph`.
+    pub fn mk_admissible_states(&self, graph: &SymbolicAsyncGraph) -> GraphColoredVertices {
+        let mut result = graph.mk_unit_colored_vertices();
+        for var in &self.variables {
+            for k in 1..var.encoding.len() {
+                let invalid = graph
+                    .fix_network_variable(var.encoding[k], true)
+                    .intersect(&graph.fix_network_variable(var.encoding[k - 1], false));
+                result = result.minus(&invalid);
+            }
+        }
+        result
+    }
+
+    /// **(internal)** Create a `BooleanNetwork` with the Boolean variables of this mapping,
+    /// but without any regulations. This network can be used to resolve names of variables
+    /// (and parameters, if added) when building the Booleanized update functions.
+    pub(crate) fn mk_template_network(&self) -> BooleanNetwork {
+        BooleanNetwork::new(RegulatoryGraph::new(self.boolean_variable_names()))
+    }
+
+    /// **(internal)** Build a `BooleanNetwork` with the given Boolean update functions
+    /// and the parameters of the `template` network (see [Booleanization::mk_template_network]).
+    ///
+    /// The regulations are inferred from the functions (see [BooleanNetwork::infer_valid_graph]).
+    pub(crate) fn build_network(
+        &self,
+        template: &BooleanNetwork,
+        functions: Vec<(VariableId, FnUpdate)>,
+    ) -> Result<BooleanNetwork, String> {
+        let mut graph = RegulatoryGraph::new(self.boolean_variable_names());
+        for (target, function) in &functions {
+            for regulator in function.collect_arguments() {
+                let regulator = graph.get_variable_name(regulator).clone();
+                let target = graph.get_variable_name(*target).clone();
+                graph.add_regulation(regulator.as_str(), target.as_str(), false, None)?;
+            }
+        }
+        let mut network = BooleanNetwork::new(graph);
+        for parameter in template.parameters() {
+            let parameter = &template[parameter];
+            network.add_parameter(parameter.get_name(), parameter.get_arity())?;
+        }
+        for (target, function) in functions {
+            network.add_update_function(target, function)?;
+        }
+        network.infer_valid_graph()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+    use crate::biodivine_std::traits::Set;
+    use crate::multi_valued::{Booleanization, MultiValuedVariable};
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::FnUpdate;
+
+    #[test]
+    fn test_van_ham_booleanization() {
+        // `x` has levels 0..=2 and grows until it reaches `y + 1`; `y` copies `x >= 2`.
+        let mapping = Booleanization::new(&[("x".to_string(), 2), ("y".to_string(), 1)]);
+        assert_eq!(
+            mapping.boolean_variable_names(),
+            vec!["x_b1".to_string(), "x_b2".to_string(), "y".to_string()]
+        );
+        let x = mapping.find_variable("x").unwrap().clone();
+        let y = mapping.find_variable("y").unwrap().clone();
+        assert_eq!(mapping.find_encoded_variable(y.encoding()[0]), Some(&y));
+
+        let terms = vec![(1, y.mk_level_geq(1).negation()), (2, y.mk_level_geq(1))];
+        let mut functions = x
+            .mk_update_functions(|k| MultiValuedVariable::mk_target_geq(&terms, 0, k))
+            .into_iter()
+            .enumerate()
+            .map(|(i, f)| (x.encoding()[i], f))
+            .collect::<Vec<_>>();
+        functions.push((y.encoding()[0], x.mk_level_geq(2)));
+        let template = mapping.mk_template_network();
+        let network = mapping.build_network(&template, functions).unwrap();
+        assert_eq!(network.num_vars(), 3);
+
+        // In any admissible state, `x` moves towards the target level.
+        let state = mapping.encode_state(&[0, 1]);
+        assert_eq!(mapping.decode_state(&state), Some(vec![0, 1]));
+        let valuation = network
+            .variables()
+            .map(|v| (v, state.get(v.to_index())))
+            .collect();
+        let f_b1 = network
+            .get_update_function(x.encoding()[0])
+            .as_ref()
+            .unwrap();
+        let f_b2 = network
+            .get_update_function(x.encoding()[1])
+            .as_ref()
+            .unwrap();
+        assert_eq!(f_b1.evaluate(&valuation), Some(true));
+        assert_eq!(f_b2.evaluate(&valuation), Some(false));
+
+        // Inadmissible state.
+        let state = ArrayBitVector::from(vec![false, true, false]);
+        assert_eq!(mapping.decode_state(&state), None);
+
+        // Admissible states form a trap set.
+        let stg = SymbolicAsyncGraph::new(&network).unwrap();
+        let admissible = mapping.mk_admissible_states(&stg);
+        assert_eq!(admissible.approx_cardinality(), 6.0);
+        assert!(stg.can_post_out(&admissible).is_empty());
+
+        assert_eq!(x.mk_level_geq(0), FnUpdate::mk_true());
+        assert_eq!(x.mk_level_geq(3), FnUpdate::mk_false());
+    }
+}
@@ -0,0 +1,30 @@
+//! Support for multi-valued (logical) models through Booleanization.
+//!
+//! Multi-valued models (e.g. from GINsim or multi-valued SBML-qual) are converted into
+//! a `BooleanNetwork` using the Van Ham encoding: a variable `x` with levels `0..=m`
+//! is represented by `m` Boolean variables `x_b1, ..., x_bm`, such that `x_bk` is true
+//! if and only if `x >= k`. Variables with `m = 1` simply keep their original name.
+//!
+//! Only *admissible* states (i.e. states where `x_b(k+1)` implies `x_bk`) correspond to
+//! states of the multi-valued model. The Booleanized update functions preserve
+//! admissibility, and [Booleanization::mk_admissible_states] can be used to restrict the
+//! state space to admissible states.
+
+use crate::VariableId;
+
+mod _impl_booleanization;
+
+/// A single variable of a multi-valued model and its Boolean encoding.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MultiValuedVariable {
+    name: String,
+    max_level: u32,
+    encoding: Vec<VariableId>,
+}
+
+/// A mapping between the variables of a multi-valued model and the variables of its
+/// Booleanized `BooleanNetwork`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Booleanization {
+    variables: Vec<MultiValuedVariable>,
+}
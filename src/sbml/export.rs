@@ -1,10 +1,15 @@
+use crate::multi_valued::{Booleanization, MultiValuedVariable};
 use crate::sbml::Layout;
-use crate::{BinaryOp, BooleanNetwork, FnUpdate, Monotonicity};
-use std::collections::HashMap;
+use crate::{BinaryOp, BooleanNetwork, FnUpdate, Monotonicity, NaryOp, VariableId};
+use std::collections::{HashMap, HashSet};
 use std::io::{Error, Write};
 
 impl BooleanNetwork {
     /// Convert this network to an SBML string with an optional `Layout`.
+    ///
+    /// Note that uninterpreted functions are written as MathML `<csymbol>` applications. This
+    /// is not part of SBML-qual, and such models can be only read back using
+    /// [BooleanNetwork::try_from_sbml].
     pub fn to_sbml(&self, layout: Option<&Layout>) -> String {
         let mut buffer: Vec<u8> = Vec::new();
         self.write_as_sbml(layout, &mut buffer)
@@ -125,6 +130,20 @@ impl BooleanNetwork {
                 self.write_update_function(out, r)?;
                 write!(out, "</apply>")?;
             }
+            FnUpdate::Threshold(_, _) => {
+                self.write_update_function(out, &function.expand_thresholds())?
+            }
+            FnUpdate::Nary(op, args) => {
+                let op = match op {
+                    NaryOp::And => "and",
+                    NaryOp::Or => "or",
+                };
+                write!(out, "<apply><{}/>", op)?;
+                for arg in args {
+                    self.write_update_function(out, arg)?;
+                }
+                write!(out, "</apply>")?;
+            }
             FnUpdate::Param(id, args) => {
                 write!(out, "<apply><csymbol>{}</csymbol>", self[*id].get_name())?;
                 for arg in args {
@@ -137,6 +156,299 @@ impl BooleanNetwork {
     }
 }
 
+impl BooleanNetwork {
+    /// Convert a Booleanized network back to a multi-valued SBML-qual string, using
+    /// the given `mapping` between multi-valued species and Boolean variables
+    /// (see [BooleanNetwork::try_from_multivalued_sbml]).
+    ///
+    /// The update functions must follow the Van Ham encoding (i.e. they should preserve
+    /// admissible states). Each transition then describes the unitary step of the species
+    /// from its current level: the result level is the current level, or one level up or
+    /// down. This is equivalent to the original model under the (multi-valued) asynchronous
+    /// semantics.
+    ///
+    /// Species whose Boolean variables all keep their value are written as constant species
+    /// (without a transition). The position of a species in the optional `layout` is taken
+    /// from its first Boolean variable.
+    ///
+    /// Returns an error if the `mapping` does not match the network, if some update function
+    /// is missing, or if the network is parametrised (uninterpreted functions have no
+    /// representation in SBML-qual).
+    pub fn to_multivalued_sbml(
+        &self,
+        mapping: &Booleanization,
+        layout: Option<&Layout>,
+    ) -> Result<String, String> {
+        let names = self
+            .variables()
+            .map(|it| self.get_variable_name(it).clone())
+            .collect::<Vec<_>>();
+        if names != mapping.boolean_variable_names() {
+            return Err("The mapping does not match the variables of the network.".to_string());
+        }
+        if self.num_parameters() > 0 {
+            return Err("Parametrised network cannot be converted to SBML-qual.".to_string());
+        }
+
+        // Compute the `(result_level, condition)` terms for every species.
+        let mut transitions = Vec::new();
+        for variable in mapping.variables() {
+            if self.is_constant_species(variable) {
+                continue;
+            }
+            let terms = self.multivalued_terms(variable)?;
+            let mut inputs: HashSet<&MultiValuedVariable> = HashSet::new();
+            for (_, condition) in &terms {
+                for arg in condition.collect_arguments() {
+                    inputs.insert(mapping.find_encoded_variable(arg).unwrap());
+                }
+            }
+            let mut inputs = Vec::from_iter(inputs);
+            inputs.sort_by_key(|it| it.get_name());
+            transitions.push((variable, inputs, terms));
+        }
+
+        let mut out = String::new();
+        out.push_str("<?xml version='1.0' encoding='UTF-8' standalone='no'?>");
+        out.push_str("<sbml xmlns=\"http://www.sbml.org/sbml/level3/version1/core\" layout:required=\"false\" level=\"3\" qual:required=\"true\" xmlns:layout=\"http://www.sbml.org/sbml/level3/version1/layout/version1\" version=\"1\" xmlns:qual=\"http://www.sbml.org/sbml/level3/version1/qual/version1\">");
+        out.push_str("<model>");
+        if let Some(layout) = layout {
+            let species_layout: Layout = mapping
+                .variables()
+                .iter()
+                .filter_map(|var| {
+                    let name = self.get_variable_name(var.encoding()[0]);
+                    layout
+                        .get(name)
+                        .map(|position| (var.get_name().to_string(), *position))
+                })
+                .collect();
+            if !species_layout.is_empty() {
+                let mut buffer: Vec<u8> = Vec::new();
+                write_layout(&mut buffer, &species_layout).map_err(|e| e.to_string())?;
+                out.push_str(
+                    String::from_utf8(buffer)
+                        .map_err(|e| e.to_string())?
+                        .as_str(),
+                );
+            }
+        }
+
+        out.push_str("<qual:listOfQualitativeSpecies xmlns:qual=\"http://www.sbml.org/sbml/level3/version1/qual/version1\">");
+        for var in mapping.variables() {
+            let constant = self.is_constant_species(var);
+            out.push_str(&format!("<qual:qualitativeSpecies qual:maxLevel=\"{}\" qual:constant=\"{}\" qual:name=\"{}\" qual:id=\"{}\"/>", var.get_max_level(), constant, var.get_name(), var.get_name()));
+        }
+        out.push_str("</qual:listOfQualitativeSpecies>");
+
+        out.push_str("<qual:listOfTransitions xmlns:qual=\"http://www.sbml.org/sbml/level3/version1/qual/version1\">");
+        for (variable, inputs, terms) in transitions {
+            let name = variable.get_name();
+            out.push_str(&format!("<qual:transition qual:id=\"tr_{}\">", name));
+            out.push_str("<qual:listOfInputs>");
+            for input in inputs {
+                let sign = self.multivalued_sign(input, variable);
+                out.push_str(&format!("<qual:input qual:qualitativeSpecies=\"{}\" qual:transitionEffect=\"none\" qual:sign=\"{}\" qual:id=\"tr_{}_in_{}\"/>", input.get_name(), sign, name, input.get_name()));
+            }
+            out.push_str("</qual:listOfInputs>");
+            out.push_str("<qual:listOfOutputs>");
+            out.push_str(&format!("<qual:output qual:qualitativeSpecies=\"{}\" qual:transitionEffect=\"assignmentLevel\" qual:id=\"tr_{}_out\"/>", name, name));
+            out.push_str("</qual:listOfOutputs>");
+            out.push_str("<qual:listOfFunctionTerms>");
+            out.push_str("<qual:defaultTerm qual:resultLevel=\"0\"/>");
+            for (level, condition) in terms {
+                out.push_str(&format!(
+                    "<qual:functionTerm qual:resultLevel=\"{}\">",
+                    level
+                ));
+                out.push_str("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">");
+                self.write_level_condition(&mut out, mapping, &condition);
+                out.push_str("</math>");
+                out.push_str("</qual:functionTerm>");
+            }
+            out.push_str("</qual:listOfFunctionTerms>");
+            out.push_str("</qual:transition>");
+        }
+        out.push_str("</qual:listOfTransitions>");
+        out.push_str("</model>");
+        out.push_str("</sbml>");
+        Ok(out)
+    }
+
+    /// **(internal)** A species is constant if each of its Boolean variables keeps its value
+    /// in every admissible state.
+    fn is_constant_species(&self, variable: &MultiValuedVariable) -> bool {
+        variable.encoding().iter().enumerate().all(|(i, var)| {
+            let Some(function) = self.get_update_function(*var) else {
+                return false;
+            };
+            (0..=variable.get_max_level()).all(|level| {
+                let expected = FnUpdate::Const((i as u32) < level);
+                fn_at_level(variable, function, level) == expected
+            })
+        })
+    }
+
+    /// **(internal)** Compute the multi-valued function terms of the given `variable`. The
+    /// terms are mutually exclusive and cover all admissible states where the target level
+    /// is not `0`.
+    fn multivalued_terms(
+        &self,
+        variable: &MultiValuedVariable,
+    ) -> Result<Vec<(u32, FnUpdate)>, String> {
+        let max_level = variable.get_max_level();
+        let mut functions = Vec::new();
+        for var in variable.encoding() {
+            let function = self.get_update_function(*var).as_ref().ok_or_else(|| {
+                format!(
+                    "Variable `{}` has no update function and cannot be exported.",
+                    self.get_variable_name(*var)
+                )
+            })?;
+            functions.push(function);
+        }
+        let at_level = |function: &FnUpdate, level: u32| fn_at_level(variable, function, level);
+        let is_level = |level: u32| {
+            variable
+                .mk_level_geq(level)
+                .and(variable.mk_level_geq(level + 1).negation())
+        };
+        // For each level, compute the condition for increasing and decreasing the level.
+        let up = (0..=max_level)
+            .map(|j| {
+                if j < max_level {
+                    at_level(functions[j as usize], j)
+                } else {
+                    FnUpdate::Const(false)
+                }
+            })
+            .collect::<Vec<_>>();
+        let down = (0..=max_level)
+            .map(|j| {
+                if j > 0 {
+                    at_level(functions[(j - 1) as usize], j).negation()
+                } else {
+                    FnUpdate::Const(false)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut terms = Vec::new();
+        for r in 1..=max_level {
+            let mut clauses = vec![is_level(r - 1).and(up[(r - 1) as usize].clone())];
+            clauses.push(
+                is_level(r).and(
+                    up[r as usize]
+                        .clone()
+                        .or(down[r as usize].clone())
+                        .negation(),
+                ),
+            );
+            if r < max_level {
+                let j = (r + 1) as usize;
+                clauses.push(is_level(r + 1).and(down[j].clone().and(up[j].clone().negation())));
+            }
+            let condition = FnUpdate::mk_nary_disjunction(&clauses).simplify_constants();
+            if condition != FnUpdate::Const(false) {
+                terms.push((r, condition));
+            }
+        }
+        Ok(terms)
+    }
+
+    /// **(internal)** The SBML sign of the influence of `regulator` on `target`, based on
+    /// the regulations between their Boolean variables.
+    fn multivalued_sign(
+        &self,
+        regulator: &MultiValuedVariable,
+        target: &MultiValuedVariable,
+    ) -> &'static str {
+        let mut monotonicity: HashSet<Option<Monotonicity>> = HashSet::new();
+        for r in regulator.encoding() {
+            for t in target.encoding() {
+                if let Some(regulation) = self.as_graph().find_regulation(*r, *t) {
+                    monotonicity.insert(regulation.get_monotonicity());
+                }
+            }
+        }
+        let monotonicity = Vec::from_iter(monotonicity);
+        match monotonicity.as_slice() {
+            [Some(Monotonicity::Activation)] => "positive",
+            [Some(Monotonicity::Inhibition)] => "negative",
+            _ => "unknown",
+        }
+    }
+
+    /// **(internal)** Write a condition over Booleanized variables as MathML, where each
+    /// Boolean variable is translated to a comparison of the species level.
+    fn write_level_condition(&self, out: &mut String, mapping: &Booleanization, f: &FnUpdate) {
+        match f {
+            FnUpdate::Const(true) => out.push_str("<true/>"),
+            FnUpdate::Const(false) => out.push_str("<false/>"),
+            FnUpdate::Var(id) => {
+                let variable = mapping.find_encoded_variable(*id).unwrap();
+                let level = level_of_encoding(variable, *id);
+                out.push_str(&format!(
+                    "<apply><geq/><ci>{}</ci><cn type=\"integer\">{}</cn></apply>",
+                    variable.get_name(),
+                    level
+                ));
+            }
+            FnUpdate::Not(inner) => {
+                out.push_str("<apply><not/>");
+                self.write_level_condition(out, mapping, inner);
+                out.push_str("</apply>");
+            }
+            FnUpdate::Binary(op, l, r) => {
+                let op = match op {
+                    BinaryOp::Imp => "implies",
+                    BinaryOp::And => "and",
+                    BinaryOp::Or => "or",
+                    BinaryOp::Xor => "xor",
+                    BinaryOp::Iff => "eq",
+                };
+                out.push_str(&format!("<apply><{}/>", op));
+                self.write_level_condition(out, mapping, l);
+                self.write_level_condition(out, mapping, r);
+                out.push_str("</apply>");
+            }
+            FnUpdate::Threshold(_, _) => {
+                self.write_level_condition(out, mapping, &f.expand_thresholds())
+            }
+            FnUpdate::Nary(op, args) => {
+                let op = match op {
+                    NaryOp::And => "and",
+                    NaryOp::Or => "or",
+                };
+                out.push_str(&format!("<apply><{}/>", op));
+                for arg in args {
+                    self.write_level_condition(out, mapping, arg);
+                }
+                out.push_str("</apply>");
+            }
+            FnUpdate::Param(_, _) => {
+                unreachable!("Parametrised networks are not exported to SBML-qual.")
+            }
+        }
+    }
+}
+
+/// **(internal)** Fix the Boolean variables of `variable` in `function` to match
+/// the given `level`.
+fn fn_at_level(variable: &MultiValuedVariable, function: &FnUpdate, level: u32) -> FnUpdate {
+    let mut result = function.clone();
+    for (i, var) in variable.encoding().iter().enumerate() {
+        let value = FnUpdate::Const((i as u32) < level);
+        result = result.substitute_variable(*var, &value);
+    }
+    result.simplify_constants()
+}
+
+/// **(internal)** The level `k` such that the Boolean `variable` encodes `x >= k`.
+fn level_of_encoding(variable: &MultiValuedVariable, id: VariableId) -> usize {
+    variable.encoding().iter().position(|it| *it == id).unwrap() + 1
+}
+
 fn write_layout(out: &mut dyn Write, layout: &HashMap<String, (f64, f64)>) -> Result<(), Error> {
     write!(out, "<layout:listOfLayouts xmlns:layout=\"http://www.sbml.org/sbml/level3/version1/layout/version1\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">")?;
     write!(out, "<layout:layout layout:id=\"__layout__\">")?;
@@ -168,6 +480,8 @@ fn write_layout(out: &mut dyn Write, layout: &HashMap<String, (f64, f64)>) -> Re
 
 #[cfg(test)]
 mod tests {
+    use crate::biodivine_std::bitvector::BitVector;
+    use crate::multi_valued::Booleanization;
     use crate::BooleanNetwork;
     use pretty_assertions::assert_eq;
     use std::collections::HashMap;
@@ -204,4 +518,78 @@ mod tests {
         assert_eq!(model, actual);
         assert_eq!(expected_layout, layout);
     }
+
+    #[test]
+    fn test_nary_sbml_export() {
+        let model = BooleanNetwork::try_from(
+            "
+            a -> c
+            b -> c
+            c -| c
+            $c: (a & b & !c) | (!a & !b & c) | (a & c)
+        ",
+        )
+        .unwrap();
+        let c = model.as_graph().find_variable("c").unwrap();
+        let function = model.get_update_function(c).as_ref().unwrap().flatten();
+        let mut model = model;
+        model
+            .set_update_function(c, Some(function.clone()))
+            .unwrap();
+        let sbml = model.to_sbml(None);
+        assert!(sbml.contains("<apply><or/><apply><and/>"));
+        // The import represents n-ary operators as chains of binary operators.
+        let (actual, _) = BooleanNetwork::try_from_sbml(&sbml).unwrap();
+        let actual = actual.get_update_function(c).as_ref().unwrap().flatten();
+        assert_eq!(function, actual);
+    }
+
+    #[test]
+    fn test_multivalued_sbml_export() {
+        let (network, _, mapping) =
+            BooleanNetwork::try_from_multivalued_sbml(crate::sbml::import::tests::MULTIVALUED_SBML)
+                .unwrap();
+        let mut layout = HashMap::new();
+        layout.insert("B_b1".to_string(), (1.0, 2.0));
+        let sbml = network
+            .to_multivalued_sbml(&mapping, Some(&layout))
+            .unwrap();
+        // The input species `A` is constant and has no transition.
+        assert!(sbml.contains("qual:constant=\"true\" qual:name=\"A\""));
+        assert!(sbml.contains("qual:constant=\"false\" qual:name=\"B\""));
+        assert!(!sbml.contains("tr_A"));
+        let (actual, actual_layout, actual_mapping) =
+            BooleanNetwork::try_from_multivalued_sbml(&sbml).unwrap();
+        assert_eq!(mapping, actual_mapping);
+        assert!(BooleanNetwork::try_from_sbml(&sbml).is_err());
+        assert_eq!(actual_layout["B_b1"], (1.0, 2.0));
+        assert_eq!(actual_layout["B_b2"], (1.0, 2.0));
+
+        // The exported model has the same dynamics in all admissible states.
+        for a in 0..=2 {
+            for b in 0..=2 {
+                for c in 0..=1 {
+                    let state = mapping.encode_state(&[a, b, c]);
+                    let valuation: HashMap<_, _> = network
+                        .variables()
+                        .map(|v| (v, state.get(v.to_index())))
+                        .collect();
+                    for v in network.variables() {
+                        let f1 = network.get_update_function(v).as_ref().unwrap();
+                        let f2 = actual.get_update_function(v).as_ref().unwrap();
+                        assert_eq!(f1.evaluate(&valuation), f2.evaluate(&valuation));
+                    }
+                }
+            }
+        }
+
+        // The mapping must match the network.
+        let other = BooleanNetwork::try_from("a -> b").unwrap();
+        assert!(other.to_multivalued_sbml(&mapping, None).is_err());
+
+        // Uninterpreted functions cannot be exported.
+        let other = BooleanNetwork::try_from("a -> b\n a -> a\n $b: f(a)\n $a: a").unwrap();
+        let mapping = Booleanization::new(&[("a".to_string(), 1), ("b".to_string(), 1)]);
+        assert!(other.to_multivalued_sbml(&mapping, None).is_err());
+    }
 }
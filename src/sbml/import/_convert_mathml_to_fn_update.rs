@@ -1,6 +1,7 @@
+use crate::multi_valued::{Booleanization, MultiValuedVariable};
 use crate::sbml::import::_read_mathml::MathMl;
-use crate::sbml::import::_read_transitions::SbmlTransition;
-use crate::{BinaryOp, BooleanNetwork, FnUpdate};
+use crate::sbml::import::_read_transitions::{SbmlTransition, SbmlTransitionInput};
+use crate::{BinaryOp, BooleanNetwork, FnUpdate, NaryOp};
 use std::collections::HashMap;
 
 /// Convert a transition into an update function.
@@ -12,128 +13,12 @@ pub fn sbml_transition_to_update_function(
     transition: &SbmlTransition,
     id_to_var: &HashMap<String, String>,
 ) -> Result<FnUpdate, String> {
-    // Recursive procedure to convert a MathML object to FnUpdate.
-    fn math_to_update(
-        math: &MathMl,
-        network: &BooleanNetwork,
-        transition: &SbmlTransition,
-        id_to_var: &HashMap<String, String>,
-    ) -> Result<FnUpdate, String> {
-        match math {
-            MathMl::Boolean(v) => Ok(FnUpdate::Const(*v)),
-            MathMl::Integer(i) => {
-                if *i == 0 {
-                    Ok(FnUpdate::Const(false))
-                } else if *i == 1 {
-                    Ok(FnUpdate::Const(true))
-                } else {
-                    Err(format!("Cannot convert integer `{}` to Boolean.", i))
-                }
-            }
-            MathMl::Identifier(name) => {
-                let input = transition
-                    .inputs
-                    .iter()
-                    .find(|i| i.id.as_ref().map(|id| id == name).unwrap_or(false))
-                    .map(|i| i.qual_species.clone())
-                    .or_else(|| id_to_var.get(name).cloned())
-                    .and_then(|name| network.graph.find_variable(&name));
-
-                if let Some(var) = input {
-                    Ok(FnUpdate::Var(var))
-                } else {
-                    Err(format!(
-                        "Identifier `{}` in transition `{:?}` is not an input nor a species.",
-                        name, transition.id
-                    ))
-                }
-            }
-            MathMl::SymbolApply(p_name, args) => {
-                let mut fn_args = Vec::new();
-                for arg in args {
-                    let update = math_to_update(arg, network, transition, id_to_var)?;
-                    fn_args.push(update);
-                }
-
-                // This should already be created by parent function.
-                let param = network.find_parameter(p_name).unwrap();
-
-                Ok(FnUpdate::Param(param, fn_args))
-            }
-            MathMl::Apply(op, args) => {
-                match op.as_str() {
-                    "not" => {
-                        if args.len() != 1 {
-                            Err(format!(
-                                "Negation operator needs exactly one argument, {} given.",
-                                args.len()
-                            ))
-                        } else {
-                            let arg = math_to_update(&args[0], network, transition, id_to_var)?;
-                            Ok(FnUpdate::Not(Box::new(arg)))
-                        }
-                    }
-                    "eq" | "neq" | "geq" | "leq" | "lt" | "gt" => {
-                        // These are strictly binary.
-                        if args.len() != 2 {
-                            Err(format!(
-                                "Operation `{}` requires exactly 2 arguments, {} given.",
-                                op,
-                                args.len()
-                            ))
-                        } else {
-                            let left = math_to_update(&args[0], network, transition, id_to_var)?;
-                            let right = math_to_update(&args[1], network, transition, id_to_var)?;
-                            Ok(transform_comparison(op, left, right))
-                        }
-                    }
-                    "implies" | "xor" => {
-                        // These are also strictly binary, bu don't have special handling
-                        if args.len() != 2 {
-                            Err(format!(
-                                "Operation `{}` requires exactly 2 arguments, {} given.",
-                                op,
-                                args.len()
-                            ))
-                        } else {
-                            let left = math_to_update(&args[0], network, transition, id_to_var)?;
-                            let right = math_to_update(&args[1], network, transition, id_to_var)?;
-                            Ok(FnUpdate::Binary(
-                                if op == "implies" {
-                                    BinaryOp::Imp
-                                } else {
-                                    BinaryOp::Xor
-                                },
-                                Box::new(left),
-                                Box::new(right),
-                            ))
-                        }
-                    }
-                    "and" | "or" => {
-                        // And/Or support variable arguments because some CNF/DNF editors will output like this
-                        let is_and = op == "and";
-                        let op = if is_and { BinaryOp::And } else { BinaryOp::Or };
-                        if args.is_empty() {
-                            Ok(FnUpdate::Const(!is_and))
-                        } else if args.len() == 1 {
-                            math_to_update(&args[0], network, transition, id_to_var)
-                        } else {
-                            let mut fns = Vec::new();
-                            for arg in args {
-                                fns.push(math_to_update(arg, network, transition, id_to_var)?);
-                            }
-                            let fst = fns[0].clone();
-                            Ok(fns
-                                .into_iter()
-                                .skip(1)
-                                .fold(fst, |l, r| FnUpdate::Binary(op, Box::new(l), Box::new(r))))
-                        }
-                    }
-                    _ => Err(format!("Unknown MathML operator `{}`.", op)),
-                }
-            }
-        }
-    }
+    let context = TransitionContext {
+        network,
+        transition,
+        id_to_var,
+        mapping: None,
+    };
 
     if transition.default_term.is_none() {
         panic!("Converting an unspecified transition to FnUpdate.");
@@ -161,11 +46,307 @@ pub fn sbml_transition_to_update_function(
         } else if term.math.is_none() {
             Err("Function term has no math formula.".to_string())
         } else {
-            math_to_update(term.math.as_ref().unwrap(), network, transition, id_to_var)
+            math_to_update(term.math.as_ref().unwrap(), &context)
         }
     };
 }
 
+/// Convert a transition of a multi-valued model into a list of `(result_level, condition)`
+/// terms and a default level. The conditions are expressed using the Boolean variables of
+/// the given `mapping` (i.e. `network` must be a Booleanized network).
+///
+/// In the formulas, a species identifier refers to the level of the species. An input
+/// identifier refers to the threshold level of the input when it is declared, or to
+/// the level of its species otherwise. When a species is used as a Boolean value, it is
+/// true when its level is at least `1` (or the input threshold).
+pub fn sbml_transition_to_level_terms(
+    mapping: &Booleanization,
+    network: &BooleanNetwork,
+    transition: &SbmlTransition,
+    id_to_var: &HashMap<String, String>,
+) -> Result<(Vec<(u32, FnUpdate)>, u32), String> {
+    let context = TransitionContext {
+        network,
+        transition,
+        id_to_var,
+        mapping: Some(mapping),
+    };
+    let default_term = transition
+        .default_term
+        .as_ref()
+        .ok_or_else(|| format!("Transition `{:?}` has no default term.", transition.id))?;
+    let mut terms = Vec::new();
+    for term in &transition.function_terms {
+        let math = term
+            .math
+            .as_ref()
+            .ok_or_else(|| "Function term has no math formula.".to_string())?;
+        let condition = math_to_update(math, &context)?;
+        terms.push((term.result_level, condition.simplify_constants()));
+    }
+    Ok((terms, default_term.result_level))
+}
+
+/// **(internal)** The data necessary to convert the formulas of a single transition.
+struct TransitionContext<'a> {
+    network: &'a BooleanNetwork,
+    transition: &'a SbmlTransition,
+    id_to_var: &'a HashMap<String, String>,
+    /// The Booleanization of the species levels, if the model is multi-valued.
+    mapping: Option<&'a Booleanization>,
+}
+
+/// **(internal)** An operand of a multi-valued comparison: either a level of a species,
+/// or a constant.
+enum LevelOperand<'a> {
+    Level(&'a MultiValuedVariable),
+    Constant(i64),
+}
+
+impl<'a> TransitionContext<'a> {
+    /// Find the transition input with the given `id`.
+    fn find_input(&self, id: &str) -> Option<&'a SbmlTransitionInput> {
+        self.transition
+            .inputs
+            .iter()
+            .find(|i| i.id.as_ref().map(|it| it == id).unwrap_or(false))
+    }
+
+    /// Find the multi-valued variable of a species or input identifier.
+    fn find_level_variable(&self, name: &str) -> Option<&'a MultiValuedVariable> {
+        let species = self
+            .find_input(name)
+            .map(|i| i.qual_species.as_str())
+            .unwrap_or(name);
+        self.id_to_var
+            .get(species)
+            .and_then(|name| self.mapping?.find_variable(name))
+    }
+
+    /// Resolve an operand of a multi-valued comparison.
+    fn math_to_operand(&self, math: &MathMl) -> Result<LevelOperand<'a>, String> {
+        match math {
+            MathMl::Integer(i) => Ok(LevelOperand::Constant(*i)),
+            MathMl::Boolean(b) => Ok(LevelOperand::Constant(i64::from(*b))),
+            MathMl::Identifier(name) => {
+                let threshold = self.find_input(name).and_then(|i| i.threshold_level);
+                if let Some(threshold) = threshold {
+                    Ok(LevelOperand::Constant(i64::from(threshold)))
+                } else if let Some(var) = self.find_level_variable(name) {
+                    Ok(LevelOperand::Level(var))
+                } else {
+                    Err(format!(
+                        "Identifier `{}` in transition `{:?}` is not an input nor a species.",
+                        name, self.transition.id
+                    ))
+                }
+            }
+            _ => Err(format!(
+                "Only species levels and integers can be compared (transition `{:?}`).",
+                self.transition.id
+            )),
+        }
+    }
+}
+
+/// **(internal)** Recursive procedure to convert a MathML object to FnUpdate.
+///
+/// In a multi-valued model (i.e. when the `context` has a `mapping`), identifiers and
+/// comparisons refer to the levels of the species. Otherwise, all species are Boolean.
+fn math_to_update(math: &MathMl, context: &TransitionContext) -> Result<FnUpdate, String> {
+    let transition = context.transition;
+    match math {
+        MathMl::Boolean(v) => Ok(FnUpdate::Const(*v)),
+        MathMl::Integer(i) => {
+            if *i == 0 {
+                Ok(FnUpdate::Const(false))
+            } else if *i == 1 || context.mapping.is_some() {
+                Ok(FnUpdate::Const(true))
+            } else {
+                Err(format!("Cannot convert integer `{}` to Boolean.", i))
+            }
+        }
+        MathMl::Identifier(name) => {
+            if context.mapping.is_some() {
+                let threshold = context
+                    .find_input(name)
+                    .and_then(|i| i.threshold_level)
+                    .unwrap_or(1);
+                if let Some(var) = context.find_level_variable(name) {
+                    return Ok(var.mk_level_geq(threshold));
+                }
+            } else {
+                let input = context
+                    .find_input(name)
+                    .map(|i| i.qual_species.clone())
+                    .or_else(|| context.id_to_var.get(name).cloned())
+                    .and_then(|name| context.network.graph.find_variable(&name));
+                if let Some(var) = input {
+                    return Ok(FnUpdate::Var(var));
+                }
+            }
+            Err(format!(
+                "Identifier `{}` in transition `{:?}` is not an input nor a species.",
+                name, transition.id
+            ))
+        }
+        MathMl::SymbolApply(p_name, args) => {
+            let mut fn_args = Vec::new();
+            for arg in args {
+                let update = math_to_update(arg, context)?;
+                fn_args.push(update);
+            }
+
+            // This should already be created by parent function.
+            let param = context.network.find_parameter(p_name).unwrap();
+
+            Ok(FnUpdate::Param(param, fn_args))
+        }
+        MathMl::Apply(op, args) => {
+            match op.as_str() {
+                "not" => {
+                    if args.len() != 1 {
+                        Err(format!(
+                            "Negation operator needs exactly one argument, {} given.",
+                            args.len()
+                        ))
+                    } else {
+                        let arg = math_to_update(&args[0], context)?;
+                        Ok(FnUpdate::Not(Box::new(arg)))
+                    }
+                }
+                "eq" | "neq" | "geq" | "leq" | "lt" | "gt" => {
+                    // These are strictly binary.
+                    if args.len() != 2 {
+                        return Err(format!(
+                            "Operation `{}` requires exactly 2 arguments, {} given.",
+                            op,
+                            args.len()
+                        ));
+                    }
+                    let is_formula =
+                        |m: &MathMl| matches!(m, MathMl::Apply(_, _) | MathMl::SymbolApply(_, _));
+                    if context.mapping.is_some() && !is_formula(&args[0]) && !is_formula(&args[1]) {
+                        // Comparison of species levels.
+                        let left = context.math_to_operand(&args[0])?;
+                        let right = context.math_to_operand(&args[1])?;
+                        transform_level_comparison(op, left, right).ok_or_else(|| {
+                            format!(
+                                "Comparison of two species levels is not supported (transition `{:?}`).",
+                                transition.id
+                            )
+                        })
+                    } else {
+                        let left = math_to_update(&args[0], context)?;
+                        let right = math_to_update(&args[1], context)?;
+                        Ok(transform_comparison(op, left, right))
+                    }
+                }
+                "implies" | "xor" => {
+                    // These are also strictly binary, bu don't have special handling
+                    if args.len() != 2 {
+                        Err(format!(
+                            "Operation `{}` requires exactly 2 arguments, {} given.",
+                            op,
+                            args.len()
+                        ))
+                    } else {
+                        let left = math_to_update(&args[0], context)?;
+                        let right = math_to_update(&args[1], context)?;
+                        Ok(FnUpdate::Binary(
+                            if op == "implies" {
+                                BinaryOp::Imp
+                            } else {
+                                BinaryOp::Xor
+                            },
+                            Box::new(left),
+                            Box::new(right),
+                        ))
+                    }
+                }
+                "and" | "or" => {
+                    // And/Or support variable arguments because some CNF/DNF editors will output like this
+                    // (three or more arguments are kept as a flat n-ary operator).
+                    let is_and = op == "and";
+                    let op = if is_and { NaryOp::And } else { NaryOp::Or };
+                    if args.is_empty() {
+                        Ok(FnUpdate::Const(!is_and))
+                    } else if args.len() == 1 {
+                        math_to_update(&args[0], context)
+                    } else {
+                        let mut fns = Vec::new();
+                        for arg in args {
+                            fns.push(math_to_update(arg, context)?);
+                        }
+                        Ok(if fns.len() == 2 {
+                            let right = fns.pop().unwrap();
+                            let left = fns.pop().unwrap();
+                            FnUpdate::mk_binary(op.to_binary(), left, right)
+                        } else {
+                            FnUpdate::Nary(op, fns)
+                        })
+                    }
+                }
+                _ => Err(format!("Unknown MathML operator `{}`.", op)),
+            }
+        }
+    }
+}
+
+/// **(internal)** Utility function for turning a comparison of a species level and a constant
+/// into a condition on the Booleanized variables. Returns `None` if both operands are levels.
+///
+/// Allowed `op` values are `eq`, `neq`, `geq`, `leq`, `lt`, `gt`.
+fn transform_level_comparison(
+    op: &str,
+    left: LevelOperand,
+    right: LevelOperand,
+) -> Option<FnUpdate> {
+    // Normalize the constant to the right side.
+    let (op, var, constant) = match (left, right) {
+        (LevelOperand::Constant(a), LevelOperand::Constant(b)) => {
+            return Some(FnUpdate::Const(match op {
+                "eq" => a == b,
+                "neq" => a != b,
+                "geq" => a >= b,
+                "leq" => a <= b,
+                "lt" => a < b,
+                "gt" => a > b,
+                _ => panic!("Unsupported comparison {}.", op),
+            }));
+        }
+        (LevelOperand::Level(_), LevelOperand::Level(_)) => return None,
+        (LevelOperand::Level(var), LevelOperand::Constant(c)) => (op, var, c),
+        (LevelOperand::Constant(c), LevelOperand::Level(var)) => {
+            let op = match op {
+                "geq" => "leq",
+                "leq" => "geq",
+                "lt" => "gt",
+                "gt" => "lt",
+                op => op,
+            };
+            (op, var, c)
+        }
+    };
+    // Levels `x >= k` for `k` out of the `0..=max_level` range are constants.
+    let geq = |k: i64| {
+        if k <= 0 {
+            FnUpdate::Const(true)
+        } else {
+            var.mk_level_geq(u32::try_from(k).unwrap_or(u32::MAX))
+        }
+    };
+    Some(match op {
+        "geq" => geq(constant),
+        "gt" => geq(constant + 1),
+        "leq" => geq(constant + 1).negation(),
+        "lt" => geq(constant).negation(),
+        "eq" => geq(constant).and(geq(constant + 1).negation()),
+        "neq" => geq(constant).and(geq(constant + 1).negation()).negation(),
+        _ => panic!("Unsupported comparison {}.", op),
+    })
+}
+
 /// **(internal)** Utility function for turning comparisons into valid `FnUpdate` functions.
 ///
 /// Allowed `op` values are `eq`, `neq`, `geq`, `leq`, `lt`, `gt`.
@@ -10,6 +10,7 @@ pub struct SbmlTransitionInput {
     pub transition_effect: Option<String>,
     pub sign: Option<String>,
     pub essential: Option<bool>,
+    pub threshold_level: Option<u32>,
 }
 
 /// Maps almost directly to the SBML transition output tag.
@@ -130,6 +131,19 @@ fn read_transition_input(
     // WARNING: This attribute is not a part of the SBML-qual specification. We thus do not use
     // the SBML-qual namespace for it.
     let essential = input.attribute("essential");
+    let threshold_level = input.attribute((SBML_QUAL, "thresholdLevel"));
+    let threshold_level = if let Some(threshold_level) = threshold_level {
+        let value = threshold_level.parse::<u32>();
+        if value.is_err() {
+            return Err(format!(
+                "Invalid threshold level in transition {:?}. {} given.",
+                transition_id, threshold_level
+            ));
+        }
+        value.ok()
+    } else {
+        None
+    };
     if species.is_none() {
         return Err(format!(
             "Transition {:?} is missing an input species.",
@@ -143,6 +157,7 @@ fn read_transition_input(
         transition_effect: effect.map(|s| s.to_string()),
         sign: sign.map(|s| s.to_string()),
         essential: essential.map(|s| s == "true"),
+        threshold_level,
     })
 }
 
@@ -1,4 +1,7 @@
-use crate::sbml::import::_convert_mathml_to_fn_update::sbml_transition_to_update_function;
+use crate::multi_valued::{Booleanization, MultiValuedVariable};
+use crate::sbml::import::_convert_mathml_to_fn_update::{
+    sbml_transition_to_level_terms, sbml_transition_to_update_function,
+};
 use crate::sbml::import::_read_layout::read_sbml_layout;
 use crate::sbml::import::_read_mathml::MathMl;
 use crate::sbml::import::_read_species::{read_species, SbmlSpecie};
@@ -26,6 +29,9 @@ impl BooleanNetwork {
     ///
     /// Also reads `Layout` information from the file. If there is no layout, an empty map is
     /// returned.
+    ///
+    /// Models with multi-valued species (`maxLevel > 1`) are rejected. Use
+    /// [BooleanNetwork::try_from_multivalued_sbml] to read them in a Booleanized form.
     pub fn try_from_sbml(model_file: &str) -> Result<(BooleanNetwork, Layout), String> {
         BooleanNetwork::try_from_sbml_strict(model_file, &mut Vec::new())
     }
@@ -38,6 +44,40 @@ impl BooleanNetwork {
         model_file: &str,
         warnings: &mut Vec<String>,
     ) -> Result<(BooleanNetwork, Layout), String> {
+        let (network, layout, _) = BooleanNetwork::read_sbml_model(model_file, false, warnings)?;
+        Ok((network, layout))
+    }
+
+    /// The same as `try_from_sbml`, but also returns the [Booleanization] of the model species.
+    ///
+    /// Species with `maxLevel > 1` are Booleanized using the Van Ham encoding (see
+    /// [crate::multi_valued]), and the returned [Booleanization] maps them back to the original
+    /// levels. For a Boolean model, every species is mapped to the variable of the same name.
+    pub fn try_from_multivalued_sbml(
+        model_file: &str,
+    ) -> Result<(BooleanNetwork, Layout, Booleanization), String> {
+        let (network, layout, mapping) =
+            BooleanNetwork::read_sbml_model(model_file, true, &mut Vec::new())?;
+        let mapping = mapping.unwrap_or_else(|| {
+            let variables = network
+                .variables()
+                .map(|it| (network.get_variable_name(it).clone(), 1))
+                .collect::<Vec<_>>();
+            Booleanization::new(&variables)
+        });
+        Ok((network, layout, mapping))
+    }
+
+    /// **(internal)** Read a `BooleanNetwork` and its `Layout` from an SBML string.
+    ///
+    /// If some species have `maxLevel > 1` and `allow_multivalued` is set, the model is
+    /// Booleanized and the [Booleanization] is returned as well (see [read_multivalued_model]).
+    /// Otherwise, such model is rejected.
+    fn read_sbml_model(
+        model_file: &str,
+        allow_multivalued: bool,
+        warnings: &mut Vec<String>,
+    ) -> Result<(BooleanNetwork, Layout, Option<Booleanization>), String> {
         let document =
             roxmltree::Document::parse(model_file).map_err(|e| format!("XML Error: {:?}", e))?;
         let root = document.root();
@@ -70,14 +110,21 @@ impl BooleanNetwork {
         let species = read_species(model)?;
         let transitions = read_transitions(model)?;
 
-        for specie in &species {
-            if specie.max_level.is_some() && specie.max_level.unwrap() != 1 {
+        if species.iter().any(|it| it.max_level.unwrap_or(1) != 1) {
+            if !allow_multivalued {
+                let specie = species
+                    .iter()
+                    .find(|it| it.max_level.unwrap_or(1) != 1)
+                    .unwrap();
                 return Err(format!(
-                    "Specie with ID {} is not Boolean (max level {}).",
+                    "Specie with ID {} is not Boolean (max level {}). Use `try_from_multivalued_sbml` to Booleanize the model.",
                     specie.id,
                     specie.max_level.unwrap()
                 ));
             }
+            let (network, layout, mapping) =
+                read_multivalued_model(model, &species, &transitions, warnings)?;
+            return Ok((network, layout, Some(mapping)));
         }
 
         let specie_to_name = create_normalized_names(&species, warnings)?;
@@ -133,8 +180,110 @@ impl BooleanNetwork {
             }
         }
 
-        Ok((boolean_network, transformed_layout))
+        Ok((boolean_network, transformed_layout, None))
+    }
+}
+
+/// **(internal)** Build a Booleanized `BooleanNetwork` of a multi-valued model.
+///
+/// The target level of a species is the maximal `resultLevel` of a satisfied function
+/// term, or the level of the default term if no function term is satisfied. Constant species
+/// and species without a transition keep their level. The formulas can compare species levels
+/// with integers, or with threshold levels of transition inputs (`thresholdLevel`). Transitions
+/// without function terms (i.e. fully unspecified transitions) are not supported.
+///
+/// Note that the regulations of the resulting network are inferred from the Booleanized
+/// update functions (see [BooleanNetwork::infer_valid_graph]). The `Layout` assigns the
+/// position of each species to all of its Boolean variables.
+fn read_multivalued_model(
+    model: Node,
+    species: &[SbmlSpecie],
+    transitions: &[SbmlTransition],
+    warnings: &mut Vec<String>,
+) -> Result<(BooleanNetwork, Layout, Booleanization), String> {
+    let specie_to_name = create_normalized_names(species, warnings)?;
+
+    let mut variables = Vec::new();
+    for specie in species {
+        let max_level = specie.max_level.unwrap_or(1);
+        if max_level == 0 {
+            return Err(format!("Specie with ID {} has max level 0.", specie.id));
+        }
+        variables.push((specie_to_name[&specie.id].clone(), max_level));
+    }
+    variables.sort();
+    let mapping = Booleanization::new(&variables);
+
+    let mut template = mapping.mk_template_network();
+    for transition in transitions {
+        for term in &transition.function_terms {
+            if let Some(math) = &term.math {
+                create_explicit_parameters(math, &mut template)?;
+            }
+        }
+    }
+
+    let mut targets = HashMap::new();
+    for transition in transitions {
+        if transition.outputs.len() != 1 {
+            return Err(format!(
+                "Every transition can have only one output. `{:?}` has {}.",
+                transition.id,
+                transition.outputs.len()
+            ));
+        }
+        let output = &transition.outputs[0].qual_species;
+        let output = specie_to_name.get(output).ok_or_else(|| {
+            format!(
+                "Unknown output specie `{}` used in transition `{:?}`.",
+                output, transition.id
+            )
+        })?;
+        if targets.insert(output.clone(), transition).is_some() {
+            return Err(format!("Multiple transitions for specie `{}`.", output));
+        }
+    }
+
+    let mut functions = Vec::new();
+    for specie in species {
+        let name = &specie_to_name[&specie.id];
+        let variable = mapping.find_variable(name).unwrap();
+        let transition = targets.get(name).filter(|_| !specie.is_constant);
+        let updates = if let Some(transition) = transition {
+            let (terms, default) =
+                sbml_transition_to_level_terms(&mapping, &template, transition, &specie_to_name)?;
+            if let Some((level, _)) = terms.iter().find(|(l, _)| *l > variable.get_max_level()) {
+                return Err(format!("Invalid result level {} of `{}`.", level, name));
+            }
+            variable.mk_update_functions(|k| MultiValuedVariable::mk_target_geq(&terms, default, k))
+        } else {
+            variable.mk_update_functions(|k| variable.mk_level_geq(k))
+        };
+        functions.extend(variable.encoding().iter().cloned().zip(updates));
+    }
+
+    let network = mapping.build_network(&template, functions)?;
+
+    let mut layout = Layout::new();
+    match read_sbml_layout(model) {
+        Ok(sbml_layout) => {
+            for (id, position) in sbml_layout.glyphs {
+                let variable = specie_to_name
+                    .get(&id)
+                    .and_then(|name| mapping.find_variable(name));
+                if let Some(variable) = variable {
+                    for var in variable.encoding() {
+                        layout.insert(network.get_variable_name(*var).clone(), position);
+                    }
+                } else {
+                    warnings.push(format!("Unknown layout glyph `{}`.", id));
+                }
+            }
+        }
+        Err(err) => warnings.push(err),
     }
+
+    Ok((network, layout, mapping))
 }
 
 /// **(internal)** Find the given tag in a parent `Node`. Returns error if the tag does
@@ -375,7 +524,8 @@ fn create_explicit_parameters(math: &MathMl, network: &mut BooleanNetwork) -> Re
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
+    use crate::biodivine_std::bitvector::BitVector;
     use crate::{BooleanNetwork, Monotonicity};
     use pretty_assertions::assert_eq;
     use std::collections::HashMap;
@@ -410,10 +560,10 @@ mod tests {
             SciP -| CcrM
             CtrA -> SciP
             DnaA -| SciP
-            $CtrA: ((false | ((((!CtrA & GcrA) & !CcrM) & !SciP) & true)) | ((CtrA & !CcrM) & !SciP))
+            $CtrA: false | (!CtrA & GcrA & !CcrM & !SciP & true) | (CtrA & !CcrM & !SciP)
             $GcrA: (!CtrA & DnaA)
-            $DnaA: (((CtrA & !GcrA) & !DnaA) & CcrM)
-            $CcrM: ((CtrA & !CcrM) & !SciP)
+            $DnaA: CtrA & !GcrA & !DnaA & CcrM
+            $CcrM: CtrA & !CcrM & !SciP
             $SciP: (CtrA & !DnaA)
         ",
         )
@@ -452,10 +602,10 @@ mod tests {
             SciP -| CcrM
             CtrA___ -> SciP
             DnaA -| SciP
-            $CtrA___: ((((!CtrA___ & GcrA) & !CcrM) & !SciP) | ((CtrA___ & !CcrM) & !SciP))
+            $CtrA___: (!CtrA___ & GcrA & !CcrM & !SciP) | (CtrA___ & !CcrM & !SciP)
             $GcrA: (!CtrA___ & DnaA)
-            $DnaA: (((CtrA___ & !GcrA) & !DnaA) & CcrM)
-            $CcrM: ((CtrA___ & !CcrM) & !SciP)
+            $DnaA: CtrA___ & !GcrA & !DnaA & CcrM
+            $CcrM: CtrA___ & !CcrM & !SciP
             $SciP: (CtrA___ & !DnaA)
         ",
         )
@@ -524,6 +674,105 @@ mod tests {
         assert_eq!(layout.len(), 0);
     }
 
+    /// A multi-valued model equivalent to the GINML model used in the GINML import tests.
+    pub const MULTIVALUED_SBML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sbml xmlns="http://www.sbml.org/sbml/level3/version1/core" level="3" version="1" xmlns:qual="http://www.sbml.org/sbml/level3/version1/qual/version1" qual:required="true">
+  <model id="example">
+    <qual:listOfQualitativeSpecies>
+      <qual:qualitativeSpecies qual:id="A" qual:maxLevel="2" qual:constant="true"/>
+      <qual:qualitativeSpecies qual:id="B" qual:maxLevel="2" qual:constant="false"/>
+      <qual:qualitativeSpecies qual:id="C" qual:maxLevel="1" qual:constant="false"/>
+    </qual:listOfQualitativeSpecies>
+    <qual:listOfTransitions>
+      <qual:transition qual:id="tr_B">
+        <qual:listOfInputs>
+          <qual:input qual:id="tr_B_in_A" qual:qualitativeSpecies="A" qual:thresholdLevel="2" qual:transitionEffect="none"/>
+          <qual:input qual:id="tr_B_in_C" qual:qualitativeSpecies="C" qual:transitionEffect="none"/>
+        </qual:listOfInputs>
+        <qual:listOfOutputs>
+          <qual:output qual:qualitativeSpecies="B" qual:transitionEffect="assignmentLevel"/>
+        </qual:listOfOutputs>
+        <qual:listOfFunctionTerms>
+          <qual:defaultTerm qual:resultLevel="0"/>
+          <qual:functionTerm qual:resultLevel="1">
+            <math xmlns="http://www.w3.org/1998/Math/MathML">
+              <apply><and/>
+                <apply><geq/><ci>A</ci><cn type="integer">1</cn></apply>
+                <apply><eq/><cn type="integer">0</cn><ci>C</ci></apply>
+              </apply>
+            </math>
+          </qual:functionTerm>
+          <qual:functionTerm qual:resultLevel="2">
+            <math xmlns="http://www.w3.org/1998/Math/MathML">
+              <apply><and/>
+                <apply><geq/><ci>A</ci><ci>tr_B_in_A</ci></apply>
+                <apply><not/><ci>C</ci></apply>
+              </apply>
+            </math>
+          </qual:functionTerm>
+        </qual:listOfFunctionTerms>
+      </qual:transition>
+      <qual:transition qual:id="tr_C">
+        <qual:listOfInputs>
+          <qual:input qual:id="tr_C_in_B" qual:qualitativeSpecies="B" qual:transitionEffect="none"/>
+        </qual:listOfInputs>
+        <qual:listOfOutputs>
+          <qual:output qual:qualitativeSpecies="C" qual:transitionEffect="assignmentLevel"/>
+        </qual:listOfOutputs>
+        <qual:listOfFunctionTerms>
+          <qual:defaultTerm qual:resultLevel="1"/>
+          <qual:functionTerm qual:resultLevel="0">
+            <math xmlns="http://www.w3.org/1998/Math/MathML">
+              <apply><eq/><ci>B</ci><cn type="integer">2</cn></apply>
+            </math>
+          </qual:functionTerm>
+        </qual:listOfFunctionTerms>
+      </qual:transition>
+    </qual:listOfTransitions>
+  </model>
+</sbml>
+"#;
+
+    #[test]
+    fn test_multivalued_sbml() {
+        let (network, layout, mapping) =
+            BooleanNetwork::try_from_multivalued_sbml(MULTIVALUED_SBML).unwrap();
+        assert!(layout.is_empty());
+        // The plain importer does not Booleanize the model silently.
+        let error = BooleanNetwork::try_from_sbml(MULTIVALUED_SBML).unwrap_err();
+        assert!(error.contains("Specie with ID A is not Boolean (max level 2)"));
+        assert_eq!(
+            mapping.boolean_variable_names(),
+            vec!["A_b1", "A_b2", "B_b1", "B_b2", "C"]
+        );
+
+        // The model has the same semantics as the GINML example.
+        let (ginml, _, ginml_mapping) = BooleanNetwork::try_from_ginml(
+            crate::_impl_boolean_network_from_ginml::tests::GINML_MODEL,
+        )
+        .unwrap();
+        assert_eq!(mapping, ginml_mapping);
+        for v in network.variables() {
+            let f1 = network.get_update_function(v).as_ref().unwrap();
+            let f2 = ginml.get_update_function(v).as_ref().unwrap();
+            for levels in [
+                [0, 0, 0],
+                [2, 0, 0],
+                [2, 1, 0],
+                [1, 2, 0],
+                [0, 2, 1],
+                [0, 1, 0],
+            ] {
+                let state = mapping.encode_state(&levels);
+                let valuation: HashMap<_, _> = network
+                    .variables()
+                    .map(|v| (v, state.get(v.to_index())))
+                    .collect();
+                assert_eq!(f1.evaluate(&valuation), f2.evaluate(&valuation));
+            }
+        }
+    }
+
     // cargo test --package biodivine-lib-param-bn --lib sbml::import::tests::diff_test -- --nocapture
     #[test]
     fn diff_test() {
@@ -566,9 +815,17 @@ mod tests {
                     aeon_model.graph.regulators(v),
                     sbml_model.graph.regulators(v)
                 );
+                // The `.aeon` models use nested binary operators, while SBML uses flat
+                // n-ary operators.
                 assert_eq!(
-                    aeon_model.get_update_function(v),
-                    sbml_model.get_update_function(v)
+                    aeon_model
+                        .get_update_function(v)
+                        .as_ref()
+                        .map(|it| it.flatten()),
+                    sbml_model
+                        .get_update_function(v)
+                        .as_ref()
+                        .map(|it| it.flatten())
                 );
 
                 for reg in aeon_model.graph.regulators(v) {
@@ -1,5 +1,9 @@
 use crate::solver_context::{BnSolver, BnSolverContext, BnSolverModel};
-use crate::{Monotonicity, Space, VariableId};
+use crate::symbolic_async_graph::_impl_function_class_constraint::{
+    function_class_table_size, mk_function_class_rows,
+};
+use crate::{FunctionClass, Monotonicity, Space, VariableId};
+use biodivine_lib_bdd::BddVariableSet;
 use std::ops::Not;
 use z3::ast::{forall_const, Ast, Bool};
 use z3::{FuncDecl, SatResult, Solver};
@@ -97,9 +101,51 @@ impl<'z3> BnSolver<'z3> {
         }
 
         let assertion = positive_update.iff(&negative_update).not();
+        let assertion = self.guard_by_existence(source, target, assertion);
         self.solver.assert(&assertion);
     }
 
+    /// Add an assertion to this solver which states that the `source` variable has no effect
+    /// on the update function of the `target` variable, unless the uncertain regulation
+    /// between the two variables exists.
+    ///
+    /// The function panics if the regulation is not uncertain.
+    pub fn assert_regulation_uncertainty(&self, source: VariableId, target: VariableId) {
+        let exists = self
+            .context
+            .mk_regulation_exists(source, target)
+            .unwrap_or_else(|| panic!("Regulation is not uncertain."));
+        let regulators = self.context.network.regulators(target);
+
+        let mut positive_update = self.context.mk_update_function(target);
+        let mut negative_update = self.context.mk_update_function(target);
+
+        let mut bounds: Vec<Bool> = Vec::new();
+
+        for reg in regulators {
+            let reg_var = self.context.mk_var(reg);
+
+            if reg == source {
+                let one = Bool::from_bool(self.as_z3(), true);
+                let zero = Bool::from_bool(self.as_z3(), false);
+                positive_update = positive_update.substitute(&[(&reg_var, &one)]);
+                negative_update = negative_update.substitute(&[(&reg_var, &zero)]);
+            } else {
+                let fresh = Bool::fresh_const(self.as_z3(), "_e_");
+                positive_update = positive_update.substitute(&[(&reg_var, &fresh)]);
+                negative_update = negative_update.substitute(&[(&reg_var, &fresh)]);
+                bounds.push(fresh);
+            }
+        }
+
+        let bounds: Vec<&dyn Ast> = bounds.iter().map(|it| it as &dyn Ast).collect();
+        let is_missing = positive_update.iff(&negative_update);
+        let is_missing = forall_const(self.as_z3(), &bounds, &[], &is_missing);
+
+        self.solver
+            .assert(&Bool::or(self.as_z3(), &[&exists, &is_missing]));
+    }
+
     /// Add assertion to this solver which states that the `source` variable must be a monotonous
     /// input in the update function of the `target` variable.
     ///
@@ -145,10 +191,25 @@ impl<'z3> BnSolver<'z3> {
         let bounds: Vec<&dyn Ast> = bounds.iter().map(|it| it as &dyn Ast).collect();
 
         let assertion = forall_const(self.as_z3(), &bounds, &[], &assertion);
+        let assertion = self.guard_by_existence(source, target, assertion);
 
         self.solver.assert(&assertion);
     }
 
+    /// **(internal)** If the regulation from `source` to `target` is uncertain, weaken
+    /// the `assertion` such that it only applies when the regulation exists.
+    fn guard_by_existence(
+        &self,
+        source: VariableId,
+        target: VariableId,
+        assertion: Bool<'z3>,
+    ) -> Bool<'z3> {
+        match self.context.mk_regulation_exists(source, target) {
+            Some(exists) => exists.implies(&assertion),
+            None => assertion,
+        }
+    }
+
     /// Add an assertion to this solver that the `i`-th argument of the given Boolean `function`
     /// must be observable.
     ///
@@ -229,12 +290,82 @@ impl<'z3> BnSolver<'z3> {
 
         self.solver.assert(&assertion);
     }
+
+    /// Add an assertion to this solver that the given Boolean `function` must belong to
+    /// the given function `class`.
+    ///
+    /// Returns an error if the class is not supported for the arity of the `function`.
+    /// Panics if the function isn't Boolean.
+    pub fn assert_function_class(
+        &self,
+        function: &FuncDecl<'z3>,
+        class: FunctionClass,
+    ) -> Result<(), String> {
+        let arity = function.arity();
+        let table_size = function_class_table_size(arity, class)?;
+        let variables = BddVariableSet::new_anonymous(table_size);
+        let bdd = mk_function_class_rows(&variables, &variables.variables(), class)?;
+
+        // The value of the function in each row of its truth table.
+        let one = Bool::from_bool(self.as_z3(), true);
+        let zero = Bool::from_bool(self.as_z3(), false);
+        let rows: Vec<Bool<'z3>> = (0..(1usize << arity))
+            .map(|row| {
+                let args: Vec<&dyn Ast> = (0..arity)
+                    .map(|i| {
+                        if (row >> i) & 1 == 1 {
+                            &one as &dyn Ast
+                        } else {
+                            &zero as &dyn Ast
+                        }
+                    })
+                    .collect();
+                function.apply(&args).as_bool().unwrap()
+            })
+            .collect();
+
+        // Bdd nodes are sorted such that children always precede their parents.
+        let mut nodes: Vec<Bool<'z3>> = Vec::with_capacity(bdd.size());
+        for pointer in bdd.pointers() {
+            let node = if pointer.is_terminal() {
+                Bool::from_bool(self.as_z3(), pointer.is_one())
+            } else {
+                let row = &rows[bdd.var_of(pointer).to_index()];
+                let low = &nodes[bdd.low_link_of(pointer).to_index()];
+                let high = &nodes[bdd.high_link_of(pointer).to_index()];
+                row.ite(high, low)
+            };
+            nodes.push(node);
+        }
+
+        self.solver.assert(&nodes[bdd.root_pointer().to_index()]);
+        Ok(())
+    }
+
+    /// Add an assertion to this solver that the given Boolean `function` must evaluate
+    /// to `value` for the given constant `args`.
+    ///
+    /// Panics if the number of arguments does not match the function arity, or the function
+    /// isn't Boolean.
+    pub fn assert_function_value(&self, function: &FuncDecl<'z3>, args: &[bool], value: bool) {
+        assert_eq!(args.len(), function.arity());
+        let args: Vec<Bool<'z3>> = args
+            .iter()
+            .map(|it| Bool::from_bool(self.as_z3(), *it))
+            .collect();
+        let arg_refs: Vec<&dyn Ast> = args.iter().map(|it| it as &dyn Ast).collect();
+        let invoke = function.apply(&arg_refs).as_bool().unwrap();
+        let assertion = if value { invoke } else { invoke.not() };
+        self.solver.assert(&assertion);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::solver_context::BnSolverContext;
-    use crate::{BooleanNetwork, Monotonicity};
+    use crate::symbolic_async_graph::_impl_function_class_constraint::mk_function_class_rows;
+    use crate::{BooleanNetwork, FunctionClass, Monotonicity};
+    use biodivine_lib_bdd::{BddVariableSet, ValuationsOfClauseIterator};
     use z3::SatResult;
 
     #[test]
@@ -350,5 +481,98 @@ mod tests {
         solver.assert_regulation_monotonicity(b, b, Monotonicity::Activation);
         assert_eq!(solver.check(), SatResult::Sat);
         solver.pop();
+
+        // f can be unate with an inhibiting first argument, but a majority function
+        // cannot ignore its first argument (which is the case if it is also activating).
+        solver.push();
+        solver
+            .assert_function_class(f_z3, FunctionClass::Unate)
+            .unwrap();
+        solver.assert_regulation_monotonicity(a, a, Monotonicity::Inhibition);
+        assert_eq!(solver.check(), SatResult::Sat);
+        solver
+            .assert_function_class(f_z3, FunctionClass::Majority)
+            .unwrap();
+        solver.assert_function_monotonicity(f_z3, 0, Monotonicity::Activation);
+        assert_eq!(solver.check(), SatResult::Unsat);
+        solver.pop();
+
+        // Known values are consistent with a positive monotonicity only if the function
+        // does not decrease in the first argument.
+        solver.push();
+        solver.assert_function_value(f_z3, &[false, true], true);
+        solver.assert_function_monotonicity(f_z3, 0, Monotonicity::Activation);
+        assert_eq!(solver.check(), SatResult::Sat);
+        solver.assert_function_value(f_z3, &[true, true], false);
+        assert_eq!(solver.check(), SatResult::Unsat);
+        solver.pop();
+    }
+
+    #[test]
+    pub fn test_solver_function_class_matches_bdd() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -?? a
+            b -?? a
+            c -?? a
+            $a: f(a) & g(a, b) & h(a, b, c)
+        ",
+        )
+        .unwrap();
+
+        let z3 = z3::Context::new(&z3::Config::new());
+        let ctx = BnSolverContext::new(&z3, bn.clone());
+        let solver = ctx.mk_empty_solver();
+
+        // For every function table, the solver admits the table iff the symbolic
+        // class constraint admits it.
+        for name in ["f", "g", "h"] {
+            let function = ctx.get_explicit_parameter_constructor(bn.find_parameter(name).unwrap());
+            let arity = function.arity();
+            let variables = BddVariableSet::new_anonymous(1 << arity);
+            let rows = variables.variables();
+            for class in FunctionClass::all() {
+                let bdd = mk_function_class_rows(&variables, &rows, class).unwrap();
+                for table in ValuationsOfClauseIterator::new_unconstrained(variables.num_vars()) {
+                    solver.push();
+                    solver.assert_function_class(function, class).unwrap();
+                    for (row, var) in rows.iter().enumerate() {
+                        let args = (0..arity).map(|i| (row >> i) & 1 == 1).collect::<Vec<_>>();
+                        solver.assert_function_value(function, &args, table.value(*var));
+                    }
+                    let expected = if bdd.eval_in(&table) {
+                        SatResult::Sat
+                    } else {
+                        SatResult::Unsat
+                    };
+                    assert_eq!(expected, solver.check(), "{} {:?}", class, table);
+                    solver.pop();
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_solver_rejects_unsupported_function_class() {
+        let mut bn = BooleanNetwork::try_from(
+            r"
+            a -?? a
+            $a: f(a, a, a, a, a, a)
+        ",
+        )
+        .unwrap();
+        let f = bn.find_parameter("f").unwrap();
+        bn.add_parameter_class(f, FunctionClass::Unate);
+
+        let z3 = z3::Context::new(&z3::Config::new());
+        let ctx = BnSolverContext::try_new(&z3, bn.clone()).unwrap();
+        let function = ctx.get_explicit_parameter_constructor(f);
+        assert!(ctx
+            .mk_empty_solver()
+            .assert_function_class(function, FunctionClass::Threshold)
+            .is_err());
+
+        bn.add_parameter_class(f, FunctionClass::Threshold);
+        assert!(BnSolverContext::try_new(&z3, bn).is_err());
     }
 }
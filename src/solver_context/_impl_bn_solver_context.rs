@@ -1,5 +1,6 @@
 use crate::solver_context::{BnSolver, BnSolverContext};
-use crate::{BinaryOp, BooleanNetwork, FnUpdate, ParameterId, VariableId};
+use crate::symbolic_async_graph::_impl_function_class_constraint::function_class_table_size;
+use crate::{BinaryOp, BooleanNetwork, FnUpdate, NaryOp, ParameterId, VariableId};
 use crate::{ExtendedBoolean, Space};
 use z3::ast::{Ast, Bool};
 use z3::{FuncDecl, Solver, Sort};
@@ -8,7 +9,41 @@ impl<'z3> BnSolverContext<'z3> {
     /// Wrap a `BooleanNetwork` into a `SolverContext` that is attached to the given Z3
     /// context. `SolverContext` will then create the network variables and parameters in this
     /// Z3 context for future manipulation.
+    ///
+    /// Panics if the network uses a function class that is not supported by the solver
+    /// (see [BnSolverContext::try_new]).
     pub fn new(z3: &'z3 z3::Context, network: BooleanNetwork) -> BnSolverContext<'z3> {
+        Self::try_new(z3, network).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The same as [BnSolverContext::new], but returns an error if the network uses
+    /// a function class that is not supported for the arity of the associated function
+    /// (see [crate::FunctionClass]).
+    pub fn try_new(
+        z3: &'z3 z3::Context,
+        network: BooleanNetwork,
+    ) -> Result<BnSolverContext<'z3>, String> {
+        for param in network.parameters() {
+            let param_data = network.get_parameter(param);
+            let arity = param_data.get_arity() as usize;
+            for class in network.get_parameter_classes(param) {
+                function_class_table_size(arity, class)
+                    .map_err(|e| format!("Invalid class of `{}`: {}", param_data.get_name(), e))?;
+            }
+        }
+        for var in network.implicit_parameters() {
+            let arity = network.regulators(var).len();
+            for class in network.get_implicit_classes(var) {
+                function_class_table_size(arity, class).map_err(|e| {
+                    format!(
+                        "Invalid class of `{}`: {}",
+                        network.get_variable_name(var),
+                        e
+                    )
+                })?;
+            }
+        }
+
         let bool_sort = Sort::bool(z3);
 
         let variable_constructors = network
@@ -52,14 +87,14 @@ impl<'z3> BnSolverContext<'z3> {
             })
             .collect::<Vec<_>>();
 
-        BnSolverContext {
+        Ok(BnSolverContext {
             network,
             z3,
             variable_constructors,
             variable_constants,
             explicit_parameter_constructors,
             implicit_parameter_constructors,
-        }
+        })
     }
 
     /// Create fresh declarations of `n` Boolean variables (zero-arity functions) corresponding
@@ -116,7 +151,8 @@ impl<'z3> BnSolverContext<'z3> {
     }
 
     /// Create a `BnSolver` that already contains all pre-existing constraints on the network's
-    /// behaviour, such as the observability and monotonicity of individual regulations.
+    /// behaviour, such as the observability and monotonicity of individual regulations,
+    /// function classes, or known values of uninterpreted functions.
     ///
     /// Note that the constraints should not influence the network variables in any way, but they
     /// do eliminate invalid uninterpreted function instantiations.
@@ -126,6 +162,9 @@ impl<'z3> BnSolverContext<'z3> {
         for reg in self.network.as_graph().regulations() {
             let source = reg.get_regulator();
             let target = reg.get_target();
+            if reg.is_uncertain() {
+                solver.assert_regulation_uncertainty(source, target);
+            }
             if reg.is_observable() {
                 solver.assert_regulation_observability(source, target);
             }
@@ -134,9 +173,61 @@ impl<'z3> BnSolverContext<'z3> {
             }
         }
 
+        for param in self.network.parameters() {
+            for class in self.network.get_parameter_classes(param) {
+                solver
+                    .assert_function_class(self.get_explicit_parameter_constructor(param), class)
+                    // Function classes are validated when the context is created.
+                    .unwrap_or_else(|e| unreachable!("{}", e));
+            }
+        }
+
+        for var in self.network.implicit_parameters() {
+            for class in self.network.get_implicit_classes(var) {
+                solver
+                    .assert_function_class(self.get_implicit_parameter_constructor(var), class)
+                    // Function classes are validated when the context is created.
+                    .unwrap_or_else(|e| unreachable!("{}", e));
+            }
+        }
+
+        for param in self.network.parameters() {
+            for (args, value) in self.network.get_parameter_values(param) {
+                let function = self.get_explicit_parameter_constructor(param);
+                solver.assert_function_value(function, &args, value);
+            }
+        }
+
+        for var in self.network.implicit_parameters() {
+            for (args, value) in self.network.get_implicit_values(var) {
+                let function = self.get_implicit_parameter_constructor(var);
+                solver.assert_function_value(function, &args, value);
+            }
+        }
+
         solver
     }
 
+    /// Create an AST node which is true when the uncertain regulation from `source` to `target`
+    /// exists. Returns `None` if the regulation is not uncertain (or does not exist).
+    pub fn mk_regulation_exists(
+        &self,
+        source: VariableId,
+        target: VariableId,
+    ) -> Option<Bool<'z3>> {
+        let regulation = self.network.as_graph().find_regulation(source, target)?;
+        if regulation.is_uncertain() {
+            let name = format!(
+                "_exists_{}->{}",
+                self.network.get_variable_name(source),
+                self.network.get_variable_name(target)
+            );
+            Some(Bool::new_const(self.as_z3(), name))
+        } else {
+            None
+        }
+    }
+
     /// Get an existing AST node representing the validity of the given network variable.
     pub fn var(&self, var: VariableId) -> &Bool<'z3> {
         &self.variable_constants[var.to_index()]
@@ -326,6 +417,28 @@ impl<'z3> BnSolverContext<'z3> {
                     BinaryOp::Imp => left.implies(&right),
                 }
             }
+            FnUpdate::Threshold(_, _) => self.translate_update_function(
+                &update.expand_thresholds(),
+                variable_constructors,
+                parameter_constructors,
+            ),
+            FnUpdate::Nary(op, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| {
+                        self.translate_update_function(
+                            it,
+                            variable_constructors,
+                            parameter_constructors,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let args = args.iter().collect::<Vec<_>>();
+                match op {
+                    NaryOp::And => Bool::and(self.z3, &args),
+                    NaryOp::Or => Bool::or(self.z3, &args),
+                }
+            }
         }
     }
 
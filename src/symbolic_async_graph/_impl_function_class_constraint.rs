@@ -0,0 +1,332 @@
+use crate::symbolic_async_graph::{
+    FunctionTable, RegulationConstraint, StaticConstraint, SymbolicContext,
+};
+use crate::FunctionClass::{Majority, NestedCanalizing, Threshold, Unate};
+use crate::{BooleanNetwork, FunctionClass, ParameterInstantiation};
+use biodivine_lib_bdd::{Bdd, BddPartialValuation, BddVariable, BddVariableSet};
+use std::collections::{HashMap, HashSet};
+
+/// **(internal)** The largest weight necessary to represent every positive threshold function
+/// with `k` arguments (index `k`) using non-negative integer weights.
+const MAX_THRESHOLD_WEIGHT: [u32; 6] = [0, 1, 1, 2, 3, 5];
+
+impl RegulationConstraint {
+    /// Compute a BDD representing all instantiations of the uninterpreted function given
+    /// by its `table` which belong to the given function `class`.
+    ///
+    /// The result only depends on the symbolic variables of the `table`. The method fails
+    /// if the `class` is not supported for functions of this arity (see [FunctionClass]).
+    pub fn mk_function_class(
+        ctx: &SymbolicContext,
+        table: &FunctionTable,
+        class: FunctionClass,
+    ) -> Result<Bdd, String> {
+        let mut rows = vec![None; 1 << table.arity];
+        for (args, bdd_var) in table {
+            rows[ParameterInstantiation::row_index(&args)] = Some(bdd_var);
+        }
+        let rows = rows.into_iter().map(|it| it.unwrap()).collect::<Vec<_>>();
+        mk_function_class_rows(ctx.bdd_variable_set(), &rows, class)
+    }
+}
+
+/// **(internal)** Compute the function class constraints of all uninterpreted functions
+/// of the `network`, tagged by the corresponding [StaticConstraint].
+pub(crate) fn mk_function_class_constraints(
+    network: &BooleanNetwork,
+    context: &SymbolicContext,
+) -> Result<Vec<(StaticConstraint, Bdd)>, String> {
+    let mut result = Vec::new();
+    for parameter in network.parameters() {
+        let table = context.get_explicit_function_table(parameter);
+        let name = network.get_parameter(parameter).get_name();
+        for class in network.get_parameter_classes(parameter) {
+            let constraint = RegulationConstraint::mk_function_class(context, table, class)
+                .map_err(|e| format!("Invalid class of `{}`: {}", name, e))?;
+            result.push((
+                StaticConstraint::ParameterClass(parameter, class),
+                constraint,
+            ));
+        }
+    }
+    for variable in network.variables() {
+        if network.get_update_function(variable).is_some() {
+            continue;
+        }
+        let Some(table) = context.get_implicit_function_table(variable) else {
+            continue;
+        };
+        let name = network.get_variable_name(variable);
+        for class in network.get_implicit_classes(variable) {
+            let constraint = RegulationConstraint::mk_function_class(context, table, class)
+                .map_err(|e| format!("Invalid class of `{}`: {}", name, e))?;
+            result.push((StaticConstraint::ImplicitClass(variable, class), constraint));
+        }
+    }
+    Ok(result)
+}
+
+/// **(internal)** Compute a BDD representing the functions of the given `class`, where the
+/// function value in the `i`-th row of its truth table is given by the symbolic variable
+/// `rows[i]` (see [ParameterInstantiation] for the ordering of rows).
+pub(crate) fn mk_function_class_rows(
+    variables: &BddVariableSet,
+    rows: &[BddVariable],
+    class: FunctionClass,
+) -> Result<Bdd, String> {
+    assert!(rows.len().is_power_of_two());
+    let arity = rows.len().trailing_zeros() as usize;
+    check_function_class(arity, class)?;
+    Ok(match class {
+        Unate => mk_unate(variables, rows, arity),
+        NestedCanalizing => {
+            let mut cache = HashMap::new();
+            mk_nested_canalizing(variables, rows, arity, (0, 0), &mut cache)
+        }
+        Threshold => mk_tables(variables, rows, threshold_tables(arity)),
+        Majority => mk_tables(variables, rows, majority_tables(arity)),
+    })
+}
+
+/// **(internal)** Check that the given function `class` is supported for functions
+/// of the given `arity` (see [FunctionClass]).
+pub(crate) fn check_function_class(arity: usize, class: FunctionClass) -> Result<(), String> {
+    if class == Threshold && arity >= MAX_THRESHOLD_WEIGHT.len() {
+        return Err(format!(
+            "Threshold functions are only supported up to {} arguments, but {} found.",
+            MAX_THRESHOLD_WEIGHT.len() - 1,
+            arity
+        ));
+    }
+    Ok(())
+}
+
+/// **(internal)** Check that the given function `class` is supported for functions of the given
+/// `arity` and return the size of their truth table.
+pub(crate) fn function_class_table_size(arity: usize, class: FunctionClass) -> Result<u16, String> {
+    check_function_class(arity, class)?;
+    u32::try_from(arity)
+        .ok()
+        .and_then(|it| 1usize.checked_shl(it))
+        .and_then(|it| u16::try_from(it).ok())
+        .ok_or_else(|| {
+            format!(
+                "Function classes are not supported for functions with {} arguments.",
+                arity
+            )
+        })
+}
+
+/// **(internal)** Every argument is either positive or negative monotonic.
+fn mk_unate(variables: &BddVariableSet, rows: &[BddVariable], arity: usize) -> Bdd {
+    let mut result = variables.mk_true();
+    for i in 0..arity {
+        let mut positive = variables.mk_true();
+        let mut negative = variables.mk_true();
+        for row in (0..rows.len()).filter(|row| row & (1 << i) == 0) {
+            let low = variables.mk_var(rows[row]);
+            let high = variables.mk_var(rows[row | (1 << i)]);
+            positive = positive.and(&low.imp(&high));
+            negative = negative.and(&high.imp(&low));
+        }
+        result = result.and(&positive.or(&negative));
+    }
+    result
+}
+
+/// **(internal)** The function is constant within the given subcube of its truth table.
+///
+/// A subcube is given as a `(mask, values)` pair, where `mask` contains the fixed arguments
+/// and `values` their values.
+fn mk_constant_on(variables: &BddVariableSet, rows: &[BddVariable], cube: (usize, usize)) -> Bdd {
+    let (mask, values) = cube;
+    let mut is_true = variables.mk_true();
+    let mut is_false = variables.mk_true();
+    for row in (0..rows.len()).filter(|row| row & mask == values) {
+        let value = variables.mk_var(rows[row]);
+        is_true = is_true.and(&value);
+        is_false = is_false.and_not(&value);
+    }
+    is_true.or(&is_false)
+}
+
+/// **(internal)** The function restricted to the given subcube is either constant, or
+/// it has a canalizing argument such that the rest of the function (i.e. where the argument
+/// has the non-canalizing value) is again nested canalizing.
+fn mk_nested_canalizing(
+    variables: &BddVariableSet,
+    rows: &[BddVariable],
+    arity: usize,
+    cube: (usize, usize),
+    cache: &mut HashMap<(usize, usize), Bdd>,
+) -> Bdd {
+    if let Some(result) = cache.get(&cube) {
+        return result.clone();
+    }
+    let (mask, values) = cube;
+    let mut result = mk_constant_on(variables, rows, cube);
+    for i in (0..arity).filter(|i| mask & (1 << i) == 0) {
+        let bit = 1 << i;
+        for (canalizing, rest) in [(values, values | bit), (values | bit, values)] {
+            let is_canalizing = mk_constant_on(variables, rows, (mask | bit, canalizing));
+            let rest = mk_nested_canalizing(variables, rows, arity, (mask | bit, rest), cache);
+            result = result.or(&is_canalizing.and(&rest));
+        }
+    }
+    cache.insert(cube, result.clone());
+    result
+}
+
+/// **(internal)** A BDD which is satisfied exactly by the given truth `tables`.
+fn mk_tables(variables: &BddVariableSet, rows: &[BddVariable], tables: HashSet<Vec<bool>>) -> Bdd {
+    let clauses = tables
+        .into_iter()
+        .map(|table| {
+            let mut clause = BddPartialValuation::empty();
+            for (row, value) in table.into_iter().enumerate() {
+                clause.set_value(rows[row], value);
+            }
+            clause
+        })
+        .collect::<Vec<_>>();
+    variables.mk_dnf(&clauses)
+}
+
+/// **(internal)** Enumerate the truth tables of all threshold functions with `arity` arguments.
+///
+/// First, all positive threshold functions are enumerated using bounded weights. Then,
+/// negative weights are obtained by negating the arguments.
+fn threshold_tables(arity: usize) -> HashSet<Vec<bool>> {
+    let max_weight = MAX_THRESHOLD_WEIGHT[arity];
+    let mut positive = HashSet::new();
+    let mut weights = vec![0u32; arity];
+    loop {
+        let total: u32 = weights.iter().sum();
+        for threshold in 0..=(total + 1) {
+            let table = (0..(1usize << arity))
+                .map(|row| {
+                    let sum: u32 = (0..arity)
+                        .filter(|i| row & (1 << i) != 0)
+                        .map(|i| weights[i])
+                        .sum();
+                    sum >= threshold
+                })
+                .collect::<Vec<_>>();
+            positive.insert(table);
+        }
+        // Advance to the next weight vector (or stop once all were explored).
+        let Some(i) = (0..arity).find(|i| weights[*i] < max_weight) else {
+            break;
+        };
+        weights[i] += 1;
+        for weight in weights.iter_mut().take(i) {
+            *weight = 0;
+        }
+    }
+    let mut result = HashSet::new();
+    for table in positive {
+        for negated in 0..(1usize << arity) {
+            let table = (0..table.len())
+                .map(|row| table[row ^ negated])
+                .collect::<Vec<_>>();
+            result.insert(table);
+        }
+    }
+    result
+}
+
+/// **(internal)** Enumerate the truth tables of all majority functions with `arity` arguments.
+fn majority_tables(arity: usize) -> HashSet<Vec<bool>> {
+    let thresholds = [(arity + 1) / 2, arity / 2 + 1];
+    let mut result = HashSet::new();
+    for negated in 0..(1usize << arity) {
+        for threshold in thresholds {
+            let table = (0..(1usize << arity))
+                .map(|row| (row ^ negated).count_ones() as usize >= threshold)
+                .collect::<Vec<_>>();
+            result.insert(table);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::_impl_function_class_constraint::mk_function_class_rows;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::FunctionClass::{Majority, NestedCanalizing, Threshold, Unate};
+    use crate::{BooleanNetwork, FunctionClass};
+    use biodivine_lib_bdd::BddVariableSet;
+
+    /// Count the functions of the given `class` with the given `arity`.
+    fn count_functions(arity: usize, class: FunctionClass) -> f64 {
+        let variables = BddVariableSet::new_anonymous(1 << arity);
+        let rows = variables.variables();
+        let functions = mk_function_class_rows(&variables, &rows, class).unwrap();
+        functions.cardinality()
+    }
+
+    #[test]
+    fn test_function_class_counts() {
+        // All functions with at most three arguments that are unate are also threshold.
+        assert_eq!(4.0, count_functions(1, Unate));
+        assert_eq!(14.0, count_functions(2, Unate));
+        assert_eq!(104.0, count_functions(3, Unate));
+        assert_eq!(104.0, count_functions(3, Threshold));
+        assert_eq!(1882.0, count_functions(4, Threshold));
+        // Nested canalizing functions of all subsets of arguments (including constants).
+        assert_eq!(4.0, count_functions(1, NestedCanalizing));
+        assert_eq!(14.0, count_functions(2, NestedCanalizing));
+        assert_eq!(96.0, count_functions(3, NestedCanalizing));
+        // Conjunctions and disjunctions of literals, or a strict majority.
+        assert_eq!(8.0, count_functions(2, Majority));
+        assert_eq!(8.0, count_functions(3, Majority));
+
+        let variables = BddVariableSet::new_anonymous(64);
+        let rows = variables.variables();
+        assert!(mk_function_class_rows(&variables, &rows, Threshold).is_err());
+    }
+
+    #[test]
+    fn test_function_class_colors() {
+        let model = r"
+            a -> c
+            b -? c
+            c -| c
+            $c: f(a, b, c)
+        ";
+        let bn = BooleanNetwork::try_from(model).unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let all = stg.unit_colors().approx_cardinality();
+
+        let unate = format!("{}\n@f: unate", model);
+        let unate = BooleanNetwork::try_from(unate.as_str()).unwrap();
+        let unate_stg = SymbolicAsyncGraph::new(&unate).unwrap();
+        let unate_count = unate_stg.unit_colors().approx_cardinality();
+        assert!(unate_count < all);
+        for (_, metrics) in unate_stg.function_metrics(c, unate_stg.unit_colors()) {
+            assert!(metrics.unateness().iter().all(|(_, it)| it.is_some()));
+        }
+        // The remaining functions are not unate in `b`.
+        let not_unate = stg.unit_colors().minus(unate_stg.unit_colors());
+        for (_, metrics) in stg.function_metrics(c, &not_unate) {
+            assert!(metrics.unateness().iter().any(|(_, it)| it.is_none()));
+        }
+
+        let canalizing = format!("{}\n@f: nested_canalizing, majority", model);
+        let canalizing = BooleanNetwork::try_from(canalizing.as_str()).unwrap();
+        // `[a + b + !c >= 2]` and `[a + !b + !c >= 2]` are not nested canalizing.
+        assert!(SymbolicAsyncGraph::new(&canalizing).is_err());
+        let majority = format!("{}\n@f: majority", model);
+        let majority = BooleanNetwork::try_from(majority.as_str()).unwrap();
+        let majority_stg = SymbolicAsyncGraph::new(&majority).unwrap();
+        // Two majority functions, times the two values of each input `a` and `b`.
+        assert_eq!(8.0, majority_stg.unit_colors().approx_cardinality());
+        assert!(canalizing
+            .remove_static_constraints()
+            .get_parameter_classes(canalizing.find_parameter("f").unwrap())
+            .is_empty());
+    }
+}
@@ -0,0 +1,348 @@
+use crate::_impl_fn_update_minimization::fn_to_bdd;
+use crate::biodivine_std::traits::Set;
+use crate::symbolic_async_graph::{FunctionMetrics, GraphColors, SymbolicAsyncGraph};
+use crate::Monotonicity::{Activation, Inhibition};
+use crate::{FnUpdate, Monotonicity, VariableId};
+use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet};
+use std::convert::TryFrom;
+
+impl SymbolicAsyncGraph {
+    /// Compute the structural properties of the update function of `variable` for all
+    /// colors in the given set.
+    ///
+    /// The `colors` are partitioned into classes in which the update function is the same,
+    /// and each class is returned together with the metrics of this function. The inputs of
+    /// the function are all network variables that the (parametrised) update function can
+    /// depend on.
+    pub fn function_metrics(
+        &self,
+        variable: VariableId,
+        colors: &GraphColors,
+    ) -> Vec<(GraphColors, FunctionMetrics)> {
+        let context = self.symbolic_context();
+        let function = self.get_symbolic_fn_update(variable);
+        let support = function.support_set();
+        let inputs = self
+            .variables()
+            .map(|it| (it, context.get_state_variable(it)))
+            .filter(|(_, bdd_var)| support.contains(bdd_var))
+            .collect::<Vec<_>>();
+
+        let mut result = Vec::new();
+        let mut remaining = colors.clone();
+        while !remaining.is_empty() {
+            let witness = remaining.as_bdd().sat_witness().unwrap();
+            let parameters = context
+                .parameter_variables()
+                .iter()
+                .map(|it| (*it, witness[*it]))
+                .collect::<Vec<_>>();
+            let instance = function.restrict(&parameters);
+            // All remaining colors where the update function is equivalent to `instance`.
+            let same_function = function.iff(&instance).for_all(context.state_variables());
+            let class = remaining.copy(remaining.as_bdd().and(&same_function));
+            remaining = remaining.minus(&class);
+            result.push((class, FunctionMetrics::from_bdd(&instance, &inputs)));
+        }
+        result
+    }
+}
+
+impl FunctionMetrics {
+    /// Compute the structural properties of an update function which contains no parameters.
+    ///
+    /// The inputs of the function are the variables that appear in it (sorted by their ids).
+    pub fn try_from_fn_update(function: &FnUpdate) -> Result<FunctionMetrics, String> {
+        if !function.collect_parameters().is_empty() {
+            return Err("Cannot compute metrics of a function with parameters.".to_string());
+        }
+        let inputs = function.collect_arguments();
+        let atoms = inputs
+            .iter()
+            .map(|it| FnUpdate::mk_var(*it))
+            .collect::<Vec<_>>();
+        let num_vars =
+            u16::try_from(inputs.len()).map_err(|_| "Function has too many inputs.".to_string())?;
+        let variables = BddVariableSet::new_anonymous(num_vars);
+        let bdd = fn_to_bdd(function, &atoms, &variables);
+        let inputs = inputs
+            .into_iter()
+            .zip(variables.variables())
+            .collect::<Vec<_>>();
+        Ok(FunctionMetrics::from_bdd(&bdd, &inputs))
+    }
+
+    /// **(internal)** Compute the metrics of a `function` which only depends on the
+    /// given `inputs`.
+    fn from_bdd(function: &Bdd, inputs: &[(VariableId, BddVariable)]) -> FunctionMetrics {
+        let mut unateness = Vec::new();
+        let mut average_sensitivity = 0.0;
+        for (var, bdd_var) in inputs {
+            let fn_is_true = function.var_restrict(*bdd_var, true);
+            let fn_is_false = function.var_restrict(*bdd_var, false);
+            let positive = fn_is_true.and_not(&fn_is_false);
+            let negative = fn_is_false.and_not(&fn_is_true);
+            average_sensitivity += probability(&positive) + probability(&negative);
+            let monotonicity = match (positive.is_false(), negative.is_false()) {
+                (true, true) => continue, // Not an essential input.
+                (false, true) => Some(Activation),
+                (true, false) => Some(Inhibition),
+                (false, false) => None,
+            };
+            unateness.push((*var, monotonicity));
+        }
+
+        let canalizing = canalizing_inputs(function, inputs);
+        let mut canalization_depth = 0;
+        let mut core = function.clone();
+        let mut layer = canalizing.clone();
+        while !layer.is_empty() {
+            for (var, bdd_var) in inputs {
+                // An input can appear twice if the function is a literal, but it is
+                // sufficient to eliminate it once.
+                if let Some((_, value, _)) = layer.iter().find(|(it, _, _)| it == var) {
+                    core = core.var_restrict(*bdd_var, !*value);
+                    canalization_depth += 1;
+                }
+            }
+            layer = canalizing_inputs(&core, inputs);
+        }
+
+        FunctionMetrics {
+            inputs: inputs.iter().map(|(it, _)| *it).collect(),
+            unateness,
+            canalizing,
+            canalization_depth,
+            average_sensitivity,
+            bias: probability(function),
+        }
+    }
+
+    /// The inputs with respect to which the metrics were computed.
+    pub fn inputs(&self) -> &[VariableId] {
+        &self.inputs
+    }
+
+    /// The inputs on which the function actually depends.
+    pub fn essential_inputs(&self) -> Vec<VariableId> {
+        self.unateness.iter().map(|(it, _)| *it).collect()
+    }
+
+    /// The unateness of every essential input: positive (`Activation`),
+    /// negative (`Inhibition`), or not unate (`None`).
+    pub fn unateness(&self) -> &[(VariableId, Option<Monotonicity>)] {
+        &self.unateness
+    }
+
+    /// The list of canalizing `(input, value, output)` triples, i.e. setting `input` to
+    /// `value` fixes the function output to `output`.
+    pub fn canalizing_inputs(&self) -> &[(VariableId, bool, bool)] {
+        &self.canalizing
+    }
+
+    /// The number of inputs eliminated by the nested canalization of the function.
+    pub fn canalization_depth(&self) -> usize {
+        self.canalization_depth
+    }
+
+    /// True if the function is nested canalizing in all essential inputs, i.e. its
+    /// canalization depth is the number of essential inputs. This is the same definition
+    /// as [crate::FunctionClass::NestedCanalizing], hence constant functions are also
+    /// nested canalizing.
+    pub fn is_nested_canalizing(&self) -> bool {
+        self.canalization_depth == self.unateness.len()
+    }
+
+    /// The expected number of inputs whose negation changes the function output.
+    pub fn average_sensitivity(&self) -> f64 {
+        self.average_sensitivity
+    }
+
+    /// The fraction of input valuations for which the function is true.
+    pub fn bias(&self) -> f64 {
+        self.bias
+    }
+}
+
+/// **(internal)** Compute the canalizing `(input, value, output)` triples of a `function`.
+fn canalizing_inputs(
+    function: &Bdd,
+    inputs: &[(VariableId, BddVariable)],
+) -> Vec<(VariableId, bool, bool)> {
+    if function.is_true() || function.is_false() {
+        return Vec::new();
+    }
+    let mut result = Vec::new();
+    for (var, bdd_var) in inputs {
+        for value in [false, true] {
+            let restricted = function.var_restrict(*bdd_var, value);
+            if restricted.is_true() {
+                result.push((*var, value, true));
+            } else if restricted.is_false() {
+                result.push((*var, value, false));
+            }
+        }
+    }
+    result
+}
+
+/// **(internal)** The probability that a `Bdd` is satisfied by a uniformly random valuation.
+///
+/// Unlike `cardinality / 2^n`, this does not overflow for large variable sets.
+fn probability(bdd: &Bdd) -> f64 {
+    // Bdd nodes are sorted such that children always precede their parents.
+    let mut probabilities = vec![0.0; bdd.size()];
+    for pointer in bdd.pointers() {
+        probabilities[pointer.to_index()] = if pointer.is_zero() {
+            0.0
+        } else if pointer.is_one() {
+            1.0
+        } else {
+            let low = probabilities[bdd.low_link_of(pointer).to_index()];
+            let high = probabilities[bdd.high_link_of(pointer).to_index()];
+            (low + high) / 2.0
+        };
+    }
+    probabilities[bdd.root_pointer().to_index()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::_impl_function_class_constraint::mk_function_class_rows;
+    use crate::symbolic_async_graph::{FunctionMetrics, SymbolicAsyncGraph};
+    use crate::FunctionClass::NestedCanalizing;
+    use crate::Monotonicity::{Activation, Inhibition};
+    use crate::{BooleanNetwork, FnUpdate, VariableId};
+    use biodivine_lib_bdd::{BddPartialValuation, BddVariableSet, ValuationsOfClauseIterator};
+
+    #[test]
+    fn test_function_metrics() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> d
+            b -| d
+            c -? d
+            a -> e
+            b -> e
+            c -? e
+            $d: a & !b & c
+            $e: (a | b) ^ c
+        ",
+        )
+        .unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let d = bn.as_graph().find_variable("d").unwrap();
+        let e = bn.as_graph().find_variable("e").unwrap();
+
+        let fn_d = bn.get_update_function(d).as_ref().unwrap();
+        let metrics = FunctionMetrics::try_from_fn_update(fn_d).unwrap();
+        assert_eq!(&[a, b, c], metrics.inputs());
+        assert_eq!(
+            &[
+                (a, Some(Activation)),
+                (b, Some(Inhibition)),
+                (c, Some(Activation))
+            ],
+            metrics.unateness()
+        );
+        assert_eq!(
+            &[(a, false, false), (b, true, false), (c, false, false)],
+            metrics.canalizing_inputs()
+        );
+        assert_eq!(3, metrics.canalization_depth());
+        assert!(metrics.is_nested_canalizing());
+        // Every input is sensitive only in the state where the others are "active".
+        assert_eq!(0.75, metrics.average_sensitivity());
+        assert_eq!(0.125, metrics.bias());
+
+        let fn_e = bn.get_update_function(e).as_ref().unwrap();
+        let metrics = FunctionMetrics::try_from_fn_update(fn_e).unwrap();
+        assert_eq!(&[(a, None), (b, None), (c, None)], metrics.unateness());
+        assert!(metrics.canalizing_inputs().is_empty());
+        assert_eq!(0, metrics.canalization_depth());
+        assert!(!metrics.is_nested_canalizing());
+        assert_eq!(0.5, metrics.bias());
+        assert_eq!(2.0, metrics.average_sensitivity());
+
+        let constant = FunctionMetrics::try_from_fn_update(&FnUpdate::mk_true()).unwrap();
+        assert!(constant.essential_inputs().is_empty());
+        assert_eq!(1.0, constant.bias());
+        assert!(constant.is_nested_canalizing());
+    }
+
+    #[test]
+    fn test_nested_canalizing_matches_function_class() {
+        // For every function of three arguments, the metrics agree with the class constraint.
+        let inputs = BddVariableSet::new_anonymous(3);
+        let input_vars = inputs
+            .variables()
+            .into_iter()
+            .enumerate()
+            .map(|(i, it)| (VariableId::from_index(i), it))
+            .collect::<Vec<_>>();
+        let rows = BddVariableSet::new_anonymous(8);
+        let class = mk_function_class_rows(&rows, &rows.variables(), NestedCanalizing).unwrap();
+        for table in ValuationsOfClauseIterator::new_unconstrained(8) {
+            let mut function = inputs.mk_false();
+            for (row, row_var) in rows.variables().into_iter().enumerate() {
+                if table.value(row_var) {
+                    let mut clause = BddPartialValuation::empty();
+                    for (i, (_, var)) in input_vars.iter().enumerate() {
+                        clause.set_value(*var, row & (1 << i) != 0);
+                    }
+                    function = function.or(&inputs.mk_conjunctive_clause(&clause));
+                }
+            }
+            let metrics = FunctionMetrics::from_bdd(&function, &input_vars);
+            assert_eq!(class.eval_in(&table), metrics.is_nested_canalizing());
+        }
+    }
+
+    #[test]
+    fn test_function_metrics_per_color() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> c
+            b -?? c
+            $c: a & f(b)
+        ",
+        )
+        .unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let fn_c = bn.get_update_function(c).as_ref().unwrap();
+        assert!(FunctionMetrics::try_from_fn_update(fn_c).is_err());
+
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let classes = stg.function_metrics(c, stg.unit_colors());
+        // `f` is not constant false, since `a` must be observable.
+        assert_eq!(3, classes.len());
+        let mut union = stg.mk_empty_colors();
+        for (colors, metrics) in &classes {
+            assert!(union.intersect(colors).is_empty());
+            union = union.union(colors);
+            assert_eq!(2, metrics.inputs().len());
+            let depth = metrics.canalization_depth();
+            match metrics.essential_inputs().len() {
+                // `f(b) = true`
+                1 => assert_eq!(
+                    (0.5, 1.0, 1),
+                    (metrics.bias(), metrics.average_sensitivity(), depth)
+                ),
+                // `f(b) = b` or `f(b) = !b`
+                _ => {
+                    assert_eq!(0.25, metrics.bias());
+                    assert_eq!(1.0, metrics.average_sensitivity());
+                    assert_eq!(2, depth);
+                    let (var, sign) = metrics.unateness()[1];
+                    assert_eq!(b, var);
+                    assert!(sign.is_some());
+                }
+            }
+        }
+        assert_eq!(&union, stg.unit_colors());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::_impl_function_values::function_row_to_string;
+use crate::symbolic_async_graph::{
+    FunctionTable, RegulationConstraint, StaticConstraint, SymbolicContext,
+};
+use crate::BooleanNetwork;
+use biodivine_lib_bdd::Bdd;
+
+impl RegulationConstraint {
+    /// Compute a BDD representing all instantiations of the uninterpreted function given
+    /// by its `table` which evaluate to `value` for the given `args`.
+    ///
+    /// The method fails if the number of `args` does not match the arity of the `table`.
+    pub fn mk_function_value(
+        ctx: &SymbolicContext,
+        table: &FunctionTable,
+        args: &[bool],
+        value: bool,
+    ) -> Result<Bdd, String> {
+        table
+            .into_iter()
+            .find(|(row, _)| row.as_slice() == args)
+            .map(|(_, bdd_var)| ctx.bdd_variable_set().mk_literal(bdd_var, value))
+            .ok_or_else(|| {
+                format!(
+                    "Expected {} arguments, but {} were given.",
+                    table.arity,
+                    args.len()
+                )
+            })
+    }
+}
+
+/// **(internal)** Compute the constraints given by the known values of all uninterpreted
+/// functions of the `network`, tagged by the corresponding [StaticConstraint].
+pub(crate) fn mk_function_value_constraints(
+    network: &BooleanNetwork,
+    context: &SymbolicContext,
+) -> Result<Vec<(StaticConstraint, Bdd)>, String> {
+    let mut result = Vec::new();
+    for parameter in network.parameters() {
+        let table = context.get_explicit_function_table(parameter);
+        let name = network.get_parameter(parameter).get_name();
+        for (args, value) in network.get_parameter_values(parameter) {
+            let constraint = RegulationConstraint::mk_function_value(context, table, &args, value)
+                .map_err(|e| {
+                    let row = function_row_to_string(name, &args);
+                    format!("Invalid value of `{}`: {}", row, e)
+                })?;
+            let constraint_id = StaticConstraint::ParameterValue(parameter, args, value);
+            result.push((constraint_id, constraint));
+        }
+    }
+    for variable in network.variables() {
+        if network.get_update_function(variable).is_some() {
+            continue;
+        }
+        let Some(table) = context.get_implicit_function_table(variable) else {
+            continue;
+        };
+        let name = network.get_variable_name(variable);
+        for (args, value) in network.get_implicit_values(variable) {
+            let constraint = RegulationConstraint::mk_function_value(context, table, &args, value)
+                .map_err(|e| {
+                    let row = function_row_to_string(name, &args);
+                    format!("Invalid value of `{}`: {}", row, e)
+                })?;
+            let constraint_id = StaticConstraint::ImplicitValue(variable, args, value);
+            result.push((constraint_id, constraint));
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::BooleanNetwork;
+
+    #[test]
+    fn test_function_value_colors() {
+        let model = r"
+            a -> b
+            b -?? b
+            a -?? c
+            b -?? c
+            $b: f(a, b)
+        ";
+        let mut bn = BooleanNetwork::try_from(model).unwrap();
+        let f = bn.find_parameter("f").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        // `a -> b` admits 5 functions `f`, `c` is unconstrained (16), `a` has an input (2).
+        assert_eq!(160.0, stg.unit_colors().approx_cardinality());
+
+        bn.add_parameter_value(f, &[true, false], true).unwrap();
+        bn.add_implicit_value(c, &[true, true], false).unwrap();
+        let constrained = SymbolicAsyncGraph::new(&bn).unwrap();
+        // `f(1, 0) = 1` leaves 4 functions, `c(1, 1) = 0` leaves 8.
+        assert_eq!(64.0, constrained.unit_colors().approx_cardinality());
+        let colors = stg.unit_colors().intersect(constrained.unit_colors());
+        assert_eq!(&colors, constrained.unit_colors());
+
+        // A value can contradict the monotonicity of a regulation.
+        bn.add_parameter_value(f, &[false, true], true).unwrap();
+        bn.add_parameter_value(f, &[true, true], false).unwrap();
+        let error = SymbolicAsyncGraph::new(&bn).err().unwrap();
+        assert!(error.contains(" - f(1, 1) cannot be 0.\n"));
+
+        // The values must match the regulators of implicit functions.
+        let mut bn = BooleanNetwork::try_from(model).unwrap();
+        bn.add_implicit_value(c, &[true, true], false).unwrap();
+        bn.as_graph_mut()
+            .add_regulation("c", "c", false, None)
+            .unwrap();
+        assert!(SymbolicAsyncGraph::new(&bn).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use crate::biodivine_std::bitvector::ArrayBitVector;
+use crate::symbolic_async_graph::{GraphColoredVertices, GraphColors, SymbolicAsyncGraph};
+use crate::Sign::{Negative, Positive};
+use crate::{SdGraph, Sign, Space, VariableId};
+
+/// Local (state-dependent) interaction graphs.
+///
+/// In a local interaction graph, a regulator `x_j` influences a target `x_i` in state `x` if
+/// the partial derivative of `f_i` with respect to `x_j` is non-zero in `x`, i.e. if
+/// `f_i(x[x_j = 1]) != f_i(x[x_j = 0])`. The sign of the interaction is then positive if
+/// `f_i(x[x_j = 1]) > f_i(x[x_j = 0])`, and negative otherwise.
+///
+/// For sets of states (or colors), the local interaction graph is the union of the graphs of
+/// the individual elements. Hence, if both signs are observed for a pair of variables,
+/// the result contains a non-monotonic edge (i.e. both a positive and a negative edge).
+impl SymbolicAsyncGraph {
+    /// Compute the local interaction graph in the given `state`, considering all colors
+    /// of this graph.
+    pub fn local_interaction_graph(&self, state: &ArrayBitVector) -> SdGraph {
+        self.colored_local_interaction_graph(&self.vertex(state))
+    }
+
+    /// Compute the local interaction graph of the given subspace, i.e. the union of local
+    /// interaction graphs of all states within the `space` (for all colors of this graph).
+    pub fn space_local_interaction_graph(&self, space: &Space) -> SdGraph {
+        self.colored_local_interaction_graph(&self.mk_subspace(&space.to_values()))
+    }
+
+    /// Compute the global interaction graph of the network instantiations given by `colors`,
+    /// i.e. the union of local interaction graphs across all states of this graph.
+    ///
+    /// For a singleton color set, the result is the "actual" regulatory graph of
+    /// the particular network instance.
+    pub fn color_interaction_graph(&self, colors: &GraphColors) -> SdGraph {
+        self.colored_local_interaction_graph(&self.unit_colored_vertices().intersect_colors(colors))
+    }
+
+    /// Compute the union of local interaction graphs for all vertex-color pairs in the given
+    /// `set`.
+    pub fn colored_local_interaction_graph(&self, set: &GraphColoredVertices) -> SdGraph {
+        let context = self.symbolic_context();
+        let mut edges = Vec::new();
+        for target in self.variables() {
+            let function = self.get_symbolic_fn_update(target);
+            let support = function.support_set();
+            for regulator in self.variables() {
+                let bdd_var = context.get_state_variable(regulator);
+                if !support.contains(&bdd_var) {
+                    continue;
+                }
+                // Note that the restricted functions do not depend on `bdd_var`, so they
+                // are evaluated in the state "without" the value of `regulator`.
+                let fn_is_true = function.var_restrict(bdd_var, true);
+                let fn_is_false = function.var_restrict(bdd_var, false);
+                let positive = fn_is_true.and_not(&fn_is_false);
+                let negative = fn_is_false.and_not(&fn_is_true);
+                if !positive.and(set.as_bdd()).is_false() {
+                    edges.push((regulator, target, Positive));
+                }
+                if !negative.and(set.as_bdd()).is_false() {
+                    edges.push((regulator, target, Negative));
+                }
+            }
+        }
+        SdGraph::from_edges(self.num_vars(), &edges)
+    }
+
+    /// Compute the set of vertex-color pairs in which `regulator` has a local influence
+    /// of the given `sign` on `target` (see [SymbolicAsyncGraph::colored_local_interaction_graph]).
+    pub fn local_interaction_set(
+        &self,
+        regulator: VariableId,
+        target: VariableId,
+        sign: Sign,
+    ) -> GraphColoredVertices {
+        let bdd_var = self.symbolic_context().get_state_variable(regulator);
+        let function = self.get_symbolic_fn_update(target);
+        let fn_is_true = function.var_restrict(bdd_var, true);
+        let fn_is_false = function.var_restrict(bdd_var, false);
+        let derivative = match sign {
+            Positive => fn_is_true.and_not(&fn_is_false),
+            Negative => fn_is_false.and_not(&fn_is_true),
+        };
+        self.unit_colored_vertices()
+            .copy(derivative.and(self.unit_colored_vertices().as_bdd()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::biodivine_std::bitvector::ArrayBitVector;
+    use crate::biodivine_std::traits::Set;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::Sign::{Negative, Positive};
+    use crate::{BooleanNetwork, ExtendedBoolean, Space};
+
+    #[test]
+    fn test_local_interaction_graph() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> a
+            b -> b
+            a -> c
+            b -| c
+            a -? d
+            b -? d
+            $a: a
+            $b: b
+            $c: a & !b
+            $d: a ^ b
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let d = bn.as_graph().find_variable("d").unwrap();
+
+        // In a=1, b=0, `a` activates `c` and `d`, while `b` inhibits `c` and `d`.
+        let state = ArrayBitVector::from(vec![true, false, false, false]);
+        let graph = stg.local_interaction_graph(&state);
+        assert!(graph.has_edge(a, c, Positive));
+        assert!(!graph.has_edge(a, c, Negative));
+        assert!(graph.has_edge(b, c, Negative));
+        assert!(!graph.has_edge(b, c, Positive));
+        assert!(graph.has_edge(a, d, Positive));
+        assert!(!graph.has_edge(a, d, Negative));
+        assert!(graph.has_edge(b, d, Negative));
+        assert!(graph.has_edge(a, a, Positive));
+
+        // In a=0, b=1, `c` does not depend on anything.
+        let state = ArrayBitVector::from(vec![false, true, false, false]);
+        let graph = stg.local_interaction_graph(&state);
+        assert!(!graph.has_edge(a, c, Positive));
+        assert!(!graph.has_edge(b, c, Negative));
+        assert!(graph.has_edge(a, d, Negative));
+
+        // In a subspace where `b` is free, `d` depends on `a` in both ways.
+        let mut space = Space::new(&bn);
+        space[a] = ExtendedBoolean::One;
+        let graph = stg.space_local_interaction_graph(&space);
+        assert!(graph.has_edge(a, d, Positive));
+        assert!(graph.has_edge(a, d, Negative));
+        assert!(graph.has_edge(b, c, Negative));
+
+        // Globally, the graph matches the regulatory graph.
+        let graph = stg.color_interaction_graph(stg.unit_colors());
+        assert!(graph.has_edge(a, c, Positive));
+        assert!(!graph.has_edge(a, c, Negative));
+        assert!(graph.has_edge(b, d, Positive));
+        assert!(graph.has_edge(b, d, Negative));
+    }
+
+    #[test]
+    fn test_color_interaction_graph() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> a
+            a -?? b
+            $a: a
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+
+        // Colors where `a` activates `b`.
+        let activation = stg.local_interaction_set(a, b, Positive).colors();
+        assert_eq!(1.0, activation.approx_cardinality());
+        let graph = stg.color_interaction_graph(&activation);
+        assert!(graph.has_edge(a, b, Positive));
+        assert!(!graph.has_edge(a, b, Negative));
+
+        // Colors where `a` has no effect on `b`.
+        let no_effect = stg
+            .unit_colors()
+            .minus(&activation)
+            .minus(&stg.local_interaction_set(a, b, Negative).colors());
+        assert_eq!(2.0, no_effect.approx_cardinality());
+        let graph = stg.color_interaction_graph(&no_effect);
+        assert!(!graph.has_edge(a, b, Positive));
+        assert!(!graph.has_edge(a, b, Negative));
+    }
+}
@@ -1,6 +1,9 @@
-use crate::symbolic_async_graph::{RegulationConstraint, SymbolicContext};
+use crate::_impl_function_values::function_row_to_string;
+use crate::symbolic_async_graph::_impl_function_class_constraint::mk_function_class_constraints;
+use crate::symbolic_async_graph::_impl_function_value_constraint::mk_function_value_constraints;
+use crate::symbolic_async_graph::{RegulationConstraint, StaticConstraint, SymbolicContext};
 use crate::{BooleanNetwork, Monotonicity, Regulation, VariableId};
-use biodivine_lib_bdd::{bdd, Bdd};
+use biodivine_lib_bdd::{bdd, Bdd, BddVariable};
 impl RegulationConstraint {
     /// Compute a BDD representing all instantiations of a (partial) function where the given
     /// `input` is observable (also called essential).
@@ -137,6 +140,7 @@ impl RegulationConstraint {
             target,
             observable,
             monotonicity,
+            uncertain: false,
         })
     }
 
@@ -201,6 +205,7 @@ impl RegulationConstraint {
             target: old_regulation.target,
             observable,
             monotonicity,
+            uncertain: old_regulation.uncertain,
         })
     }
 }
@@ -217,6 +222,45 @@ pub(crate) fn apply_regulation_constraints(
     network: &BooleanNetwork,
     context: &SymbolicContext,
 ) -> Result<Bdd, String> {
+    let mut error_message = String::new();
+    let mut unit_bdd = initial;
+    for (constraint, bdd) in mk_regulation_static_constraints(network, context) {
+        /* If a regulation constraint failed, report error and continue. */
+        if bdd.is_false() {
+            let problem = constraint_problem(network, &constraint);
+            error_message = format!("{}{}", error_message, problem);
+        }
+        unit_bdd = unit_bdd.and(&bdd);
+    }
+
+    let mut function_constraints = mk_function_class_constraints(network, context)?;
+    function_constraints.extend(mk_function_value_constraints(network, context)?);
+    for (constraint, bdd) in function_constraints {
+        let restricted = unit_bdd.and(&bdd);
+        if restricted.is_false() && !unit_bdd.is_false() {
+            let problem = constraint_problem(network, &constraint);
+            error_message = format!("{}{}", error_message, problem);
+        }
+        unit_bdd = restricted;
+    }
+
+    if unit_bdd.is_false() {
+        Err(format!(
+            "No update functions satisfy given constraints: \n{}",
+            error_message
+        ))
+    } else {
+        Ok(unit_bdd)
+    }
+}
+
+/// **(internal)** Compute the observability and monotonicity constraints of all regulations
+/// of the `network` (see [mk_regulation_constraints]), tagged by the corresponding
+/// [StaticConstraint].
+pub(crate) fn mk_regulation_static_constraints(
+    network: &BooleanNetwork,
+    context: &SymbolicContext,
+) -> Vec<(StaticConstraint, Bdd)> {
     // Detect "input parameters". For these, we don't actually want to apply any restrictions,
     // as these are typically just variables that are converted into parameters. Therefore,
     // they *can* have both values, even though one of them would make a particular constraint
@@ -235,75 +279,122 @@ pub(crate) fn apply_regulation_constraints(
         })
         .collect();
 
-    let mut error_message = String::new();
-    let mut unit_bdd = initial;
+    let mut result = Vec::new();
     for regulation in &network.graph.regulations {
-        let regulator = regulation.regulator;
         let fn_is_true = &update_function_is_true[regulation.target.to_index()];
+        let (observability, monotonicity) =
+            mk_regulation_constraints(context, regulation, fn_is_true, &inputs);
+        result.push((
+            StaticConstraint::Observability(regulation.clone()),
+            observability,
+        ));
+        result.push((
+            StaticConstraint::Monotonicity(regulation.clone()),
+            monotonicity,
+        ));
+    }
+    result
+}
 
-        let observability = if regulation.observable {
-            RegulationConstraint::mk_observability(context, fn_is_true, regulator)
-        } else {
-            context.mk_constant(true)
-        };
-
-        // The regulation is observable if it is observable for at least one input valuation.
-        let observability = observability.exists(&inputs);
-
-        /* If observability failed, report error and continue. */
-        if observability.is_false() {
-            let problem = format!(
-                " - {} has no effect in {}.\n",
-                network.get_variable_name(regulation.regulator),
-                network.get_variable_name(regulation.target),
-            );
-            error_message = format!("{}{}", error_message, problem);
-        }
-
-        let monotonicity = match regulation.monotonicity {
-            Some(Monotonicity::Activation) => {
-                RegulationConstraint::mk_activation(context, fn_is_true, regulator)
-            }
-            Some(Monotonicity::Inhibition) => {
-                RegulationConstraint::mk_inhibition(context, fn_is_true, regulator)
-            }
-            None => context.mk_constant(true),
-        };
-
-        // The input parameter either makes the regulation not observable,
-        // or it must be monotonic.
-        let monotonicity = monotonicity.for_all(&inputs);
-
-        if monotonicity.is_false() {
+/// **(internal)** A human-readable line which explains that the given `constraint`
+/// cannot be satisfied.
+fn constraint_problem(network: &BooleanNetwork, constraint: &StaticConstraint) -> String {
+    match constraint {
+        StaticConstraint::Observability(regulation) => format!(
+            " - {} has no effect in {}.\n",
+            network.get_variable_name(regulation.regulator),
+            network.get_variable_name(regulation.target),
+        ),
+        StaticConstraint::Monotonicity(regulation) => {
             let monotonicity_str = match regulation.monotonicity {
                 Some(Monotonicity::Activation) => "activating",
                 Some(Monotonicity::Inhibition) => "inhibiting",
                 None => "monotonous",
             };
-            let problem = format!(
+            format!(
                 " - {} not {} in {}.\n",
                 network.get_variable_name(regulation.regulator),
                 monotonicity_str,
                 network.get_variable_name(regulation.target),
-            );
-            error_message = format!("{}{}", error_message, problem);
+            )
+        }
+        StaticConstraint::ParameterClass(parameter, class) => {
+            format!(
+                " - {} cannot be {}.\n",
+                network[*parameter].get_name(),
+                class
+            )
+        }
+        StaticConstraint::ImplicitClass(variable, class) => {
+            format!(" - {} cannot be {}.\n", network[*variable], class)
+        }
+        StaticConstraint::ParameterValue(parameter, args, value) => {
+            let row = function_row_to_string(network[*parameter].get_name(), args);
+            format!(" - {} cannot be {}.\n", row, u8::from(*value))
+        }
+        StaticConstraint::ImplicitValue(variable, args, value) => {
+            let row = function_row_to_string(network.get_variable_name(*variable), args);
+            format!(" - {} cannot be {}.\n", row, u8::from(*value))
         }
-
-        unit_bdd = bdd!(unit_bdd & (monotonicity & observability));
     }
+}
 
-    if unit_bdd.is_false() {
-        Err(format!(
-            "No update functions satisfy given constraints: \n{}",
-            error_message
-        ))
+/// **(internal)** Compute the observability and monotonicity constraints (in this order)
+/// imposed by a single `regulation` on the function of its target (given as `fn_is_true`).
+///
+/// The `inputs` are the "input parameters" of the `context`. The observability must hold
+/// for at least one valuation of these, while the monotonicity must hold for all of them.
+///
+/// If the regulation is uncertain (i.e. it has a symbolic variable in the `context`), both
+/// constraints only apply when the regulation exists. The second constraint then also requires
+/// that the `regulator` has no effect on the function when the regulation does not exist.
+pub(crate) fn mk_regulation_constraints(
+    context: &SymbolicContext,
+    regulation: &Regulation,
+    fn_is_true: &Bdd,
+    inputs: &[BddVariable],
+) -> (Bdd, Bdd) {
+    let regulator = regulation.regulator;
+    let observability = if regulation.observable {
+        RegulationConstraint::mk_observability(context, fn_is_true, regulator)
     } else {
-        Ok(unit_bdd)
+        context.mk_constant(true)
+    };
+
+    // The regulation is observable if it is observable for at least one input valuation.
+    let observability = observability.exists(inputs);
+
+    let monotonicity = match regulation.monotonicity {
+        Some(Monotonicity::Activation) => {
+            RegulationConstraint::mk_activation(context, fn_is_true, regulator)
+        }
+        Some(Monotonicity::Inhibition) => {
+            RegulationConstraint::mk_inhibition(context, fn_is_true, regulator)
+        }
+        None => context.mk_constant(true),
+    };
+
+    // The input parameter either makes the regulation not observable,
+    // or it must be monotonic.
+    let monotonicity = monotonicity.for_all(inputs);
+
+    if let Some(exists) = context.get_regulation_variable(regulator, regulation.target) {
+        let exists = context.bdd_variable_set().mk_var(exists);
+        // A regulation that does not exist cannot be observable for any input valuation.
+        let missing = RegulationConstraint::mk_observability(context, fn_is_true, regulator)
+            .exists(inputs)
+            .not();
+        let observability = exists.imp(&observability);
+        let monotonicity = Bdd::if_then_else(&exists, &monotonicity, &missing);
+        (observability, monotonicity)
+    } else {
+        (observability, monotonicity)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::biodivine_std::traits::Set;
     use crate::symbolic_async_graph::_impl_regulation_constraint::apply_regulation_constraints;
     use crate::symbolic_async_graph::{RegulationConstraint, SymbolicAsyncGraph, SymbolicContext};
     use crate::Monotonicity::{Activation, Inhibition};
@@ -348,6 +439,7 @@ mod tests {
             target: b,
             observable: false,
             monotonicity: None,
+            uncertain: false,
         };
         let reg =
             RegulationConstraint::fix_regulation(&ctx, &empty_reg, stg.get_symbolic_fn_update(b))
@@ -369,6 +461,7 @@ mod tests {
             target: c,
             observable: false,
             monotonicity: None,
+            uncertain: false,
         };
         let reg =
             RegulationConstraint::fix_regulation(&ctx, &empty_reg, stg.get_symbolic_fn_update(c))
@@ -392,4 +485,54 @@ mod tests {
         let constraint = apply_regulation_constraints(unit.clone(), &bn, &ctx);
         assert!(constraint.is_err());
     }
+
+    #[test]
+    fn uncertain_regulation_constraints() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> c
+            b ?-> c
+        ",
+        )
+        .unwrap();
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        assert!(bn.as_graph().find_regulation(b, c).unwrap().is_uncertain());
+
+        let ctx = SymbolicContext::new(&bn).unwrap();
+        assert!(ctx.get_regulation_variable(b, c).is_some());
+        assert!(ctx.get_regulation_variable(a, c).is_none());
+        assert_eq!(ctx, ctx.as_canonical_context());
+
+        // Without `b`, `c = a`. With `b`, `c = a & b` or `c = a | b`. Inputs `a` and `b`
+        // can have any value.
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        assert_eq!(12.0, stg.unit_colors().approx_cardinality());
+        let with_b = stg.mk_regulation_colors(b, c);
+        assert_eq!(8.0, with_b.approx_cardinality());
+        assert_eq!(stg.unit_colors(), &stg.mk_regulation_colors(a, c));
+        assert!(stg.mk_regulation_colors(c, a).is_empty());
+
+        // Witness networks have the correct regulatory graph.
+        let witness = stg.pick_witness(&with_b);
+        assert!(witness.as_graph().find_regulation(b, c).is_some());
+        let witness = stg.pick_witness(&stg.unit_colors().minus(&with_b));
+        assert!(witness.as_graph().find_regulation(b, c).is_none());
+        assert!(witness.as_graph().find_regulation(a, c).is_some());
+
+        // A regulation that cannot exist is not an error.
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> c
+            b ?-> c
+            $c: a
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        assert!(stg.mk_regulation_colors(b, c).is_empty());
+        // Only the values of inputs `a` and `b` remain.
+        assert_eq!(4.0, stg.unit_colors().approx_cardinality());
+    }
 }
@@ -0,0 +1,346 @@
+use crate::_impl_regulatory_graph::_impl_graph_export::escape_xml;
+use crate::biodivine_std::bitvector::{ArrayBitVector, BitVector};
+use crate::biodivine_std::traits::Set;
+use crate::symbolic_async_graph::{
+    GraphColoredVertices, GraphColors, GraphVertices, StateGraphExport, SymbolicAsyncGraph,
+};
+use crate::VariableId;
+use std::collections::HashMap;
+
+/// **(internal)** An explicit representation of the exported state-transition graph.
+struct ExplicitStateGraph {
+    states: Vec<ArrayBitVector>,
+    highlighted: Vec<bool>,
+    // Each edge is a `(source, target, variable)` triple (source/target are indices
+    // into `states`).
+    edges: Vec<(usize, usize, VariableId)>,
+}
+
+impl SymbolicAsyncGraph {
+    /// Create a configurable [StateGraphExport] of the state-transition graph induced by
+    /// the given `states`.
+    ///
+    /// The `states` set must contain exactly one color (you can use e.g.
+    /// [GraphColoredVertices::intersect_colors] together with [GraphColors::pick_singleton]
+    /// to obtain such a set). Only transitions between the exported `states` are included.
+    pub fn export_state_graph(&self, states: &GraphColoredVertices) -> StateGraphExport<'_> {
+        StateGraphExport {
+            graph: self,
+            states: states.clone(),
+            highlighted: None,
+            highlight_attractors: false,
+            size_limit: 1024,
+        }
+    }
+}
+
+impl<'a> StateGraphExport<'a> {
+    /// Set the maximal number of states that can be exported (1024 by default). If the
+    /// exported set is larger, the export fails with an error.
+    pub fn with_size_limit(mut self, size_limit: usize) -> Self {
+        self.size_limit = size_limit;
+        self
+    }
+
+    /// Highlight the given set of `states` in the output.
+    pub fn with_highlighted_states(mut self, states: &GraphVertices) -> Self {
+        self.highlighted = Some(states.clone());
+        self
+    }
+
+    /// Highlight all exported states that belong to an attractor (of the whole
+    /// state-transition graph, not only the exported subgraph).
+    pub fn with_attractors(mut self) -> Self {
+        self.highlight_attractors = true;
+        self
+    }
+
+    /// Produce the `.dot` representation of the state-transition graph.
+    ///
+    /// States are labelled by their values (in the order of network variables), edges are
+    /// labelled by the name of the updated variable, and highlighted states are drawn with
+    /// a filled background.
+    pub fn to_dot(&self) -> Result<String, String> {
+        let explicit = self.build()?;
+        let mut result = "digraph G {\n".to_string();
+        for (i, state) in explicit.states.iter().enumerate() {
+            let style = if explicit.highlighted[i] {
+                ", style=filled, fillcolor=\"#fdb462\""
+            } else {
+                ""
+            };
+            result.push_str(
+                format!(
+                    "s{} [shape=box, label=\"{}\"{}];\n",
+                    i,
+                    state_to_string(state),
+                    style
+                )
+                .as_str(),
+            );
+        }
+        for (source, target, var) in &explicit.edges {
+            let name = self.graph.get_variable_name(*var);
+            result.push_str(format!("s{} -> s{} [label=\"{}\"];\n", source, target, name).as_str());
+        }
+        result.push_str("}\n");
+        Ok(result)
+    }
+
+    /// Produce the `GraphML` representation of the state-transition graph.
+    ///
+    /// Nodes have a `state` attribute (values in the order of network variables), a boolean
+    /// `highlighted` attribute and one boolean attribute for each network variable. Edges
+    /// have a `variable` attribute with the name of the updated variable.
+    pub fn to_graphml(&self) -> Result<String, String> {
+        let explicit = self.build()?;
+        let mut result = String::new();
+        result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        result.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        result.push_str(
+            "  <key id=\"state\" for=\"node\" attr.name=\"state\" attr.type=\"string\"/>\n",
+        );
+        result.push_str(
+            "  <key id=\"highlighted\" for=\"node\" attr.name=\"highlighted\" attr.type=\"boolean\"/>\n",
+        );
+        for var in self.graph.variables() {
+            let name = self.graph.get_variable_name(var);
+            result.push_str(
+                format!(
+                    "  <key id=\"var_{}\" for=\"node\" attr.name=\"{}\" attr.type=\"boolean\"/>\n",
+                    var.to_index(),
+                    escape_xml(&name)
+                )
+                .as_str(),
+            );
+        }
+        result.push_str(
+            "  <key id=\"variable\" for=\"edge\" attr.name=\"variable\" attr.type=\"string\"/>\n",
+        );
+        result.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+        for (i, state) in explicit.states.iter().enumerate() {
+            result.push_str(format!("    <node id=\"s{}\">\n", i).as_str());
+            write_data(&mut result, "state", &state_to_string(state));
+            write_data(
+                &mut result,
+                "highlighted",
+                &explicit.highlighted[i].to_string(),
+            );
+            for var in self.graph.variables() {
+                let key = format!("var_{}", var.to_index());
+                write_data(&mut result, &key, &state.get(var.to_index()).to_string());
+            }
+            result.push_str("    </node>\n");
+        }
+        for (source, target, var) in &explicit.edges {
+            result.push_str(
+                format!("    <edge source=\"s{}\" target=\"s{}\">\n", source, target).as_str(),
+            );
+            write_data(&mut result, "variable", &self.graph.get_variable_name(*var));
+            result.push_str("    </edge>\n");
+        }
+        result.push_str("  </graph>\n");
+        result.push_str("</graphml>\n");
+        Ok(result)
+    }
+
+    /// **(internal)** Check the export conditions and enumerate the explicit graph.
+    fn build(&self) -> Result<ExplicitStateGraph, String> {
+        let color = self.states.colors();
+        if color.is_empty() {
+            return Err("Cannot export an empty set of states.".to_string());
+        }
+        let color_count = color.exact_cardinality();
+        if color_count != 1u32.into() {
+            return Err(format!(
+                "The exported states must have exactly one color, but {} colors were found.",
+                color_count
+            ));
+        }
+        let vertices = self.states.vertices();
+        let state_count = vertices.exact_cardinality();
+        if state_count > self.size_limit.into() {
+            return Err(format!(
+                "Cannot export {} states (the limit is {}).",
+                state_count, self.size_limit
+            ));
+        }
+
+        let states = vertices.iter().collect::<Vec<_>>();
+        let index = states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state.values(), i))
+            .collect::<HashMap<_, _>>();
+
+        let attractors = if self.highlight_attractors {
+            Some(self.attractor_states(&states, &color))
+        } else {
+            None
+        };
+        let highlighted = states
+            .iter()
+            .map(|state| {
+                let in_attractor = attractors
+                    .as_ref()
+                    .map(|it| !it.intersect(&self.graph.vertex(state)).is_empty())
+                    .unwrap_or(false);
+                let in_highlighted = self
+                    .highlighted
+                    .as_ref()
+                    .map(|it| {
+                        !it.intersect(&self.graph.vertex(state).vertices())
+                            .is_empty()
+                    })
+                    .unwrap_or(false);
+                in_attractor || in_highlighted
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+        for (source, state) in states.iter().enumerate() {
+            let vertex = self.graph.vertex(state).intersect_colors(&color);
+            for var in self.graph.variables() {
+                if self.graph.var_post(var, &vertex).is_empty() {
+                    continue;
+                }
+                let mut successor = state.clone();
+                successor.flip(var.to_index());
+                if let Some(target) = index.get(&successor.values()) {
+                    edges.push((source, *target, var));
+                }
+            }
+        }
+
+        Ok(ExplicitStateGraph {
+            states,
+            highlighted,
+            edges,
+        })
+    }
+
+    /// **(internal)** Compute the set of the given `states` that belong to some attractor.
+    ///
+    /// A state is in an attractor if it can reach back every state that it can reach.
+    /// Once a state is resolved, all its forward (attractor) or backward (non-attractor)
+    /// reachable states are resolved as well.
+    fn attractor_states(
+        &self,
+        states: &[ArrayBitVector],
+        color: &GraphColors,
+    ) -> GraphColoredVertices {
+        let mut attractor = self.graph.mk_empty_colored_vertices();
+        let mut not_attractor = self.graph.mk_empty_colored_vertices();
+        for state in states {
+            let vertex = self.graph.vertex(state).intersect_colors(color);
+            if vertex.is_subset(&attractor) || vertex.is_subset(&not_attractor) {
+                continue;
+            }
+            let fwd = self.graph.reach_forward(&vertex);
+            let bwd = self.graph.reach_backward(&vertex);
+            if fwd.is_subset(&bwd) {
+                attractor = attractor.union(&fwd);
+            } else {
+                not_attractor = not_attractor.union(&bwd);
+            }
+        }
+        attractor
+    }
+}
+
+/// **(internal)** Write the values of a state as a string of zeros and ones.
+fn state_to_string(state: &ArrayBitVector) -> String {
+    state
+        .values()
+        .into_iter()
+        .map(|it| if it { '1' } else { '0' })
+        .collect()
+}
+
+/// **(internal)** Write a single GraphML `data` element (the `value` is escaped).
+fn write_data(output: &mut String, key: &str, value: &str) {
+    output.push_str(format!("      <data key=\"{}\">{}</data>\n", key, escape_xml(value)).as_str());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::symbolic_async_graph::_impl_state_graph_export::write_data;
+    use crate::symbolic_async_graph::SymbolicAsyncGraph;
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_state_graph_export() {
+        // A toggle switch with an oscillating third component.
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -| b
+            b -| a
+            c -| c
+            $a: !b
+            $b: !a
+            $c: !c
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        let dot = stg
+            .export_state_graph(stg.unit_colored_vertices())
+            .with_attractors()
+            .to_dot()
+            .unwrap();
+        assert_eq!(dot.matches("[shape=box").count(), 8);
+        // Each state can update `c`, and the four states with `a = b` can update `a` and `b`.
+        assert_eq!(dot.matches("->").count(), 8 + 4 * 2);
+        assert_eq!(dot.matches("label=\"c\"").count(), 8);
+        // Attractors are `a=1, b=0` and `a=0, b=1` with oscillating `c`.
+        assert_eq!(dot.matches("style=filled").count(), 4);
+        assert!(dot.contains("[shape=box, label=\"100\", style=filled"));
+        assert!(dot.contains("[shape=box, label=\"110\"];"));
+
+        // Only transitions within the exported set are included.
+        let subspace = stg.fix_network_variable(bn.as_graph().find_variable("c").unwrap(), false);
+        let graphml = stg.export_state_graph(&subspace).to_graphml().unwrap();
+        let document = roxmltree::Document::parse(&graphml).unwrap();
+        let count = |tag: &str| {
+            document
+                .descendants()
+                .filter(|it| it.has_tag_name(tag))
+                .count()
+        };
+        assert_eq!(count("node"), 4);
+        assert_eq!(count("edge"), 4);
+
+        assert!(stg
+            .export_state_graph(stg.unit_colored_vertices())
+            .with_size_limit(4)
+            .to_dot()
+            .is_err());
+        assert!(stg
+            .export_state_graph(stg.empty_colored_vertices())
+            .to_dot()
+            .is_err());
+    }
+
+    #[test]
+    fn test_state_graph_export_requires_single_color() {
+        let bn = BooleanNetwork::try_from("a -> b\n$b: f(a)").unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        assert!(stg
+            .export_state_graph(stg.unit_colored_vertices())
+            .to_dot()
+            .is_err());
+        let color = stg.unit_colors().pick_singleton();
+        let states = stg.unit_colored_vertices().intersect_colors(&color);
+        let dot = stg.export_state_graph(&states).to_dot().unwrap();
+        assert_eq!(dot.matches("[shape=box").count(), 4);
+    }
+
+    #[test]
+    fn test_graphml_data_is_escaped() {
+        let mut data = String::new();
+        write_data(&mut data, "variable", "<a & \"b\">");
+        let document = roxmltree::Document::parse(&data).unwrap();
+        assert_eq!(document.root_element().text(), Some("<a & \"b\">"));
+    }
+}
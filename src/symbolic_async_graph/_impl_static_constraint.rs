@@ -0,0 +1,187 @@
+use crate::_impl_function_values::function_row_to_string;
+use crate::symbolic_async_graph::_impl_function_class_constraint::mk_function_class_constraints;
+use crate::symbolic_async_graph::_impl_function_value_constraint::mk_function_value_constraints;
+use crate::symbolic_async_graph::_impl_regulation_constraint::mk_regulation_static_constraints;
+use crate::symbolic_async_graph::{StaticConstraint, SymbolicContext};
+use crate::BooleanNetwork;
+use biodivine_lib_bdd::Bdd;
+
+impl StaticConstraint {
+    /// Create a human-readable description of this constraint, using the names of variables
+    /// and parameters of the given `network`.
+    pub fn to_string(&self, network: &BooleanNetwork) -> String {
+        match self {
+            StaticConstraint::Observability(regulation) => {
+                format!(
+                    "{} (observability)",
+                    regulation.to_string(network.as_graph())
+                )
+            }
+            StaticConstraint::Monotonicity(regulation) => {
+                format!(
+                    "{} (monotonicity)",
+                    regulation.to_string(network.as_graph())
+                )
+            }
+            StaticConstraint::ParameterClass(parameter, class) => {
+                format!("@{}: {}", network[*parameter].get_name(), class)
+            }
+            StaticConstraint::ImplicitClass(variable, class) => {
+                format!("@{}: {}", network[*variable], class)
+            }
+            StaticConstraint::ParameterValue(parameter, args, value) => {
+                let row = function_row_to_string(network[*parameter].get_name(), args);
+                format!("@{} = {}", row, u8::from(*value))
+            }
+            StaticConstraint::ImplicitValue(variable, args, value) => {
+                let row = function_row_to_string(network.get_variable_name(*variable), args);
+                format!("@{} = {}", row, u8::from(*value))
+            }
+        }
+    }
+}
+
+impl BooleanNetwork {
+    /// Explain why this network admits no valid colors (i.e. why the unit color set of its
+    /// `SymbolicAsyncGraph` would be empty).
+    ///
+    /// Returns `None` when the static constraints of the network are satisfiable. Otherwise,
+    /// returns a minimal conflicting subset of constraints: the constraints in the subset
+    /// cannot be satisfied together, but removing any one of them resolves the conflict.
+    /// Note that the network can contain other (disjoint) conflicts as well.
+    ///
+    /// The method fails if the network cannot be symbolically encoded, or if it contains
+    /// an invalid function class or function value.
+    pub fn find_conflicting_constraints(&self) -> Result<Option<Vec<StaticConstraint>>, String> {
+        let context = SymbolicContext::new(self)?;
+        let constraints = mk_static_constraints(self, &context)?;
+
+        // Find the first constraint which makes the conjunction unsatisfiable. This one
+        // is necessary for the conflict, and only the constraints before it are relevant.
+        let mut acc = context.mk_constant(true);
+        let Some(last) = constraints.iter().position(|(_, bdd)| {
+            acc = acc.and(bdd);
+            acc.is_false()
+        }) else {
+            return Ok(None);
+        };
+
+        // Repeat the same process with the known necessary constraints as the initial
+        // value until these alone are unsatisfiable. Every iteration adds one constraint
+        // which is necessary for the conflict among the remaining candidates.
+        let mut core = vec![last];
+        let mut candidates = last;
+        loop {
+            let mut acc = context.mk_constant(true);
+            for i in &core {
+                acc = acc.and(&constraints[*i].1);
+            }
+            if acc.is_false() {
+                break;
+            }
+            let next = (0..candidates)
+                .find(|i| {
+                    acc = acc.and(&constraints[*i].1);
+                    acc.is_false()
+                })
+                .unwrap_or_else(|| unreachable!("The candidates must be unsatisfiable."));
+            core.push(next);
+            candidates = next;
+        }
+
+        core.sort();
+        Ok(Some(
+            core.into_iter().map(|i| constraints[i].0.clone()).collect(),
+        ))
+    }
+}
+
+/// **(internal)** Compute the symbolic representation of all static constraints of the
+/// `network`. Constraints which are trivially satisfied are omitted.
+fn mk_static_constraints(
+    network: &BooleanNetwork,
+    context: &SymbolicContext,
+) -> Result<Vec<(StaticConstraint, Bdd)>, String> {
+    let mut result = mk_regulation_static_constraints(network, context);
+    result.extend(mk_function_class_constraints(network, context)?);
+    result.extend(mk_function_value_constraints(network, context)?);
+    result.retain(|(_, bdd)| !bdd.is_true());
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::symbolic_async_graph::{StaticConstraint, SymbolicAsyncGraph};
+    use crate::BooleanNetwork;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_find_conflicting_constraints() {
+        let bn = BooleanNetwork::try_from(
+            r"
+            a -> c
+            b -> c
+            c -> d
+            $c: f(a, b)
+            @f(1, 1) = 0
+        ",
+        )
+        .unwrap();
+        assert!(SymbolicAsyncGraph::new(&bn).is_err());
+
+        let a = bn.as_graph().find_variable("a").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let f = bn.find_parameter("f").unwrap();
+        let a_c = bn.as_graph().find_regulation(a, c).unwrap().clone();
+        let b_c = bn.as_graph().find_regulation(b, c).unwrap().clone();
+
+        let conflict = bn.find_conflicting_constraints().unwrap().unwrap();
+        assert_eq!(
+            conflict,
+            vec![
+                StaticConstraint::Observability(a_c.clone()),
+                StaticConstraint::Monotonicity(a_c),
+                StaticConstraint::Monotonicity(b_c),
+                StaticConstraint::ParameterValue(f, vec![true, true], false),
+            ]
+        );
+        let descriptions = conflict
+            .iter()
+            .map(|it| it.to_string(&bn))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            descriptions,
+            vec![
+                "a -> c (observability)",
+                "a -> c (monotonicity)",
+                "b -> c (monotonicity)",
+                "@f(1, 1) = 0",
+            ]
+        );
+
+        // Removing the known value resolves the conflict.
+        let mut bn = bn;
+        bn.clear_function_values();
+        assert_eq!(None, bn.find_conflicting_constraints().unwrap());
+
+        // A single unsatisfiable regulation is a conflict on its own.
+        let bn = BooleanNetwork::try_from("a -> b\n b -| b\n $b: a & b").unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let b_b = bn.as_graph().find_regulation(b, b).unwrap().clone();
+        assert_eq!(
+            Some(vec![StaticConstraint::Monotonicity(b_b)]),
+            bn.find_conflicting_constraints().unwrap()
+        );
+
+        // Unsupported classes are reported with the name of the function.
+        let mut model = String::new();
+        for regulator in ["a", "b", "c", "d", "e", "f"] {
+            model.push_str(format!("{} -?? x\n", regulator).as_str());
+        }
+        model.push_str("@x: threshold\n");
+        let bn = BooleanNetwork::try_from(model.as_str()).unwrap();
+        let error = bn.find_conflicting_constraints().unwrap_err();
+        assert!(error.starts_with("Invalid class of `x`"));
+    }
+}
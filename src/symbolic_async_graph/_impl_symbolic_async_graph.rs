@@ -3,17 +3,18 @@ use crate::biodivine_std::traits::Set;
 use crate::symbolic_async_graph::_impl_regulation_constraint::apply_regulation_constraints;
 use crate::symbolic_async_graph::_impl_symbolic_async_graph_operators::a_and_b_and_c;
 use crate::symbolic_async_graph::bdd_set::BddSet;
+use crate::symbolic_async_graph::projected_iteration::{UniqueFnUpdateIterator, WitnessIterator};
 use crate::symbolic_async_graph::{
     GraphColoredVertices, GraphColors, GraphVertices, RegulationConstraint, SymbolicAsyncGraph,
     SymbolicContext,
 };
 use crate::trap_spaces::SymbolicSpaceContext;
 use crate::{
-    BooleanNetwork, FnUpdate, Monotonicity, Regulation, RegulatoryGraph, VariableId,
-    VariableIdIterator,
+    BooleanNetwork, FnUpdate, Monotonicity, ParameterInstantiation, Regulation, RegulatoryGraph,
+    VariableId, VariableIdIterator,
 };
 use crate::{ExtendedBoolean, Space};
-use biodivine_lib_bdd::{bdd, Bdd, BddVariable};
+use biodivine_lib_bdd::{bdd, Bdd, BddValuation, BddVariable};
 use std::collections::HashMap;
 
 impl SymbolicAsyncGraph {
@@ -211,23 +212,86 @@ impl SymbolicAsyncGraph {
         )
     }
 
+    /// Make an explicit [ParameterInstantiation] for one color in the given set.
+    ///
+    /// Compared to [SymbolicAsyncGraph::pick_witness], the result can be used to evaluate
+    /// the original (parametrised) network explicitly.
+    pub fn pick_instantiation(&self, colors: &GraphColors) -> ParameterInstantiation {
+        if colors.is_empty() {
+            panic!("Cannot create instantiation for empty color set.");
+        }
+        let witness_valuation = colors.bdd.sat_witness().unwrap();
+        self.symbolic_context
+            .mk_parameter_instantiation(&witness_valuation)
+    }
+
     /// Make a witness network for one color in the given set.
     pub fn pick_witness(&self, colors: &GraphColors) -> BooleanNetwork {
         if colors.is_empty() {
             panic!("Cannot create witness for empty color set.");
         }
         let witness_valuation = colors.bdd.sat_witness().unwrap();
+        let fn_update = self
+            .fn_update
+            .iter()
+            .map(|fn_bdd| {
+                self.symbolic_context
+                    .mk_instantiated_fn_update(&witness_valuation, fn_bdd)
+            })
+            .collect();
+        self.mk_witness_network(fn_update)
+    }
 
-        let mut regulators: Vec<Vec<VariableId>> = Vec::new();
-        let mut fn_update: Vec<FnUpdate> = Vec::new();
-        for fn_bdd in &self.fn_update {
-            let dnf = self
-                .symbolic_context
-                .mk_instantiated_fn_update(&witness_valuation, fn_bdd);
-            let regs = dnf.collect_arguments();
-            fn_update.push(dnf);
-            regulators.push(regs);
-        }
+    /// Lazily iterate over the witness networks of all colors in the given set.
+    ///
+    /// Colors which instantiate to the same (semantically equivalent) update functions
+    /// are only visited once. Use `Iterator::take` to enumerate up to a given number of
+    /// networks. To only enumerate the update functions that actually differ between
+    /// the colors, see [SymbolicAsyncGraph::varying_functions] and
+    /// [FnUpdateProjection::iter_unique].
+    pub fn witnesses(&self, colors: &GraphColors) -> WitnessIterator<'_> {
+        let functions = self.symbolic_context.network_variables().collect();
+        WitnessIterator::new(UniqueFnUpdateIterator::new(functions, self, &colors.bdd))
+    }
+
+    /// Compute the network variables whose update functions are not the same
+    /// (semantically equivalent) in all colors of the given set.
+    pub fn varying_functions(&self, colors: &GraphColors) -> Vec<VariableId> {
+        let Some(witness_valuation) = colors.bdd.sat_witness() else {
+            return Vec::new();
+        };
+        self.symbolic_context
+            .network_variables()
+            .filter(|var| {
+                let same = self.mk_same_fn_update(*var, &witness_valuation);
+                !colors.bdd.imp(&same).is_true()
+            })
+            .collect()
+    }
+
+    /// **(internal)** Compute the parameter valuations in which the update function of `var`
+    /// is equivalent to its instantiation given by the `valuation`.
+    pub(crate) fn mk_same_fn_update(&self, var: VariableId, valuation: &BddValuation) -> Bdd {
+        let parameters = self
+            .symbolic_context
+            .parameter_variables()
+            .iter()
+            .map(|it| (*it, valuation[*it]))
+            .collect::<Vec<_>>();
+        let symbolic_function = &self.fn_update[var.to_index()];
+        let instantiated = symbolic_function.restrict(&parameters);
+        symbolic_function
+            .iff(&instantiated)
+            .for_all(self.symbolic_context.state_variables())
+    }
+
+    /// **(internal)** Build a network with the given instantiated update functions, such that
+    /// its regulatory graph is inferred from these functions.
+    pub(crate) fn mk_witness_network(&self, fn_update: Vec<FnUpdate>) -> BooleanNetwork {
+        let regulators = fn_update
+            .iter()
+            .map(|it| it.collect_arguments())
+            .collect::<Vec<_>>();
 
         let variables = self
             .symbolic_context
@@ -244,6 +308,7 @@ impl SymbolicAsyncGraph {
                     target,
                     observable: false,
                     monotonicity: None,
+                    uncertain: false,
                 })
                 .unwrap_or_else(|_| {
                     unreachable!("Unconstrained regulation should be always allowed here.");
@@ -264,6 +329,27 @@ impl SymbolicAsyncGraph {
         })
     }
 
+    /// Compute the colors in which the regulation from `regulator` to `target` exists.
+    ///
+    /// For an uncertain regulation, these are the colors in which its symbolic existence
+    /// variable is true. Otherwise, the result contains all colors if the update function
+    /// of `target` can depend on `regulator`, and no colors if it cannot.
+    pub fn mk_regulation_colors(&self, regulator: VariableId, target: VariableId) -> GraphColors {
+        let ctx = &self.symbolic_context;
+        let unit = self.unit_colors().as_bdd();
+        let bdd = if let Some(exists) = ctx.get_regulation_variable(regulator, target) {
+            unit.var_select(exists, true)
+        } else {
+            let support = self.fn_update[target.to_index()].support_set();
+            if support.contains(&ctx.get_state_variable(regulator)) {
+                unit.clone()
+            } else {
+                ctx.mk_constant(false)
+            }
+        };
+        GraphColors::new(bdd, ctx)
+    }
+
     /// Reference to an empty color set.
     pub fn empty_colors(&self) -> &GraphColors {
         &self.color_space.0
@@ -925,10 +1011,16 @@ impl SymbolicAsyncGraph {
                     let obs = RegulationConstraint::mk_observability(ctx, fn_bdd, regulator);
                     let act = RegulationConstraint::mk_activation(ctx, fn_bdd, regulator);
                     let inh = RegulationConstraint::mk_inhibition(ctx, fn_bdd, regulator);
-                    let observable = self.unit_bdd.imp(&obs).is_true();
-                    let monotonicity = if self.unit_bdd.imp(&act).is_true() {
+                    // Constraints of uncertain regulations only apply when they exist.
+                    let exists = ctx.get_regulation_variable(regulator, target);
+                    let unit = match exists {
+                        Some(var) => self.unit_bdd.var_restrict(var, true),
+                        None => self.unit_bdd.clone(),
+                    };
+                    let observable = unit.imp(&obs).is_true();
+                    let monotonicity = if unit.imp(&act).is_true() {
                         Some(Monotonicity::Activation)
-                    } else if self.unit_bdd.imp(&inh).is_true() {
+                    } else if unit.imp(&inh).is_true() {
                         Some(Monotonicity::Inhibition)
                     } else {
                         None
@@ -938,6 +1030,7 @@ impl SymbolicAsyncGraph {
                         target,
                         observable,
                         monotonicity,
+                        uncertain: exists.is_some(),
                     })
                     .unwrap();
                 }
@@ -1620,5 +1713,18 @@ mod tests {
         .unwrap();
 
         assert_eq!(expected, bn3);
+
+        // Uncertain regulations remain uncertain.
+        let bn = BooleanNetwork::try_from(
+            r"
+            a ?-> b
+            b -| a
+            $a: !b
+            $b: a
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        assert_eq!(bn, stg.reconstruct_network().unwrap());
     }
 }
@@ -1,7 +1,7 @@
 use crate::symbolic_async_graph::{FunctionTable, SymbolicContext};
 use crate::{
-    BinaryOp, BooleanNetwork, FnUpdate, ParameterId, ParameterIdIterator, VariableId,
-    VariableIdIterator,
+    BinaryOp, BooleanNetwork, FnUpdate, NaryOp, ParameterId, ParameterIdIterator,
+    ParameterInstantiation, VariableId, VariableIdIterator,
 };
 use biodivine_lib_bdd::op_function::{and, and_not};
 use biodivine_lib_bdd::{
@@ -61,6 +61,7 @@ impl SymbolicContext {
             vec![None; network.num_vars()];
         let mut explicit_function_tables: Vec<Option<FunctionTable>> =
             vec![None; network.num_parameters()];
+        let mut regulation_variables: Vec<(VariableId, VariableId, BddVariable)> = Vec::new();
 
         for variable in network.variables() {
             let variable_name = network[variable].get_name();
@@ -93,6 +94,22 @@ impl SymbolicContext {
                 let function_table = FunctionTable::new(&function_name, arity, &mut builder);
                 implicit_function_tables[variable.0] = Some(function_table);
             }
+            // Existence variables of uncertain regulations are placed after the function
+            // of their target.
+            for regulator in network.regulators(variable) {
+                let regulation = network
+                    .as_graph()
+                    .find_regulation(regulator, variable)
+                    .unwrap_or_else(|| unreachable!("Regulation must exist."));
+                if regulation.is_uncertain() {
+                    let name = regulation_variable_name(
+                        network.get_variable_name(regulator),
+                        network.get_variable_name(variable),
+                    );
+                    let bdd_var = builder.make_variable(name.as_str());
+                    regulation_variables.push((regulator, variable, bdd_var));
+                }
+            }
         }
 
         // Create a "flattened" version of extra state variables.
@@ -123,6 +140,9 @@ impl SymbolicContext {
         for table in implicit_function_tables.iter().flatten() {
             parameter_variables.extend_from_slice(&table.rows);
         }
+        for (_, _, bdd_var) in &regulation_variables {
+            parameter_variables.push(*bdd_var);
+        }
 
         Ok(SymbolicContext {
             bdd: builder.build(),
@@ -132,6 +152,7 @@ impl SymbolicContext {
             parameter_variables,
             explicit_function_tables,
             implicit_function_tables,
+            regulation_variables,
         })
     }
 
@@ -223,6 +244,21 @@ impl SymbolicContext {
             table.name = var_name;
             result.explicit_function_tables.push(table);
         }
+        // Uncertain regulations of the eliminated variable are no longer accessible, the
+        // remaining ones have to be re-indexed.
+        let shift = |it: VariableId| {
+            if it.to_index() > index {
+                VariableId::from_index(it.to_index() - 1)
+            } else {
+                it
+            }
+        };
+        result.regulation_variables = self
+            .regulation_variables
+            .iter()
+            .filter(|(regulator, target, _)| *regulator != variable && *target != variable)
+            .map(|(regulator, target, bdd_var)| (shift(*regulator), shift(*target), *bdd_var))
+            .collect();
         result
     }
 
@@ -241,6 +277,10 @@ impl SymbolicContext {
         let mut explicit_function_tables = Vec::new();
         let mut implicit_function_tables: Vec<Option<FunctionTable>> =
             vec![None; self.num_state_variables()];
+        let mut regulation_variables = Vec::new();
+        // Existence variables of regulations which are no longer accessible
+        // (see [SymbolicContext::eliminate_network_variable]).
+        let mut other_parameter_variables = Vec::new();
         // Now, a key problem is that we need to recreate all objects in the correct order, such that we are
         // truly compatible with the original representation. This is a bit complicated, because we don't have that
         // order saved anywhere explicitly. However, we can "reconstruct" it from the current data-structures.
@@ -290,6 +330,20 @@ impl SymbolicContext {
                     }
                 }
             }
+            if self.parameter_variables.contains(&var) {
+                let name = self.bdd.name_of(var);
+                let new_var = builder.make_variable(name.as_str());
+                let regulation = self
+                    .regulation_variables
+                    .iter()
+                    .find(|(_, _, it)| *it == var);
+                if let Some((regulator, target, _)) = regulation {
+                    regulation_variables.push((*regulator, *target, new_var));
+                } else {
+                    other_parameter_variables.push(new_var);
+                }
+                continue 'var_loop;
+            }
             unreachable!("There shouldn't be any other symbolic variables.");
         }
 
@@ -300,6 +354,10 @@ impl SymbolicContext {
         for table in implicit_function_tables.iter().flatten() {
             parameter_variables.extend_from_slice(&table.rows);
         }
+        for (_, _, bdd_var) in &regulation_variables {
+            parameter_variables.push(*bdd_var);
+        }
+        parameter_variables.append(&mut other_parameter_variables);
         // People will probably expect these to be sorted.
         parameter_variables.sort();
 
@@ -311,6 +369,7 @@ impl SymbolicContext {
             parameter_variables,
             explicit_function_tables,
             implicit_function_tables,
+            regulation_variables,
         }
     }
 
@@ -395,6 +454,25 @@ impl SymbolicContext {
         self.implicit_function_tables[variable.0].as_ref()
     }
 
+    /// Get the symbolic variable which is true when the uncertain regulation from `regulator`
+    /// to `target` exists. Returns `None` if the regulation is not uncertain (or does not exist).
+    pub fn get_regulation_variable(
+        &self,
+        regulator: VariableId,
+        target: VariableId,
+    ) -> Option<BddVariable> {
+        self.regulation_variables
+            .iter()
+            .find(|(r, t, _)| *r == regulator && *t == target)
+            .map(|(_, _, bdd_var)| *bdd_var)
+    }
+
+    /// The list of all uncertain regulations (as `(regulator, target)` pairs) together
+    /// with their symbolic variables.
+    pub fn regulation_variables(&self) -> &Vec<(VariableId, VariableId, BddVariable)> {
+        &self.regulation_variables
+    }
+
     /// Getter for the entire function table of an explicit parameter.
     pub fn get_explicit_function_table(&self, parameter: ParameterId) -> &FunctionTable {
         &self.explicit_function_tables[parameter.0]
@@ -465,6 +543,20 @@ impl SymbolicContext {
                     BinaryOp::Iff => l.iff(&r),
                 }
             }
+            FnUpdate::Nary(op, args) => {
+                let args = args.iter().map(|it| self.mk_fn_update_true(it));
+                match op {
+                    NaryOp::And => args.fold(self.mk_constant(true), |acc, it| acc.and(&it)),
+                    NaryOp::Or => args.fold(self.mk_constant(false), |acc, it| acc.or(&it)),
+                }
+            }
+            FnUpdate::Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(w, it)| (*w, self.mk_fn_update_true(it)))
+                    .collect();
+                self.mk_threshold(args, *threshold)
+            }
         }
     }
 
@@ -560,6 +652,22 @@ impl SymbolicContext {
                     BinaryOp::Iff => l.iff(&r),
                 }
             }
+            FnUpdate::Nary(op, args) => {
+                let args = args
+                    .iter()
+                    .map(|it| self.instantiate_fn_update(valuation, it));
+                match op {
+                    NaryOp::And => args.fold(self.mk_constant(true), |acc, it| acc.and(&it)),
+                    NaryOp::Or => args.fold(self.mk_constant(false), |acc, it| acc.or(&it)),
+                }
+            }
+            FnUpdate::Threshold(args, threshold) => {
+                let args = args
+                    .iter()
+                    .map(|(w, it)| (*w, self.instantiate_fn_update(valuation, it)))
+                    .collect();
+                self.mk_threshold(args, *threshold)
+            }
         }
     }
 
@@ -577,6 +685,34 @@ impl SymbolicContext {
         FnUpdate::build_from_bdd(self, &restricted)
     }
 
+    /// Build an explicit [ParameterInstantiation] of all uninterpreted functions
+    /// (explicit and implicit) using the parameter values in the given `valuation`.
+    pub fn mk_parameter_instantiation(&self, valuation: &BddValuation) -> ParameterInstantiation {
+        let to_table = |table: &FunctionTable| {
+            let mut result = vec![false; 1usize << table.arity];
+            for (row, var) in table {
+                result[ParameterInstantiation::row_index(&row)] = valuation[var];
+            }
+            result
+        };
+        let mut result = ParameterInstantiation::new();
+        for parameter in self.network_parameters() {
+            let table = self.get_explicit_function_table(parameter);
+            result.set_parameter_table(parameter, to_table(table));
+        }
+        for variable in self.network_implicit_parameters() {
+            let table = self.get_implicit_function_table(variable).unwrap();
+            result.set_implicit_table(variable, to_table(table));
+        }
+        result
+    }
+
+    /// **(internal)** Create a `Bdd` which is true when the sum of weights of the `args` that
+    /// are true is at least `threshold`.
+    fn mk_threshold(&self, args: Vec<(i32, Bdd)>, threshold: i32) -> Bdd {
+        mk_threshold_bdd(&self.bdd, args, threshold)
+    }
+
     /// **(internal)** Utility method for converting `FnUpdate` arguments to `Bdd` arguments.
     fn prepare_args(&self, args: &[FnUpdate]) -> Vec<Bdd> {
         return args.iter().map(|v| self.mk_fn_update_true(v)).collect();
@@ -609,6 +745,39 @@ impl SymbolicContext {
     }
 }
 
+/// **(internal)** Create a `Bdd` (over the given `variables`) which is true when the sum of
+/// weights of the `args` that are true is at least `threshold`.
+pub(crate) fn mk_threshold_bdd(
+    variables: &BddVariableSet,
+    args: Vec<(i32, Bdd)>,
+    threshold: i32,
+) -> Bdd {
+    // For every achievable partial sum, the condition under which it is achieved.
+    let mut sums: HashMap<i64, Bdd> = HashMap::new();
+    sums.insert(0, variables.mk_true());
+    for (weight, arg) in args {
+        let mut next: HashMap<i64, Bdd> = HashMap::new();
+        for (sum, condition) in sums {
+            let with_arg = (sum + i64::from(weight), condition.and(&arg));
+            let without_arg = (sum, condition.and_not(&arg));
+            for (sum, condition) in [with_arg, without_arg] {
+                if condition.is_false() {
+                    continue;
+                }
+                let condition = match next.remove(&sum) {
+                    Some(existing) => existing.or(&condition),
+                    None => condition,
+                };
+                next.insert(sum, condition);
+            }
+        }
+        sums = next;
+    }
+    sums.into_iter()
+        .filter(|(sum, _)| *sum >= i64::from(threshold))
+        .fold(variables.mk_false(), |acc, (_, it)| acc.or(&it))
+}
+
 /// **(internal)** Compute the number of rows necessary to represent a function with given arity.
 fn arity_to_row_count(arity: u32) -> u32 {
     1u32.checked_shl(arity).unwrap_or(u32::MAX)
@@ -636,7 +805,18 @@ fn network_symbolic_size(network: &BooleanNetwork, extra: &HashMap<VariableId, u
             size = size.saturating_add(arity_to_row_count(arity))
         }
     }
-    size
+    let uncertain = network
+        .as_graph()
+        .regulations()
+        .filter(|it| it.is_uncertain())
+        .count();
+    size.saturating_add(u32::try_from(uncertain).unwrap_or(u32::MAX))
+}
+
+/// **(internal)** The name of the symbolic variable which is true when the uncertain
+/// regulation from `regulator` to `target` exists.
+fn regulation_variable_name(regulator: &str, target: &str) -> String {
+    format!("exists[{},{}]", regulator, target)
 }
 
 impl Debug for SymbolicContext {
@@ -679,6 +859,10 @@ impl PartialEq for SymbolicContext {
             return false;
         }
 
+        if self.regulation_variables != other.regulation_variables {
+            return false;
+        }
+
         true
     }
 }
@@ -29,7 +29,7 @@ pub trait BddSet {
     /// Compute the exact cardinality of this symbolic set.
     fn exact_cardinality(&self) -> BigInt {
         let unused_variables = self.as_bdd().num_vars() - self.active_variables();
-        self.as_bdd().exact_cardinality().shr(unused_variables)
+        self.as_bdd().exact_cardinality().shr(unused_variables).into()
     }
 
     /// Compute an "approximate" cardinality of this symbolic set.
@@ -29,22 +29,34 @@
 //!
 
 use crate::symbolic_async_graph::projected_iteration::{OwnedRawSymbolicIterator, RawProjection};
-use crate::BooleanNetwork;
+use crate::{BooleanNetwork, FunctionClass, Monotonicity, ParameterId, Regulation, VariableId};
 use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet, ValuationsOfClauseIterator};
 use std::iter::Enumerate;
 
 /// **(internal)** Implementing conversion between `FnUpdate` and `BooleanExpression`.
 mod _impl_fn_update_from_boolean_expression;
+/// **(internal)** Symbolic encoding of function class constraints.
+pub(crate) mod _impl_function_class_constraint;
+/// **(internal)** Structural properties (canalization, sensitivity, ...) of update functions.
+mod _impl_function_metrics;
 /// **(internal)** Implementation for `FunctionTable` and `FunctionTableIterator`.
 mod _impl_function_table;
+/// **(internal)** Symbolic encoding of known function values.
+pub(crate) mod _impl_function_value_constraint;
 /// **(internal)** Implement set operations for `GraphColoredVertices`.
 mod _impl_graph_colored_vertices;
 /// **(internal)** Implement set operations for `GraphColors`.
 mod _impl_graph_colors;
 /// **(internal)** Implement set operations for `GraphVertices`.
 mod _impl_graph_vertices;
+/// **(internal)** Computation of local (state-dependent) interaction graphs.
+mod _impl_local_interaction_graph;
 /// **(internal)** Utility methods for validation of static constraints on network regulations.
-mod _impl_regulation_constraint;
+pub(crate) mod _impl_regulation_constraint;
+/// **(internal)** Explicit `.dot` and `GraphML` export of (small) state-transition graphs.
+mod _impl_state_graph_export;
+/// **(internal)** Diagnosis of conflicting static constraints of a network.
+mod _impl_static_constraint;
 /// **(internal)** Utility methods for `SymbolicAsyncGraph`.
 mod _impl_symbolic_async_graph;
 /// **(internal)** Implementation of symbolic utility algorithms.
@@ -52,7 +64,7 @@ mod _impl_symbolic_async_graph_algorithm;
 /// **(internal)** Implement symbolic graph operators (pre/post/...).
 mod _impl_symbolic_async_graph_operators;
 /// **(internal)** Implementation of the `SymbolicContext`.
-mod _impl_symbolic_context;
+pub(crate) mod _impl_symbolic_context;
 
 /// Implementation of the `RawSymbolicIterator` and other low-level iterators
 /// that are used to iterate through various projections of symbolic sets.
@@ -162,6 +174,9 @@ pub struct SymbolicContext {
     parameter_variables: Vec<BddVariable>,
     explicit_function_tables: Vec<FunctionTable>,
     implicit_function_tables: Vec<Option<FunctionTable>>,
+    // One symbolic variable for each uncertain regulation, which is true when
+    // the regulation exists.
+    regulation_variables: Vec<(VariableId, VariableId, BddVariable)>,
 }
 
 /// Function table maps one the table of an uninterpreted function to corresponding `Bdd` variables.
@@ -190,6 +205,71 @@ pub struct RegulationConstraint {
     _impossible: (), // Ensures `RegulationConstraint` cannot be instantiated.
 }
 
+/// A single static constraint imposed on the uninterpreted functions of a `BooleanNetwork`.
+///
+/// Static constraints are used to explain why a network admits no valid colors (see
+/// [BooleanNetwork::find_conflicting_constraints]). Use [StaticConstraint::to_string] to
+/// obtain a human-readable description of the constraint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StaticConstraint {
+    /// The regulator must have an effect on the update function of the target.
+    Observability(Regulation),
+    /// The regulator must be monotonous in the update function of the target
+    /// (this also covers the absence of uncertain regulations).
+    Monotonicity(Regulation),
+    /// An explicit parameter must belong to the given function class.
+    ParameterClass(ParameterId, FunctionClass),
+    /// The implicit update function of a variable must belong to the given function class.
+    ImplicitClass(VariableId, FunctionClass),
+    /// An explicit parameter has a known value for the given arguments.
+    ParameterValue(ParameterId, Vec<bool>, bool),
+    /// The implicit update function of a variable has a known value for the given arguments.
+    ImplicitValue(VariableId, Vec<bool>, bool),
+}
+
+/// Structural properties of a single (fully instantiated) Boolean update function.
+///
+/// The metrics are computed with respect to a fixed list of `inputs` (typically the
+/// regulators of the updated variable):
+///  - *Unateness*: For every essential input, the function is either positive unate
+///    (`Activation`), negative unate (`Inhibition`), or not unate (`None`).
+///  - *Canalization*: An input `x` is canalizing with value `a` and output `b` if setting
+///    `x = a` fixes the function output to `b`. Constant functions have no canalizing inputs.
+///  - *Canalization depth*: The number of inputs that can be eliminated by repeatedly fixing
+///    all canalizing inputs of the function to their non-canalizing values. A function is
+///    nested canalizing when every essential input can be eliminated this way.
+///  - *Average sensitivity*: The expected number of inputs whose negation changes the output,
+///    assuming all input valuations are equally likely.
+///  - *Bias*: The fraction of input valuations for which the function is true.
+///
+/// Metrics are created using [SymbolicAsyncGraph::function_metrics] (per color) or
+/// [FunctionMetrics::try_from_fn_update] (for functions without parameters).
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionMetrics {
+    inputs: Vec<VariableId>,
+    unateness: Vec<(VariableId, Option<Monotonicity>)>,
+    canalizing: Vec<(VariableId, bool, bool)>,
+    canalization_depth: usize,
+    average_sensitivity: f64,
+    bias: f64,
+}
+
+/// A configurable explicit export of the asynchronous state-transition graph of a
+/// `SymbolicAsyncGraph` (restricted to a set of states of a single color) into
+/// the GraphViz `.dot` or `GraphML` format.
+///
+/// The export is created using [SymbolicAsyncGraph::export_state_graph]. Since the states
+/// are enumerated explicitly, this is only intended for small networks (see
+/// [StateGraphExport::with_size_limit]).
+#[derive(Clone)]
+pub struct StateGraphExport<'a> {
+    graph: &'a SymbolicAsyncGraph,
+    states: GraphColoredVertices,
+    highlighted: Option<GraphVertices>,
+    highlight_attractors: bool,
+    size_limit: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::biodivine_std::traits::Set;
@@ -25,7 +25,7 @@
 //!
 
 use crate::symbolic_async_graph::{SymbolicAsyncGraph, SymbolicContext};
-use crate::{FnUpdate, VariableId};
+use crate::{BooleanNetwork, FnUpdate, VariableId};
 use biodivine_lib_bdd::{
     Bdd, BddPartialValuation, BddSatisfyingValuations, BddValuation, BddVariable,
 };
@@ -261,6 +261,94 @@ impl<'a, 'b> Iterator for FnUpdateProjectionIterator<'a, 'b> {
     }
 }
 
+/// An iterator over the semantically unique instantiations of a subset of update functions.
+///
+/// In every step, the iterator picks one remaining instantiation and then removes all
+/// instantiations which yield equivalent update functions. As such, it is lazy and never
+/// visits the same combination of update functions twice (as opposed to
+/// `FnUpdateProjectionIterator`).
+pub struct UniqueFnUpdateIterator<'a> {
+    context: &'a SymbolicAsyncGraph,
+    retained_functions: Vec<VariableId>,
+    remaining: Bdd,
+}
+
+/// An iterator over the unique witness networks of a color set
+/// (see [SymbolicAsyncGraph::witnesses]).
+pub struct WitnessIterator<'a> {
+    inner_iterator: UniqueFnUpdateIterator<'a>,
+}
+
+impl<'a> FnUpdateProjection<'a> {
+    /// Iterate over the instantiated update functions in this projection, but skip
+    /// instantiations which are semantically equivalent to an already visited one.
+    pub fn iter_unique(&self) -> UniqueFnUpdateIterator<'a> {
+        UniqueFnUpdateIterator::new(
+            self.retained_functions.clone(),
+            self.context,
+            &self.raw_projection.bdd,
+        )
+    }
+}
+
+impl<'a> UniqueFnUpdateIterator<'a> {
+    /// Create an iterator over the unique instantiations of the `retained` update functions
+    /// within the given `bdd` (a set of colors, or any other set of symbolic valuations of
+    /// the `context`).
+    pub fn new(
+        retained: Vec<VariableId>,
+        context: &'a SymbolicAsyncGraph,
+        bdd: &Bdd,
+    ) -> UniqueFnUpdateIterator<'a> {
+        UniqueFnUpdateIterator {
+            context,
+            retained_functions: retained,
+            remaining: bdd.clone(),
+        }
+    }
+}
+
+impl<'a> Iterator for UniqueFnUpdateIterator<'a> {
+    type Item = Vec<(VariableId, FnUpdate)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let valuation = self.remaining.sat_witness()?;
+        // Remove all valuations which instantiate every retained function the same way.
+        let mut same = self.remaining.clone();
+        for var in &self.retained_functions {
+            same = same.and(&self.context.mk_same_fn_update(*var, &valuation));
+        }
+        self.remaining = self.remaining.and_not(&same);
+        Some(instantiate_functions(
+            self.context,
+            &self.retained_functions,
+            &valuation,
+        ))
+    }
+}
+
+impl<'a> WitnessIterator<'a> {
+    /// **(internal)** Create an iterator which builds a witness network for every item of
+    /// the `inner` iterator. The `inner` iterator must retain all update functions of the
+    /// network (use [SymbolicAsyncGraph::witnesses] to obtain a valid iterator).
+    pub(crate) fn new(inner: UniqueFnUpdateIterator<'a>) -> WitnessIterator<'a> {
+        WitnessIterator {
+            inner_iterator: inner,
+        }
+    }
+}
+
+impl<'a> Iterator for WitnessIterator<'a> {
+    type Item = BooleanNetwork;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let functions = self.inner_iterator.next()?;
+        let graph = self.inner_iterator.context;
+        let fn_update = functions.into_iter().map(|(_, it)| it).collect();
+        Some(graph.mk_witness_network(fn_update))
+    }
+}
+
 /// A combination of `StateProjection` and `FnUpdateProjection` which retains some of
 /// the network variables and some of the update functions.
 ///
@@ -387,6 +475,7 @@ mod tests {
     use crate::biodivine_std::traits::Set;
     use crate::symbolic_async_graph::SymbolicAsyncGraph;
     use crate::BooleanNetwork;
+    use std::collections::HashSet;
 
     #[test]
     pub fn test_state_projection() {
@@ -514,6 +603,57 @@ mod tests {
         assert_eq!(3, set.fn_update_projection(&[c], &stg).iter().count());
     }
 
+    #[test]
+    pub fn test_unique_witnesses() {
+        let bn = BooleanNetwork::try_from(
+            "
+        a -?? a
+        a -?? b
+        a -?? c
+        b -?? c
+        $a: a
+        $b: f(a) | g(a)
+        $c: h(a)
+        ",
+        )
+        .unwrap();
+        let b = bn.as_graph().find_variable("b").unwrap();
+        let c = bn.as_graph().find_variable("c").unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+
+        // Many instantiations of `f | g` are equivalent, hence there are only 4 * 4 unique
+        // networks.
+        assert_eq!(64.0, stg.unit_colors().approx_cardinality());
+        let witnesses = stg.witnesses(stg.unit_colors()).collect::<Vec<_>>();
+        assert_eq!(16, witnesses.len());
+        let unique = witnesses
+            .iter()
+            .map(|it| it.to_string())
+            .collect::<HashSet<_>>();
+        assert_eq!(16, unique.len());
+        assert_eq!(3, stg.witnesses(stg.unit_colors()).take(3).count());
+        assert_eq!(0, stg.witnesses(&stg.mk_empty_colors()).count());
+        assert_eq!(vec![b, c], stg.varying_functions(stg.unit_colors()));
+
+        // With an explicit function of `c`, only `b` is varying.
+        let bn = BooleanNetwork::try_from(
+            "
+        a -?? a
+        a -?? b
+        a -?? c
+        $a: a
+        $b: f(a) | g(a)
+        $c: a
+        ",
+        )
+        .unwrap();
+        let stg = SymbolicAsyncGraph::new(&bn).unwrap();
+        assert_eq!(vec![b], stg.varying_functions(stg.unit_colors()));
+        let projection = stg.unit_colors().fn_update_projection(&[b], &stg);
+        assert_eq!(16, projection.iter().count());
+        assert_eq!(4, projection.iter_unique().count());
+    }
+
     #[test]
     pub fn test_mixed_projection() {
         let bn = BooleanNetwork::try_from(